@@ -0,0 +1,39 @@
+//! Times the predecessor search (behind `predecessors` and `sorted`) over random intervals of a few mean lengths: short
+//! intervals find their predecessor in a step or two back, long ones mostly by the binary search that follows.
+//!
+//! Run with `cargo bench --bench final_compatible`, on a commit and on the one before it, to compare two searches.
+
+use std::{hint::black_box, time::{Duration, Instant}};
+use w_inter::{predecessors, sorted, WeightedInterval};
+
+const COUNT: usize = 5_000_000;
+const RUNS:  usize = 3;
+
+fn main() {
+  let mut seed = 1u64;
+  let mut next = |modulus: u64| {
+    seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+    (seed >> 33) % modulus
+  };
+
+  for mean_length in [50u64, 5_000, 500_000] {
+    let mut intervals: Vec<WeightedInterval<u64, u64>> = (0..COUNT)
+      .map(|_| { let start = next(10 * COUNT as u64); (start, start + next(2 * mean_length), next(100)).into() })
+      .collect();
+    intervals.sort_unstable_by_key(|i| i.end);
+
+    let (mut memo, mut solution) = (vec![0u64; COUNT], Vec::with_capacity(COUNT));
+    let search = best_of(|| { black_box(predecessors(black_box(&intervals))); });
+    let solve = best_of(|| {
+      solution.clear();
+      sorted(black_box(&intervals), &mut memo, &mut solution);
+      black_box(&solution);
+    });
+    println!("mean length {mean_length:>7}: predecessors {search:>10.2?}, sorted {solve:>10.2?}");
+  }
+}
+
+/// The quickest of `RUNS` runs.
+fn best_of(mut run: impl FnMut()) -> Duration {
+  (0..RUNS).map(|_| { let started = Instant::now(); run(); started.elapsed() }).min().unwrap_or_default()
+}
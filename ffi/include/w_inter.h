@@ -0,0 +1,54 @@
+/*
+ * C bindings for w_inter, the Weighted INTERval scheduling solver.
+ *
+ * Build with `cargo build --release -p w_inter_ffi`, and link against
+ * `libw_inter_ffi.so` / `libw_inter_ffi.a` (or the platform equivalent).
+ *
+ * Every `w_inter_solve_*` function solves the problem whose `i`th interval is
+ * `starts[i]..ends[i]` with weight `weights[i]` (the arrays may be in any order),
+ * and writes the indices of the optimal set, ascending, to `selected`.
+ *
+ * - `*selected_len` is always set to the size of the optimal set
+ *   (unless `W_INTER_NULL_POINTER` or `W_INTER_WEIGHT_OVERFLOW` is returned).
+ * - If `capacity` is smaller than that, nothing is written to `selected`, and
+ *   `W_INTER_BUFFER_TOO_SMALL` is returned. A capacity of `len` always suffices.
+ * - Pointers may only be NULL when `len` (or `capacity`, for `selected`) is 0.
+ * - Intervals that merely touch (one ends when the other starts) are compatible.
+ * - The total weight of all the intervals must fit in a `uint64_t`. If it
+ *   doesn't, nothing is written, and `W_INTER_WEIGHT_OVERFLOW` is returned.
+ */
+
+#ifndef W_INTER_H
+#define W_INTER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum w_inter_status {
+  W_INTER_OK               = 0,
+  W_INTER_NULL_POINTER     = 1,
+  W_INTER_BUFFER_TOO_SMALL = 2,
+  W_INTER_WEIGHT_OVERFLOW  = 3
+} w_inter_status;
+
+w_inter_status w_inter_solve_u32(const uint32_t *starts, const uint32_t *ends, const uint64_t *weights, size_t len,
+                                 size_t *selected, size_t capacity, size_t *selected_len);
+
+w_inter_status w_inter_solve_u64(const uint64_t *starts, const uint64_t *ends, const uint64_t *weights, size_t len,
+                                 size_t *selected, size_t capacity, size_t *selected_len);
+
+w_inter_status w_inter_solve_i32(const int32_t *starts, const int32_t *ends, const uint64_t *weights, size_t len,
+                                 size_t *selected, size_t capacity, size_t *selected_len);
+
+w_inter_status w_inter_solve_i64(const int64_t *starts, const int64_t *ends, const uint64_t *weights, size_t len,
+                                 size_t *selected, size_t capacity, size_t *selected_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* W_INTER_H */
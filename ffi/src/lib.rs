@@ -0,0 +1,148 @@
+//! C bindings for `w_inter`, see `include/w_inter.h` for the documented interface.
+
+use std::slice;
+use w_inter::unsorted_soa;
+
+/// Mirrors `w_inter_status` in the header.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+  Ok             = 0,
+  NullPointer    = 1,
+  BufferTooSmall = 2,
+  WeightOverflow = 3
+}
+
+/// Views `len` elements at `pointer`, which may only be null when `len` is 0.
+unsafe fn column<'a, T>(pointer: *const T, len: usize) -> Option<&'a [T]> {
+  if len == 0 { return Some(&[]); }
+  if pointer.is_null() { return None; }
+  Some(slice::from_raw_parts(pointer, len))
+}
+
+/// # Safety
+/// See `w_inter_solve_*` in the header.
+unsafe fn solve<Time: Ord>(
+  starts:       *const Time,
+  ends:         *const Time,
+  weights:      *const u64,
+  len:          usize,
+  selected:     *mut usize,
+  capacity:     usize,
+  selected_len: *mut usize
+) -> Status {
+  let (Some(starts), Some(ends), Some(weights)) = (column(starts, len), column(ends, len), column(weights, len))
+  else { return Status::NullPointer };
+  if selected_len.is_null() { return Status::NullPointer; }
+
+  // no sum the solver forms exceeds the total, so if that fits, nothing overflows (which would panic across the FFI)
+  if weights.iter().try_fold(0u64, |total, &weight| total.checked_add(weight)).is_none() { return Status::WeightOverflow; }
+
+  let mut solution = unsorted_soa(starts, ends, weights);
+  *selected_len = solution.len();
+
+  if solution.is_empty() { return Status::Ok; }
+  if capacity < solution.len() { return Status::BufferTooSmall; }
+  if selected.is_null() { return Status::NullPointer; }
+
+  solution.sort_unstable();
+  slice::from_raw_parts_mut(selected, solution.len()).copy_from_slice(&solution);
+  Status::Ok
+}
+
+macro_rules! solver {
+  ($name:ident, $time:ty) => {
+    /// # Safety
+    /// See the header: `starts`, `ends`, and `weights` must point to `len` elements, `selected` to `capacity` elements,
+    /// and `selected_len` to one element (pointers may be null only when their length is 0).
+    #[no_mangle]
+    pub unsafe extern "C" fn $name(
+      starts:       *const $time,
+      ends:         *const $time,
+      weights:      *const u64,
+      len:          usize,
+      selected:     *mut usize,
+      capacity:     usize,
+      selected_len: *mut usize
+    ) -> Status {
+      solve(starts, ends, weights, len, selected, capacity, selected_len)
+    }
+  };
+}
+
+solver!(w_inter_solve_u32, u32);
+solver!(w_inter_solve_u64, u64);
+solver!(w_inter_solve_i32, i32);
+solver!(w_inter_solve_i64, i64);
+
+#[cfg(test)]
+mod tests {
+  use std::ptr;
+  use super::*;
+
+  #[test]
+  fn solves_unsorted_columns() {
+    let starts  = [5i64, 0, 1, 3];
+    let ends    = [9i64, 1, 4, 8];
+    let weights = [7u64, 2, 5, 8];
+
+    let mut selected = [usize::MAX; 4];
+    let mut selected_len = 0;
+    let status = unsafe {
+      w_inter_solve_i64(starts.as_ptr(), ends.as_ptr(), weights.as_ptr(), 4, selected.as_mut_ptr(), 4, &mut selected_len)
+    };
+
+    assert_eq!(status, Status::Ok);
+    assert_eq!(&selected[..selected_len], &[0, 1, 2]);
+  }
+
+  #[test]
+  fn reports_required_capacity() {
+    let starts  = [0u32, 1];
+    let ends    = [1u32, 2];
+    let weights = [1u64, 1];
+
+    let mut selected = [usize::MAX; 1];
+    let mut selected_len = 0;
+    let status = unsafe {
+      w_inter_solve_u32(starts.as_ptr(), ends.as_ptr(), weights.as_ptr(), 2, selected.as_mut_ptr(), 1, &mut selected_len)
+    };
+
+    assert_eq!(status, Status::BufferTooSmall);
+    assert_eq!(selected_len, 2);
+    assert_eq!(selected, [usize::MAX]);
+  }
+
+  #[test]
+  fn weight_overflow() {
+    let starts  = [0u64, 1];
+    let ends    = [1u64, 2];
+    let weights = [u64::MAX, 1];
+
+    let mut selected = [usize::MAX; 2];
+    let mut selected_len = usize::MAX;
+    let status = unsafe {
+      w_inter_solve_u64(starts.as_ptr(), ends.as_ptr(), weights.as_ptr(), 2, selected.as_mut_ptr(), 2, &mut selected_len)
+    };
+
+    assert_eq!(status, Status::WeightOverflow);
+    assert_eq!((selected_len, selected), (usize::MAX, [usize::MAX; 2]));
+
+    let weights = [u64::MAX - 1, 1];
+    let status = unsafe {
+      w_inter_solve_u64(starts.as_ptr(), ends.as_ptr(), weights.as_ptr(), 2, selected.as_mut_ptr(), 2, &mut selected_len)
+    };
+    assert_eq!(status, Status::Ok);
+    assert_eq!(&selected[..selected_len], &[0, 1]);
+  }
+
+  #[test]
+  fn null_pointers() {
+    let mut selected_len = usize::MAX;
+    let status = unsafe { w_inter_solve_u64(ptr::null(), ptr::null(), ptr::null(), 0, ptr::null_mut(), 0, &mut selected_len) };
+    assert_eq!((status, selected_len), (Status::Ok, 0));
+
+    let status = unsafe { w_inter_solve_u64(ptr::null(), ptr::null(), ptr::null(), 3, ptr::null_mut(), 0, &mut selected_len) };
+    assert_eq!(status, Status::NullPointer);
+  }
+}
@@ -0,0 +1,130 @@
+//! Python bindings for `w_inter`, built with [maturin](https://www.maturin.rs) (`maturin develop` from this directory).
+//!
+//! ```python
+//! import numpy as np
+//! import w_inter
+//!
+//! starts  = np.array([0, 1, 4])
+//! ends    = np.array([4, 5, 9])
+//! weights = np.array([2.0, 3.0, 3.0])
+//!
+//! w_inter.solve(starts, ends, weights)  # [0, 2]
+//! ```
+
+use std::{cmp::Ordering, ops::Add};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use w_inter::unsorted_soa;
+
+/// A column of numbers: any sequence (list, tuple, numpy array, ...) of all integers or all floats.
+#[derive(FromPyObject)]
+enum Column {
+  Int(Vec<i64>),
+  Float(Vec<f64>)
+}
+
+impl Column {
+  fn len(&self) -> usize {
+    match self {
+      Column::Int(c)   => c.len(),
+      Column::Float(c) => c.len()
+    }
+  }
+
+  fn into_totals(self, name: &str) -> PyResult<Vec<Total>> {
+    let column = match self {
+      Column::Int(c)   => c.into_iter().map(|v| v as f64).collect::<Vec<_>>(),
+      Column::Float(c) => c
+    };
+    if column.iter().any(|v| v.is_nan()) { return Err(PyValueError::new_err(format!("`{name}` contains NaN"))); }
+    Ok(column.into_iter().map(Total).collect())
+  }
+}
+
+/// Returns the (ascending) indices of the maximum total weight set of non-overlapping intervals,
+/// where interval `i` is `starts[i]..ends[i]` with weight `weights[i]`.
+///
+/// - Columns can be lists, tuples, numpy arrays, ... of integers or floats, in any order.
+/// - Intervals that merely touch (one ends when the other starts) are compatible.
+/// - Intervals with a negative weight are never selected.
+#[pyfunction]
+fn solve(starts: Column, ends: Column, weights: Column) -> PyResult<Vec<usize>> {
+  if starts.len() != ends.len() || ends.len() != weights.len() {
+    return Err(PyValueError::new_err(format!(
+      "columns must have equal lengths (starts: {}, ends: {}, weights: {})", starts.len(), ends.len(), weights.len()
+    )));
+  }
+
+  // integer times are solved exactly, anything involving floats is solved over floats
+  let weights = weights.into_totals("weights")?;
+  match (starts, ends) {
+    (Column::Int(starts), Column::Int(ends)) => Ok(solve_columns(&starts, &ends, &weights)),
+    (starts, ends) => Ok(solve_columns(&starts.into_totals("starts")?, &ends.into_totals("ends")?, &weights))
+  }
+}
+
+/// Drops negative weights, solves, and maps the solution back to indices into the columns.
+fn solve_columns<Time: Ord + Clone>(starts: &[Time], ends: &[Time], weights: &[Total]) -> Vec<usize> {
+  let kept: Vec<usize> = (0..weights.len()).filter(|&i| weights[i] >= Total(0.0)).collect();
+  let column = |c: &[Time]| kept.iter().map(|&i| c[i].clone()).collect::<Vec<_>>();
+  let weights: Vec<Total> = kept.iter().map(|&i| weights[i]).collect();
+
+  let mut solution: Vec<usize> = unsorted_soa(&column(starts), &column(ends), &weights).into_iter()
+    .map(|i| kept[i])
+    .collect();
+  solution.sort_unstable();
+  solution
+}
+
+/// An `f64` that has been checked not to be `NaN`, ordered by `f64::total_cmp`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Total(f64);
+
+impl Eq for Total { }
+
+impl PartialOrd for Total {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Total {
+  fn cmp(&self, other: &Self) -> Ordering { self.0.total_cmp(&other.0) }
+}
+
+impl Add for Total {
+  type Output = Total;
+  fn add(self, other: Self) -> Self { Total(self.0 + other.0) }
+}
+
+#[pymodule]
+#[pyo3(name = "w_inter")]
+fn w_inter_python(module: &Bound<'_, PyModule>) -> PyResult<()> {
+  module.add_function(wrap_pyfunction!(solve, module)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use pyo3::{prelude::*, types::PyDict};
+
+  fn run(code: &str) -> PyResult<String> {
+    Python::attach(|py| {
+      let module = PyModule::new(py, "w_inter")?;
+      super::w_inter_python(&module)?;
+
+      let locals = PyDict::new(py);
+      locals.set_item("w_inter", module)?;
+      py.run(&std::ffi::CString::new(code).unwrap(), None, Some(&locals))?;
+      Ok(locals.get_item("result")?.unwrap().str()?.to_string())
+    })
+  }
+
+  #[test]
+  fn integer_and_float_columns() {
+    assert_eq!(run("result = w_inter.solve([4, 0, 1], (9, 4, 5), [3, 2.5, 3])").unwrap(), "[0, 1]");
+    assert_eq!(run("result = w_inter.solve([0.5, 0], [1.5, 1], [1, -1])").unwrap(), "[0]");
+  }
+
+  #[test]
+  fn rejects_bad_columns() {
+    assert!(run("result = w_inter.solve([0], [1, 2], [1])").unwrap_err().to_string().contains("equal lengths"));
+    assert!(run("result = w_inter.solve([0], [1], [float('nan')])").unwrap_err().to_string().contains("NaN"));
+  }
+}
@@ -0,0 +1,113 @@
+//! Wrappers changing what a solver optimizes, without touching the wrapped intervals.
+
+use std::{marker::PhantomData, ops::{Add, Sub}};
+use crate::traits;
+
+/// Gives every interval a weight of `1`, so the solvers maximize the *number* of selected intervals.
+/// 
+/// The wrapped interval's own weight (if any) is ignored.
+/// 
+/// NOTE: when only the count matters, [`max_count`](crate::max_count) finds an optimal set with much less work than
+/// the weighted solvers do. This remains useful with the solvers `max_count` has no counterpart for.
+/// 
+/// ```rust
+/// # use w_inter::*;
+/// let intervals = [(0, 10), (1, 2), (2, 3), (3, 4)].map(Unweighted);
+/// 
+/// let most = unsorted(&intervals);
+/// assert_eq!(most.len(), 3);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Unweighted<Interval>(pub Interval);
+
+impl<Time: Ord, Interval: traits::Interval<Time>> traits::Interval<Time> for Unweighted<Interval> {
+  fn start(&self) -> Time { self.0.start() }
+  fn end(&self) -> Time { self.0.end() }
+}
+
+impl<Interval> traits::Weighted<usize> for Unweighted<Interval> {
+  fn weight(&self) -> usize { 1 }
+}
+
+/// Weighs every interval by its length (`end - start`), so the solvers maximize total busy time.
+/// 
+/// - `Length` is the type lengths are measured *and summed* in, converted from the difference of two `Time`s.
+///   It defaults to `Time` itself, but choosing a wider type avoids overflow when many lengths add up
+///   (e.g. `u64` for `u32` times), and it must be chosen for times whose difference is another type
+///   (e.g. `Duration` for `Instant` times).
+/// - The wrapped interval's own weight (if any) is ignored.
+/// - Intervals must not be inverted (`start > end`), as their length may not be representable.
+/// 
+/// ```rust
+/// # use w_inter::*;
+/// let intervals = [(0u32, 10u32), (1, 2), (2, 3), (3, 4)];
+/// 
+/// // widen the sum to u64, to be safe
+/// let busiest = unsorted(intervals.map(WeightByLength::<_, u32, u64>::new));
+/// assert_eq!(busiest, vec![WeightByLength::new((0, 10))]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct WeightByLength<Interval, Time, Length = Time> {
+  pub interval: Interval,
+  measure: PhantomData<fn(Time) -> Length>
+}
+
+impl<Interval, Time, Length> WeightByLength<Interval, Time, Length> {
+  pub fn new(interval: Interval) -> Self { Self { interval, measure: PhantomData } }
+}
+
+// not derived, as that would require `Time: Clone` and `Length: Clone`
+impl<Interval: Clone, Time, Length> Clone for WeightByLength<Interval, Time, Length> {
+  fn clone(&self) -> Self { Self::new(self.interval.clone()) }
+}
+
+impl<Interval: Copy, Time, Length> Copy for WeightByLength<Interval, Time, Length> { }
+
+impl<Time: Ord, Length, Interval: traits::Interval<Time>> traits::Interval<Time> for WeightByLength<Interval, Time, Length> {
+  fn start(&self) -> Time { self.interval.start() }
+  fn end(&self) -> Time { self.interval.end() }
+}
+
+impl<Time, Length, Interval> traits::Weighted<Length> for WeightByLength<Interval, Time, Length>
+  where Time: Ord + Sub,
+        Length: Ord + Add + From<Time::Output>,
+        Interval: traits::Interval<Time>
+{
+  fn weight(&self) -> Length { Length::from(self.interval.end() - self.interval.start()) }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, Instant};
+  use crate::{unsorted, Unweighted, WeightByLength, WeightedInterval};
+
+  #[test]
+  fn count_beats_weight() {
+    let intervals = [
+      WeightedInterval::new(0u8, 9u8, 100u8),
+      WeightedInterval::new(0u8, 3u8, 1u8),
+      WeightedInterval::new(3u8, 6u8, 1u8),
+      WeightedInterval::new(6u8, 9u8, 1u8)
+    ];
+
+    assert_eq!(unsorted(intervals).len(), 1);
+    assert_eq!(unsorted(intervals.map(Unweighted)).len(), 3);
+  }
+
+  #[test]
+  fn durations_between_instants() {
+    let t = Instant::now();
+    let s = Duration::from_secs;
+    let intervals = [(t, t + s(5)), (t + s(4), t + s(6)), (t + s(6), t + s(7))];
+
+    let busiest = unsorted(intervals.map(WeightByLength::<_, Instant, Duration>::new));
+    assert_eq!(busiest.iter().map(|w| w.interval).collect::<Vec<_>>(), vec![intervals[2], intervals[0]]);
+  }
+
+  #[test]
+  fn wide_accumulator() {
+    // the lengths sum to more than u8 can hold
+    let intervals = [(0u8, 200u8), (200, 255)].map(WeightByLength::<_, u8, u16>::new);
+    assert_eq!(unsorted(intervals).len(), 2);
+  }
+}
@@ -0,0 +1,374 @@
+//! Analyses of how each interval stands with respect to the optimum, from a forward and a backward pass of the
+//! memoization (the optimum of the intervals ending by each time, and of those starting from each time).
+
+use std::ops::{Add, Range, Sub};
+use crate::traits;
+
+/// How far an interval's weight is from changing whether it is selected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sensitivity<Weight> {
+  /// The interval is in some optimal selection, and would stay in one with its weight decreased by up to `slack`
+  /// (`None` if it is the only interval, so that it always stays).
+  Selected { slack: Option<Weight> },
+  /// The interval is in no optimal selection, and would need its weight increased by `gap` to be in one.
+  Unselected { gap: Weight }
+}
+
+/// Whether an interval is selected by the optimal selections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Persistency {
+  /// In every optimal selection.
+  Always,
+  /// In some optimal selections, but not all.
+  Sometimes,
+  /// In no optimal selection.
+  Never
+}
+
+/// For each interval (in input order), how much its weight could increase before it enters an optimal selection, or
+/// decrease before it leaves them all. `O(n log n)` in interval number.
+///
+/// Weights are assumed non-negative.
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::analysis::Sensitivity;
+/// let bids: Vec<WeightedInterval<u32, u8>> = vec![(0, 4, 10).into(), (2, 6, 7).into(), (4, 8, 4).into()];
+///
+/// assert_eq!(analysis::sensitivity(&bids), vec![
+///   Sensitivity::Selected { slack: Some(7) },
+///   Sensitivity::Unselected { gap: 7 },
+///   Sensitivity::Selected { slack: Some(4) }
+/// ]);
+/// ```
+#[must_use]
+pub fn sensitivity<Weight, Time, Interval, InputContainer>(intervals: InputContainer) -> Vec<Sensitivity<Weight>>
+  where Weight: Ord + Add<Output = Weight> + Sub<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let Some(passes) = Passes::new(intervals.as_ref()) else { return vec![] };
+  let optimum = passes.optimum();
+
+  passes.without().into_iter()
+    .zip(passes.included)
+    .map(|(without, included)| {
+      if included == optimum { Sensitivity::Selected { slack: without.map(|without| included - without) } }
+      else { Sensitivity::Unselected { gap: optimum.clone() - included } }
+    })
+    .collect()
+}
+
+/// For each interval (in input order), the weight of the best selection containing it: the optimum if it had to be
+/// selected. `O(n log n)` in interval number.
+///
+/// The difference with the optimum is the opportunity cost of guaranteeing the interval a place.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let slots: Vec<WeightedInterval<u32, u8>> = vec![(0, 4, 10).into(), (2, 6, 7).into(), (4, 8, 4).into(), (6, 9, 3).into()];
+///
+/// // the optimum is 14, so guaranteeing the slot from 2 to 6 costs 4
+/// assert_eq!(analysis::value_if_included(&slots), vec![14, 10, 14, 13]);
+/// ```
+#[must_use]
+pub fn value_if_included<Weight, Time, Interval, InputContainer>(intervals: InputContainer) -> Vec<Weight>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  Passes::new(intervals.as_ref()).map_or(vec![], |passes| passes.included)
+}
+
+/// For each interval (in input order), whether it is in every optimal selection, some of them, or none.
+/// `O(n log n)` in interval number.
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::analysis::Persistency;
+/// let slots: Vec<WeightedInterval<u32, u8>> = vec![(0, 4, 5).into(), (4, 8, 5).into(), (2, 6, 3).into(), (0, 8, 10).into()];
+///
+/// assert_eq!(analysis::persistency(&slots), vec![
+///   Persistency::Sometimes,
+///   Persistency::Sometimes,
+///   Persistency::Never,
+///   Persistency::Sometimes
+/// ]);
+///
+/// let slots = &slots[..3];
+/// assert_eq!(analysis::persistency(slots), vec![Persistency::Always, Persistency::Always, Persistency::Never]);
+/// ```
+#[must_use]
+pub fn persistency<Weight, Time, Interval, InputContainer>(intervals: InputContainer) -> Vec<Persistency>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let Some(passes) = Passes::new(intervals.as_ref()) else { return vec![] };
+  let optimum = passes.optimum();
+
+  passes.without().into_iter()
+    .zip(&passes.included)
+    .map(|(without, included)| match (*included == optimum, without.is_some_and(|without| without == optimum)) {
+      (false, _)    => Persistency::Never,
+      (true, false) => Persistency::Always,
+      (true, true)  => Persistency::Sometimes
+    })
+    .collect()
+}
+
+/// The forward and backward passes over a nonempty problem, and what follows from them for each interval.
+struct Passes<'a, Weight, Time, Interval> {
+  intervals:  &'a [Interval],
+  /// The intervals by end (then start, so that zero-length intervals follow those ending where they are).
+  by_end:     Vec<usize>,
+  ends:       Vec<Time>,
+  /// The optimal weight of the intervals up to each position in `by_end`.
+  prefix:     Vec<Weight>,
+  /// The intervals by start (then end, so that zero-length intervals precede those starting where they are).
+  by_start:   Vec<usize>,
+  starts:     Vec<(Time, Time)>,
+  /// The optimal weight of the intervals from each rank in `by_start` on.
+  suffix:     Vec<Weight>,
+  /// The position of each interval in `by_end`.
+  position:   Vec<usize>,
+  /// The weight of the best selection compatible with each interval (`None` if nothing is).
+  compatible: Vec<Option<Weight>>,
+  /// The weight of the best selection containing each interval.
+  included:   Vec<Weight>
+}
+
+impl<'a, Weight, Time, Interval> Passes<'a, Weight, Time, Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  /// `None` if there are no intervals. `O(n log n)`.
+  fn new(intervals: &'a [Interval]) -> Option<Self> {
+    let count = intervals.len();
+    if count == 0 { return None; }
+
+    let mut by_end: Vec<usize> = (0..count).collect();
+    phase!("sort", count, by_end.sort_by_key(|&i| (intervals[i].end(), intervals[i].start())));
+    let ends: Vec<Time> = by_end.iter().map(|&i| intervals[i].end()).collect();
+
+    let mut by_start: Vec<usize> = (0..count).collect();
+    phase!("sort", count, by_start.sort_by_key(|&i| (intervals[i].start(), intervals[i].end())));
+    let starts: Vec<(Time, Time)> = by_start.iter().map(|&i| (intervals[i].start(), intervals[i].end())).collect();
+
+    let mut position = vec![0; count];
+    for (p, &i) in by_end.iter().enumerate() { position[i] = p; }
+    let mut rank = vec![0; count];
+    for (r, &i) in by_start.iter().enumerate() { rank[i] = r; }
+
+    let mut passes = Self {
+      intervals, by_end, ends, prefix: vec![], by_start, starts, suffix: vec![], position, compatible: vec![], included: vec![]
+    };
+
+    phase!("memoize", count, for p in 0..count {
+      let included = sum(Some(intervals[passes.by_end[p]].weight()), passes.predecessor(p).map(|q| passes.prefix[q].clone()));
+      let best = included.max(passes.prefix.last().cloned()).expect("something included");
+      passes.prefix.push(best);
+    });
+
+    passes.suffix = vec![intervals[passes.by_start[count - 1]].weight(); count];
+    phase!("memoize", count, for r in (0..count - 1).rev() {
+      let included = sum(Some(intervals[passes.by_start[r]].weight()), passes.successor(r).map(|s| passes.suffix[s].clone()));
+      passes.suffix[r] = included.max(Some(passes.suffix[r + 1].clone())).expect("something included");
+    });
+
+    passes.compatible = (0..count)
+      .map(|i| {
+        let before = passes.predecessor(passes.position[i]).map(|q| passes.prefix[q].clone());
+        sum(before, passes.successor(rank[i]).map(|s| passes.suffix[s].clone()))
+      })
+      .collect();
+    passes.included = (0..count)
+      .map(|i| sum(Some(intervals[i].weight()), passes.compatible[i].clone()).expect("something included"))
+      .collect();
+
+    Some(passes)
+  }
+
+  /// The optimal weight.
+  fn optimum(&self) -> Weight { self.prefix[self.prefix.len() - 1].clone() }
+
+  /// The last position (before `p`, in `by_end`) whose interval ends by the time the one at `p` starts.
+  fn predecessor(&self, p: usize) -> Option<usize> {
+    self.ends[..p].partition_point(|e| *e <= self.intervals[self.by_end[p]].start()).checked_sub(1)
+  }
+
+  /// The first rank (after `r`, in `by_start`) whose interval starts once the one at `r` ends.
+  fn successor(&self, r: usize) -> Option<usize> {
+    let end = self.intervals[self.by_start[r]].end();
+    Some(r + 1 + self.starts[r + 1..].partition_point(|(s, _)| *s < end)).filter(|&s| s < self.starts.len())
+  }
+
+  /// The weight of the best selection without each interval (`None` if there are no other intervals). `O(n log n)`.
+  fn without(&self) -> Vec<Option<Weight>> {
+    let (intervals, count) = (self.intervals, self.intervals.len());
+
+    // the best selection splitting at each time: those ending by it, then those starting from it (but not ending there)
+    let mut times: Vec<Time> = intervals.iter().flat_map(|i| [i.start(), i.end()]).collect();
+    phase!("sort", times.len(), times.sort_unstable());
+    times.dedup();
+
+    let mut splits = MaxTree::new(times.len());
+    for (t, time) in times.iter().enumerate() {
+      let before = self.ends.partition_point(|e| e <= time).checked_sub(1).map(|p| self.prefix[p].clone());
+      let after = self.suffix.get(self.starts.partition_point(|(s, e)| (s, e) <= (time, time))).cloned();
+      if let Some(split) = sum(before, after) { splits.set(t, split); }
+    }
+
+    // the best selection containing an interval that contains each interval (strictly, for those of zero length)
+    let mut containing: Vec<Option<Weight>> = vec![None; count];
+    let mut selections = MaxTree::new(count);
+    let mut group = 0;
+    while group < count {
+      let start = intervals[self.by_start[group]].start();
+      let members = &self.by_start[group..group + self.starts[group..].partition_point(|(s, _)| *s == start)];
+
+      for &i in members.iter().filter(|&&i| intervals[i].end() == start) {
+        containing[i] = selections.max(self.ends.partition_point(|e| *e <= start)..count);
+      }
+      for &i in members { selections.set(self.position[i], self.included[i].clone()); }
+      for &i in members.iter().filter(|&&i| intervals[i].end() > start) {
+        let from = self.ends.partition_point(|e| *e < intervals[i].end());
+        containing[i] = selections.max(from..self.position[i]).max(selections.max(self.position[i] + 1..count));
+      }
+
+      group += members.len();
+    }
+
+    // without an interval, the best selection either leaves room for it, splits within it, or contains an interval
+    // containing it
+    (0..count)
+      .map(|i| {
+        let (start, end) = (intervals[i].start(), intervals[i].end());
+        let within = times.partition_point(|t| *t <= start)..times.partition_point(|t| *t < end);
+        self.compatible[i].clone().max(splits.max(within)).max(containing[i].clone())
+      })
+      .collect()
+  }
+}
+
+/// Adds up optional weights, `None` being nothing at all.
+fn sum<Weight: Add<Output = Weight>>(a: Option<Weight>, b: Option<Weight>) -> Option<Weight> {
+  match (a, b) {
+    (Some(a), Some(b)) => Some(a + b),
+    (a, b)             => a.or(b)
+  }
+}
+
+/// A segment tree of weights, answering the greatest over ranges of positions.
+struct MaxTree<Weight> {
+  size:  usize,
+  nodes: Vec<Option<Weight>>
+}
+
+impl<Weight: Ord + Clone> MaxTree<Weight> {
+  fn new(size: usize) -> Self { Self { size, nodes: vec![None; 2 * size] } }
+
+  fn set(&mut self, at: usize, weight: Weight) {
+    let mut node = at + self.size;
+    self.nodes[node] = Some(weight);
+    while node > 1 {
+      node /= 2;
+      self.nodes[node] = self.nodes[2 * node].clone().max(self.nodes[2 * node + 1].clone());
+    }
+  }
+
+  fn max(&self, range: Range<usize>) -> Option<Weight> {
+    let (mut low, mut high) = (range.start + self.size, range.end + self.size);
+    let mut greatest = None;
+    while low < high {
+      if low % 2 == 1 { greatest = greatest.max(self.nodes[low].clone()); low += 1; }
+      if high % 2 == 1 { high -= 1; greatest = greatest.max(self.nodes[high].clone()); }
+      low /= 2;
+      high /= 2;
+    }
+    greatest
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{analysis::{self, Persistency, Sensitivity}, testing::{pairwise, subsets}, unsorted, WeightedInterval};
+
+  /// For each interval, the best selections with and without it, by brute force.
+  fn brute_force(intervals: &[WeightedInterval<u32, u32>]) -> Vec<(Option<u32>, Option<u32>)> {
+    let compatible = |a: &WeightedInterval<u32, u32>, b: &WeightedInterval<u32, u32>| a.end <= b.start || b.end <= a.start;
+    let selections: Vec<(Vec<usize>, u32)> = subsets(intervals.len())
+      .skip(1)
+      .filter(|set| pairwise(set, |a, b| compatible(&intervals[a], &intervals[b])))
+      .map(|set| { let weight = set.iter().map(|&i| intervals[i].weight).sum(); (set, weight) })
+      .collect();
+
+    (0..intervals.len())
+      .map(|i| {
+        let best = |with: bool| selections.iter().filter(|(set, _)| set.contains(&i) == with).map(|(_, w)| *w).max();
+        (best(true), best(false))
+      })
+      .collect()
+  }
+
+  #[test]
+  fn sensitivity_matches_brute_force() {
+    // zero-length intervals, some sharing times with the others, and duplicates
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..13u32)
+      .map(|i| (i * 5 % 9, i * 5 % 9 + (i * 7) % 4, 1 + i % 4).into())
+      .chain([(3, 3, 2).into(), (3, 3, 1).into()])
+      .collect();
+    let expected = brute_force(&intervals);
+    let optimum = expected.iter().filter_map(|(with, _)| *with).max().unwrap();
+
+    for (i, sensitivity) in analysis::sensitivity(&intervals).into_iter().enumerate() {
+      let (with, without) = expected[i];
+      let with = with.unwrap();
+      assert_eq!(sensitivity, match with == optimum {
+        true  => Sensitivity::Selected { slack: without.map(|without| with - without) },
+        false => Sensitivity::Unselected { gap: optimum - with }
+      }, "{:?}", intervals[i]);
+    }
+
+    assert_eq!(analysis::sensitivity(&intervals[..1]), vec![Sensitivity::Selected { slack: None }]);
+    assert!(analysis::sensitivity(Vec::<WeightedInterval<u32, u32>>::new()).is_empty());
+  }
+
+  #[test]
+  fn value_if_included_matches_brute_force() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..13u32)
+      .map(|i| (i * 5 % 9, i * 5 % 9 + (i * 7) % 4, 1 + i % 4).into())
+      .chain([(3, 3, 2).into(), (3, 3, 1).into()])
+      .collect();
+
+    let expected: Vec<u32> = brute_force(&intervals).into_iter().map(|(with, _)| with.unwrap()).collect();
+    assert_eq!(analysis::value_if_included(&intervals), expected);
+
+    // the best of them is the optimum
+    let positive: Vec<WeightedInterval<u32, u32>> = intervals.iter().copied().filter(|i| i.start < i.end).collect();
+    let optimum = unsorted(&positive).iter().map(|i| i.weight).sum::<u32>();
+    assert_eq!(analysis::value_if_included(&positive).into_iter().max(), Some(optimum));
+  }
+
+  #[test]
+  fn persistency_matches_brute_force() {
+    // unit weights, for plenty of ties
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..13u32).map(|i| (i * 5 % 9, i * 5 % 9 + (i * 7) % 4, 1).into()).collect();
+    let expected = brute_force(&intervals);
+    let optimum = expected.iter().filter_map(|(with, _)| *with).max();
+
+    let persistency = analysis::persistency(&intervals);
+    for (i, (with, without)) in expected.into_iter().enumerate() {
+      assert_eq!(persistency[i], match (with == optimum, without == optimum) {
+        (false, _)    => Persistency::Never,
+        (true, false) => Persistency::Always,
+        (true, true)  => Persistency::Sometimes
+      });
+    }
+    assert!([Persistency::Always, Persistency::Sometimes, Persistency::Never].iter().all(|p| persistency.contains(p)));
+  }
+}
@@ -0,0 +1,74 @@
+//! Zero-copy solving over `rkyv` archives (behind the `rkyv` feature).
+
+use std::ops::Add;
+use rkyv::Archive;
+use crate::{solvers, traits, util::final_compatible, HalfOpen, WeightedInterval};
+
+pub use crate::weighted_interval::ArchivedWeightedInterval;
+
+impl<Weight, Time> traits::Weighted<Weight> for ArchivedWeightedInterval<Weight, Time>
+  where Weight: Ord + Add + Clone + Archive,
+        Time: Ord + Clone + Archive,
+        Weight::Archived: Clone + Into<Weight>
+{
+  fn weight(&self) -> Weight { self.weight.clone().into() }
+}
+
+impl<Weight, Time> traits::Interval<Time> for ArchivedWeightedInterval<Weight, Time>
+  where Weight: Ord + Add + Clone + Archive,
+        Time: Ord + Clone + Archive,
+        Time::Archived: Clone + Into<Time>
+{
+  fn start(&self) -> Time { self.start.clone().into() }
+  fn end(&self) -> Time { self.end.clone().into() }
+}
+
+/// Like [`sorted`](crate::sorted), but reads the intervals straight out of an archive. `O(n log n)` in interval number.
+/// 
+/// No deserialization pass is needed: fields are converted to their native types as the solver reads them,
+/// and only the members of the optimal set are materialized (appended to `solution` as plain `WeightedInterval`s).
+/// 
+/// The rules of `sorted` apply: **`intervals` must be sorted ascending by interval end time** (so sort before archiving),
+/// `memoization.len()` must be at least the interval count, and `solution` is appended to.
+/// 
+/// ```rust
+/// # use w_inter::*;
+/// let problem: Vec<WeightedInterval<u32, u64>> = vec![
+///   (0, 4, 2).into(),
+///   (1, 5, 5).into(),
+///   (4, 9, 3).into()
+/// ];
+/// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&problem).unwrap();
+/// 
+/// // e.g. the bytes of a memory-mapped file
+/// let archived = rkyv::access::<rkyv::Archived<Vec<WeightedInterval<u32, u64>>>, rkyv::rancor::Error>(&bytes).unwrap();
+/// 
+/// let mut memo = vec![0; archived.len()];
+/// let mut solution = vec![];
+/// sorted_archived(archived, &mut memo, &mut solution);
+/// 
+/// assert_eq!(solution, vec![(1, 5, 5).into()]);
+/// ```
+pub fn sorted_archived<Weight, Time>(
+  intervals:   &[ArchivedWeightedInterval<Weight, Time>],
+  memoization: &mut [Weight],
+  solution:    &mut Vec<WeightedInterval<Weight, Time>>
+) where Weight: Ord + Add<Output = Weight> + Clone + Archive,
+        Time: Ord + Clone + Archive,
+        Weight::Archived: Clone + Into<Weight>,
+        Time::Archived: Clone + Into<Time>
+{
+  use traits::{Interval, Weighted};
+
+  if let Some(i) = intervals.first() { memoization[0] = i.weight(); }
+  else { return; } // empty intervals
+
+  let weight      = |i: usize| intervals[i].weight();
+  let predecessor = |i: usize| final_compatible(intervals, i, &HalfOpen);
+
+  phase!("memoize", intervals.len(), solvers::memoize(intervals.len(), memoization, weight, predecessor));
+  phase!("reconstruct", intervals.len(), solvers::reconstruct(intervals.len(), memoization, weight, predecessor, |i| {
+    let i = &intervals[i];
+    solution.push(WeightedInterval::new(i.start(), i.end(), i.weight()));
+  }));
+}
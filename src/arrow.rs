@@ -0,0 +1,144 @@
+//! Solving straight from Apache Arrow columns (behind the `arrow` feature).
+//!
+//! Columns don't need to be sorted, and are never copied (see [`unsorted_soa`](crate::unsorted_soa)).
+//! Only integer-like native types can be used, since weights must be `Ord` (floats aren't).
+
+use std::{fmt, ops::Add};
+use arrow_array::{Array, ArrowPrimitiveType, BooleanArray, PrimitiveArray, UInt64Array};
+use crate::unsorted_soa;
+
+/// Reasons a problem given as Arrow columns can't be solved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+  /// The three columns don't have the same length.
+  LengthMismatch { starts: usize, ends: usize, weights: usize },
+  /// A column contains a null, at `index`.
+  Null { column: &'static str, index: usize }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::LengthMismatch { starts, ends, weights } => write!(
+        f, "columns must have equal lengths (starts: {starts}, ends: {ends}, weights: {weights})"
+      ),
+      Error::Null { column, index } => write!(f, "null in column `{column}` at index {index}")
+    }
+  }
+}
+
+impl std::error::Error for Error { }
+
+/// Solves the problem given column-wise, returning the (ascending) indices of the optimal set. `O(n log n)` in interval number.
+///
+/// ```rust
+/// # use arrow_array::{Int64Array, UInt32Array, UInt64Array};
+/// let starts  = Int64Array::from(vec![4, 0, 1]);
+/// let ends    = Int64Array::from(vec![9, 4, 5]);
+/// let weights = UInt32Array::from(vec![3, 2, 3]);
+///
+/// let indices = w_inter::arrow::solve_indices(&starts, &ends, &weights).unwrap();
+/// assert_eq!(indices, UInt64Array::from(vec![0, 1]));
+/// ```
+pub fn solve_indices<TimeType, WeightType>(
+  starts:  &PrimitiveArray<TimeType>,
+  ends:    &PrimitiveArray<TimeType>,
+  weights: &PrimitiveArray<WeightType>
+) -> Result<UInt64Array, Error>
+  where TimeType: ArrowPrimitiveType,
+        WeightType: ArrowPrimitiveType,
+        TimeType::Native: Ord,
+        WeightType::Native: Ord + Add<Output = WeightType::Native>
+{
+  let mut selected = solve(starts, ends, weights)?;
+  selected.sort_unstable();
+  Ok(selected.into_iter().map(|i| i as u64).collect())
+}
+
+/// Solves the problem given column-wise, returning a mask that is `true` exactly at members of the optimal set.
+/// `O(n log n)` in interval number.
+///
+/// ```rust
+/// # use arrow_array::{BooleanArray, Int64Array, UInt32Array};
+/// let starts  = Int64Array::from(vec![4, 0, 1]);
+/// let ends    = Int64Array::from(vec![9, 4, 5]);
+/// let weights = UInt32Array::from(vec![3, 2, 3]);
+///
+/// let mask = w_inter::arrow::solve_mask(&starts, &ends, &weights).unwrap();
+/// assert_eq!(mask, BooleanArray::from(vec![true, true, false]));
+/// ```
+pub fn solve_mask<TimeType, WeightType>(
+  starts:  &PrimitiveArray<TimeType>,
+  ends:    &PrimitiveArray<TimeType>,
+  weights: &PrimitiveArray<WeightType>
+) -> Result<BooleanArray, Error>
+  where TimeType: ArrowPrimitiveType,
+        WeightType: ArrowPrimitiveType,
+        TimeType::Native: Ord,
+        WeightType::Native: Ord + Add<Output = WeightType::Native>
+{
+  let mut mask = vec![false; weights.len()];
+  for i in solve(starts, ends, weights)? { mask[i] = true; }
+  Ok(BooleanArray::from(mask))
+}
+
+/// Indices (into the columns) of the optimal set, in no particular order.
+fn solve<TimeType, WeightType>(
+  starts:  &PrimitiveArray<TimeType>,
+  ends:    &PrimitiveArray<TimeType>,
+  weights: &PrimitiveArray<WeightType>
+) -> Result<Vec<usize>, Error>
+  where TimeType: ArrowPrimitiveType,
+        WeightType: ArrowPrimitiveType,
+        TimeType::Native: Ord,
+        WeightType::Native: Ord + Add<Output = WeightType::Native>
+{
+  if starts.len() != ends.len() || ends.len() != weights.len() {
+    return Err(Error::LengthMismatch { starts: starts.len(), ends: ends.len(), weights: weights.len() });
+  }
+  check_nulls("starts", starts)?;
+  check_nulls("ends", ends)?;
+  check_nulls("weights", weights)?;
+
+  Ok(unsorted_soa(starts.values(), ends.values(), weights.values()))
+}
+
+fn check_nulls(column: &'static str, array: &impl Array) -> Result<(), Error> {
+  match array.nulls().and_then(|n| (0..n.len()).find(|&i| n.is_null(i))) {
+    Some(index) => Err(Error::Null { column, index }),
+    None        => Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use arrow_array::{Int32Array, UInt8Array};
+  use crate::arrow::{solve_indices, Error};
+
+  #[test]
+  fn rejects_nulls() {
+    let starts  = Int32Array::from(vec![Some(0), None]);
+    let ends    = Int32Array::from(vec![1, 2]);
+    let weights = UInt8Array::from(vec![1, 1]);
+
+    assert_eq!(solve_indices(&starts, &ends, &weights), Err(Error::Null { column: "starts", index: 1 }));
+  }
+
+  #[test]
+  fn rejects_ragged_columns() {
+    let starts  = Int32Array::from(vec![0, 1]);
+    let ends    = Int32Array::from(vec![1, 2]);
+    let weights = UInt8Array::from(vec![1]);
+
+    assert_eq!(
+      solve_indices(&starts, &ends, &weights),
+      Err(Error::LengthMismatch { starts: 2, ends: 2, weights: 1 })
+    );
+  }
+
+  #[test]
+  fn empty() {
+    let empty = Int32Array::from(Vec::<i32>::new());
+    assert_eq!(solve_indices(&empty, &empty, &UInt8Array::from(Vec::<u8>::new())).unwrap().len(), 0);
+  }
+}
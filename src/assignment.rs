@@ -0,0 +1,140 @@
+//! The assignment problem: pairing rows (e.g. jobs) with columns (e.g. machines or people), each at most once, at least
+//! total cost or most total weight.
+
+/// Assigns as many rows to distinct columns as there can be (all of the rows, or all of the columns, whichever are
+/// fewer), at least total cost, where assigning row `i` to column `j` costs `cost(i, j)` (Hungarian algorithm).
+/// `O(n² m)` for `n` rows and `m` columns, `n` the fewer.
+///
+/// Costs may be negative, and must convert losslessly into `i128` (all primitive integers up to 64 bits do). Returns the
+/// column assigned to each row (`None` for the rows left over, when there are more rows than columns).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let cost = [[4, 1, 3], [2, 0, 5], [3, 2, 2]];
+///
+/// let assigned = assignment::min_cost(3, 3, |i, j| cost[i][j]);
+/// assert_eq!(assigned, vec![Some(1), Some(0), Some(2)]);
+/// ```
+#[must_use]
+pub fn min_cost<Cost: Into<i128>>(rows: usize, columns: usize, cost: impl Fn(usize, usize) -> Cost) -> Vec<Option<usize>> {
+  if rows <= columns { return hungarian(rows, columns, &|i, j| cost(i, j).into()); }
+
+  // assign each column a row instead
+  let by_column = hungarian(columns, rows, &|j, i| cost(i, j).into());
+  let mut assigned = vec![None; rows];
+  for (j, i) in by_column.into_iter().enumerate() {
+    if let Some(i) = i { assigned[i] = Some(j); }
+  }
+  assigned
+}
+
+/// [`min_cost`], for no more rows than columns.
+fn hungarian(rows: usize, columns: usize, cost: &dyn Fn(usize, usize) -> i128) -> Vec<Option<usize>> {
+  // one-based, row and column 0 standing for none: `row[j]` is the row assigned column `j`, and `u`, `v` are the
+  // potentials of the rows and columns, whose sums never exceed the costs
+  let (mut u, mut v) = (vec![0i128; rows + 1], vec![0i128; columns + 1]);
+  let mut row = vec![0; columns + 1];
+  let mut way = vec![0; columns + 1];
+
+  phase!("assign", rows, for i in 1..=rows {
+    // grow a tree of tight edges from row `i` until it reaches a free column, shifting the potentials whenever no edge
+    // is tight
+    row[0] = i;
+    let mut column = 0;
+    let mut slack = vec![i128::MAX; columns + 1];
+    let mut used = vec![false; columns + 1];
+    loop {
+      used[column] = true;
+      let from = row[column];
+      let (mut delta, mut next) = (i128::MAX, 0);
+      for j in 1..=columns {
+        if used[j] { continue; }
+        let reduced = cost(from - 1, j - 1) - u[from] - v[j];
+        if reduced < slack[j] { slack[j] = reduced; way[j] = column; }
+        if slack[j] < delta { delta = slack[j]; next = j; }
+      }
+      for j in 0..=columns {
+        if used[j] { u[row[j]] += delta; v[j] -= delta; }
+        else { slack[j] -= delta; }
+      }
+      column = next;
+      if row[column] == 0 { break; }
+    }
+
+    // flip the assignments along the path back to row `i`
+    while column != 0 {
+      let previous = way[column];
+      row[column] = row[previous];
+      column = previous;
+    }
+  });
+
+  let mut assigned = vec![None; rows];
+  for j in 1..=columns {
+    if row[j] != 0 { assigned[row[j] - 1] = Some(j - 1); }
+  }
+  assigned
+}
+
+/// Like [`min_cost`], but at most total weight, where assigning row `i` to column `j` weighs `weight(i, j)`. As many
+/// rows are assigned as there can be, even where a weight is negative.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // how well each of three people does each of two tasks
+/// let skill = [[7, 3], [6, 5], [2, 4]];
+///
+/// let assigned = assignment::max_weight(3, 2, |i, j| skill[i][j]);
+/// assert_eq!(assigned, vec![Some(0), Some(1), None]);
+/// ```
+#[must_use]
+pub fn max_weight<Weight: Into<i128>>(rows: usize, columns: usize, weight: impl Fn(usize, usize) -> Weight) -> Vec<Option<usize>> {
+  min_cost(rows, columns, |i, j| -weight(i, j).into())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{assignment::{max_weight, min_cost}, testing::Lcg};
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(59);
+
+    /// The least total cost of assigning rows `i..` to distinct columns not yet `taken`, all of them or all of the
+    /// columns, whichever are fewer.
+    fn best(cost: &[Vec<i64>], columns: usize, i: usize, taken: u32) -> i64 {
+      let (rows, left) = (cost.len() - i, columns - taken.count_ones() as usize);
+      if rows == 0 || left == 0 { return 0; }
+      let assign = (0..columns)
+        .filter(|j| taken & (1 << j) == 0)
+        .map(|j| cost[i][j] + best(cost, columns, i + 1, taken | (1 << j)))
+        .min()
+        .unwrap_or(0);
+      // a row may only go unassigned when there are more rows than columns left
+      if rows > left { assign.min(best(cost, columns, i + 1, taken)) } else { assign }
+    }
+
+    for _ in 0..300 {
+      let (rows, columns) = (rng.next(6) as usize, rng.next(6) as usize);
+      let cost: Vec<Vec<i64>> = (0..rows).map(|_| (0..columns).map(|_| i64::from(rng.next(30)) - 10).collect()).collect();
+
+      let check = |assigned: &[Option<usize>]| {
+        assert_eq!(assigned.len(), rows);
+        assert_eq!(assigned.iter().flatten().count(), rows.min(columns));
+        let mut distinct: Vec<usize> = assigned.iter().flatten().copied().collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert_eq!(distinct.len(), rows.min(columns));
+      };
+
+      let assigned = min_cost(rows, columns, |i, j| cost[i][j]);
+      check(&assigned);
+      let total = (0..rows).filter_map(|i| Some(cost[i][assigned[i]?])).sum::<i64>();
+      assert_eq!(total, best(&cost, columns, 0, 0), "{cost:?}");
+
+      let assigned = max_weight(rows, columns, |i, j| -cost[i][j]);
+      check(&assigned);
+      assert_eq!((0..rows).filter_map(|i| Some(cost[i][assigned[i]?])).sum::<i64>(), total);
+    }
+  }
+}
@@ -0,0 +1,368 @@
+//! Bandwidth allocation: choosing intervals that each use up some of a shared capacity while they run (bandwidth on a
+//! link, memory on a host, seats in a room), so that the intervals running at any one time fit within it.
+//!
+//! With every demand equal to one unit, this is scheduling on [`k_machines`](crate::k_machines), `k` the capacity. In
+//! general it is NP-hard (with every interval running at once, it is a [`knapsack`](crate::knapsack) problem), so
+//! [`exact`] takes time exponential in how many selected intervals run together, and [`approximate`] is polynomial,
+//! with a guarantee.
+//!
+//! Demands and the capacity are whole units (they must convert losslessly into `u64`), given for each interval by a
+//! closure over its index. Several resources at once (CPUs, memory, crew) are given as arrays, to [`exact_multi`] and
+//! [`approximate_multi`]. As in [`unsorted`](crate::unsorted), intervals touching end to start don't run together.
+//! The solvers return the indices of the selected intervals, ascending.
+
+use std::{collections::{btree_map::Entry, BTreeMap}, ops::Add};
+use crate::{interval_graph, traits};
+
+/// Whether intervals `a` and `b` run together (an empty interval sitting on another's start or end doesn't).
+fn overlap<Time: Ord>(a: &impl traits::Interval<Time>, b: &impl traits::Interval<Time>) -> bool {
+  !(a.end() <= b.start() || b.end() <= a.start())
+}
+
+/// Whether `used` more fits within `capacity`, once `load` is used, in every dimension.
+fn fits<const D: usize>(used: &[u64; D], load: &[u64; D], capacity: &[u64; D]) -> bool {
+  used.iter().zip(load).zip(capacity).all(|((used, load), capacity)| *used <= capacity - load)
+}
+
+/// The loads `a` and `b` together, in every dimension.
+fn add<const D: usize>(a: &[u64; D], b: &[u64; D]) -> [u64; D] {
+  std::array::from_fn(|r| a[r] + b[r])
+}
+
+/// A choice reaching some state: the heaviest, and the choice before it, should the interval `taken` have been.
+struct Choice<Weight> {
+  weight: Weight,
+  taken:  Option<usize>,
+  before: Option<usize>
+}
+
+/// Selects the intervals of maximum total weight whose demands fit within `capacity` at all times, interval `i`
+/// demanding `demand(i)` units. Exact.
+///
+/// By increasing start time, each interval is taken or not, from every state reached: the ends and demands of the
+/// selected intervals still running. Of the choices reaching a state, only the heaviest is kept. The number of states
+/// is exponential in the greatest number of selected intervals running together, which is at most the capacity
+/// (with demands of at least one unit): `O(n mᶜ)` for `n` intervals, at most `m` of them overlapping at once, and
+/// capacity `C`. Fast for small capacities, or when few intervals overlap; see [`approximate`] otherwise.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // reservations of a 10 Mbit/s link, each needing some of it
+/// let reservations: Vec<WeightedInterval<u32, u8>> = vec![
+///   (0, 4, 5).into(),
+///   (1, 5, 6).into(),
+///   (2, 6, 4).into(),
+///   (4, 8, 3).into()
+/// ];
+/// let mbits = [4u8, 6, 5, 5];
+///
+/// // the second leaves room for the first alone, which the third and fourth are worth more than
+/// assert_eq!(bandwidth::exact(&reservations, |i| mbits[i], 10u8), vec![0, 2, 3]);
+/// ```
+#[must_use]
+pub fn exact<Weight, Size, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  demand:    impl Fn(usize) -> Size,
+  capacity:  Size
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Size: Into<u64>,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  exact_multi(intervals, |i| [demand(i)], [capacity])
+}
+
+/// Like [`exact`], but for several resources at once (CPUs, memory, crew), interval `i` demanding `demand(i)[r]` units
+/// of resource `r`, of which there are `capacity[r]`. The states grow with the number of resources, as selected
+/// intervals may run together as long as each resource has room for them.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // jobs on a host with 4 CPUs and 8 GB of memory
+/// let jobs: Vec<WeightedInterval<u32, u8>> = vec![(0, 4, 5).into(), (1, 5, 4).into(), (2, 6, 3).into()];
+/// let needs = [[2u8, 6], [2, 2], [1, 4]];
+///
+/// // the first and third have CPUs enough between them, but not memory
+/// assert_eq!(bandwidth::exact_multi(&jobs, |i| needs[i], [4u8, 8]), vec![0, 1]);
+/// ```
+#[must_use]
+pub fn exact_multi<Weight, Size, Time, Interval, InputContainer, const D: usize>(
+  intervals: InputContainer,
+  demand:    impl Fn(usize) -> [Size; D],
+  capacity:  [Size; D]
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Size: Into<u64>,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  let capacity = capacity.map(Into::into);
+
+  // empty intervals go before the others starting with them, having ended by the time those start
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].start(), intervals[i].end())));
+
+  // keeps the heavier of the choices reaching a state
+  fn keep<Weight: Ord, State: Ord>(states: &mut BTreeMap<State, usize>, choices: &[Choice<Weight>], state: State, choice: usize) {
+    match states.entry(state) {
+      Entry::Vacant(entry) => { entry.insert(choice); },
+      Entry::Occupied(mut entry) => if choices[choice].weight > choices[*entry.get()].weight { entry.insert(choice); }
+    }
+  }
+
+  // states list the running intervals by end, those taking up no capacity left out
+  let mut choices = vec![Choice { weight: Weight::default(), taken: None, before: None }];
+  let mut states: BTreeMap<Vec<(Time, [u64; D])>, usize> = BTreeMap::from([(vec![], 0)]);
+  phase!("memoize", order.len(), for &i in &order {
+    let (start, end, used) = (intervals[i].start(), intervals[i].end(), demand(i).map(Into::into));
+
+    for (mut state, choice) in std::mem::take(&mut states) {
+      let ended = state.partition_point(|(end, _)| *end <= start);
+      state.drain(..ended);
+
+      let load = state.iter().fold([0; D], |load, (_, used)| add(&load, used));
+      if fits(&used, &load, &capacity) {
+        let mut taken = state.clone();
+        if used != [0; D] {
+          let position = taken.partition_point(|(running, _)| *running <= end);
+          taken.insert(position, (end.clone(), used));
+        }
+        let weight = choices[choice].weight.clone() + intervals[i].weight();
+        choices.push(Choice { weight, taken: Some(i), before: Some(choice) });
+        keep(&mut states, &choices, taken, choices.len() - 1);
+      }
+      keep(&mut states, &choices, state, choice);
+    }
+  });
+
+  let mut selected = vec![];
+  let mut choice = states.into_values().max_by(|&a, &b| choices[a].weight.cmp(&choices[b].weight));
+  phase!("reconstruct", order.len(), while let Some(c) = choice {
+    selected.extend(choices[c].taken);
+    choice = choices[c].before;
+  });
+  selected.sort_unstable();
+  selected
+}
+
+/// Like [`exact`], but in polynomial time, selecting intervals of at least a quarter of the maximum total weight
+/// (local ratio). `O(n²)` in interval number. Weights must convert losslessly into `i128` (all primitive integers up
+/// to 64 bits do), and intervals weighing nothing (or less) are never selected.
+///
+/// Intervals demanding more than half the capacity are wide: no two of them run together, so the heaviest set of them
+/// is found exactly, as in [`unsorted`](crate::unsorted). The others are narrow, and are selected within a factor of 3
+/// of their best, as by Bar-Noy et al. (*A Unified Approach to Approximating Resource Allocation and Scheduling*,
+/// 2001). The heavier of the two is returned, and the best set is no heavier than their sum.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let reservations: Vec<WeightedInterval<u32, u8>> = vec![
+///   (0, 4, 5).into(),
+///   (1, 5, 6).into(),
+///   (2, 6, 4).into(),
+///   (4, 8, 3).into()
+/// ];
+/// let mbits = [4u8, 6, 5, 5];
+///
+/// let selected = bandwidth::approximate(&reservations, |i| mbits[i], 10u8);
+/// let weight: u32 = selected.iter().map(|&i| reservations[i].weight).sum();
+/// assert!(4 * weight >= 12);
+/// ```
+#[must_use]
+pub fn approximate<Weight, Size, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  demand:    impl Fn(usize) -> Size,
+  capacity:  Size
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone + Into<i128>,
+        Size: Into<u64>,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  approximate_multi(intervals, |i| [demand(i)], [capacity])
+}
+
+/// Like [`approximate`], but for `D` resources at once, as in [`exact_multi`], selecting intervals of at least
+/// `1 / (3D + 1)` of the maximum total weight. `O(n² D)` in interval number.
+///
+/// An interval is wide if it demands more than half of some resource, and no two intervals wide in the same resource
+/// run together, so the heaviest set of those is found exactly, one resource at a time. The narrow intervals are
+/// selected within a factor of `2D + 1` of their best, charging each the share of every resource it demands. The
+/// heaviest of these `D + 1` sets is returned.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<WeightedInterval<u32, u8>> = vec![(0, 4, 5).into(), (1, 5, 4).into(), (2, 6, 3).into()];
+/// let needs = [[2u8, 6], [2, 2], [1, 4]];
+///
+/// let selected = bandwidth::approximate_multi(&jobs, |i| needs[i], [4u8, 8]);
+/// let weight: u32 = selected.iter().map(|&i| jobs[i].weight).sum();
+/// assert!(7 * weight >= 9);
+/// ```
+#[must_use]
+pub fn approximate_multi<Weight, Size, Time, Interval, InputContainer, const D: usize>(
+  intervals: InputContainer,
+  demand:    impl Fn(usize) -> [Size; D],
+  capacity:  [Size; D]
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone + Into<i128>,
+        Size: Into<u64>,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  let capacity = capacity.map(Into::into);
+  let demands: Vec<[u64; D]> = (0..intervals.len()).map(|i| demand(i).map(Into::into)).collect();
+  let weights: Vec<i128> = intervals.iter().map(|i| i.weight().into()).collect();
+
+  // the first resource each interval is wide in
+  let wide_in = |i: usize| (0..D).find(|&r| demands[i][r] > capacity[r] / 2);
+  let (wide, narrow): (Vec<usize>, Vec<usize>) = (0..intervals.len())
+    .filter(|&i| weights[i] > 0 && fits(&demands[i], &[0; D], &capacity))
+    .partition(|&i| wide_in(i).is_some());
+
+  // no two intervals wide in the same resource fit together, so they are chosen as an independent set
+  let mut sets: Vec<Vec<usize>> = phase!("wide", wide.len(), (0..D)
+    .map(|r| {
+      let members: Vec<usize> = wide.iter().copied().filter(|&i| wide_in(i) == Some(r)).collect();
+      let bounds: Vec<(Time, Time)> = members.iter().map(|&i| (intervals[i].start(), intervals[i].end())).collect();
+      let weights: Vec<i128> = members.iter().map(|&i| weights[i]).collect();
+      interval_graph::max_weight_independent_set(&bounds, &weights).into_iter().map(|k| members[k]).collect()
+    })
+    .collect());
+
+  sets.push(phase!("narrow", narrow.len(), local_ratio(intervals, narrow, &demands, &weights, &capacity)));
+
+  let total = |set: &Vec<usize>| set.iter().map(|&i| weights[i]).sum::<i128>();
+  let mut selected = sets.into_iter().max_by_key(total).unwrap_or_default();
+  selected.sort_unstable();
+  selected
+}
+
+/// Selects from the `narrow` intervals (demanding at most half of each resource), within a factor of `2D + 1` of their
+/// best.
+fn local_ratio<Time, Interval, const D: usize>(
+  intervals:  &[Interval],
+  mut narrow: Vec<usize>,
+  demands:    &[[u64; D]],
+  weights:    &[i128],
+  capacity:   &[u64; D]
+) -> Vec<usize>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>
+{
+  // by increasing end time, each interval left with some weight is stacked, and that weight charged to it and to
+  // the later ones overlapping it, those in proportion to their shares of the resources. Any set that can't take
+  // another stacked interval is heavy enough in each charge
+  narrow.sort_by_key(|&i| intervals[i].end());
+  let share = |i: usize| (0..D).filter(|&r| capacity[r] > 0).map(|r| demands[i][r] as f64 / capacity[r] as f64).sum::<f64>();
+  let mut residual: Vec<f64> = narrow.iter().map(|&i| weights[i] as f64).collect();
+  let mut stack = vec![];
+  for (k, &i) in narrow.iter().enumerate() {
+    if residual[k] <= 0.0 { continue; }
+    stack.push(i);
+    let charge = 2.0 * residual[k];
+    for (l, &j) in narrow.iter().enumerate().skip(k + 1) {
+      if overlap(&intervals[i], &intervals[j]) { residual[l] -= charge * share(j); }
+    }
+  }
+
+  // unstacking, each interval is selected if it still fits. Time is cut into slots, alternating between the instants
+  // that intervals start and end at and the stretches in between: an interval takes up the stretches it spans and
+  // the instants inside, and an empty interval its instant, where empty intervals run one after the other
+  let mut times: Vec<Time> = narrow.iter().flat_map(|&i| [intervals[i].start(), intervals[i].end()]).collect();
+  times.sort_unstable();
+  times.dedup();
+  let slot = |t: &Time| 2 * times.binary_search(t).unwrap_or_else(|_| unreachable!());
+  let (mut load, mut instants) = (vec![[0u64; D]; 2 * times.len()], vec![[0u64; D]; times.len()]);
+
+  let mut selected = vec![];
+  while let Some(i) = stack.pop() {
+    let (from, to, used) = (slot(&intervals[i].start()), slot(&intervals[i].end()), &demands[i]);
+    if from == to {
+      if fits(used, &load[from], capacity) {
+        instants[from / 2] = std::array::from_fn(|r| instants[from / 2][r].max(used[r]));
+        selected.push(i);
+      }
+    } else if (from + 1..to).all(|s| fits(used, &if s % 2 == 0 { add(&load[s], &instants[s / 2]) } else { load[s] }, capacity)) {
+      for cell in &mut load[from + 1..to] { *cell = add(cell, used); }
+      selected.push(i);
+    }
+  }
+  selected
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{bandwidth::{approximate, approximate_multi, exact, exact_multi, overlap}, testing::{brute_force_best, Lcg}, WeightedInterval};
+
+  /// Whether the intervals in `set` fit within `capacity` at the start of each (where the load peaks).
+  fn fits<const D: usize>(intervals: &[WeightedInterval<u32, u8>], demands: &[[u8; D]], capacity: [u8; D], set: &[usize]) -> bool {
+    set.iter().all(|&k| {
+      let running: Vec<usize> = set.iter()
+        .copied()
+        .filter(|&l| l == k || (intervals[l].start <= intervals[k].start && overlap(&intervals[l], &intervals[k])))
+        .collect();
+      (0..D).all(|r| running.iter().map(|&l| u32::from(demands[l][r])).sum::<u32>() <= u32::from(capacity[r]))
+    })
+  }
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(67);
+
+    for _ in 0..400 {
+      let n = rng.next(10) as usize;
+      let intervals: Vec<WeightedInterval<u32, u8>> = (0..n)
+        .map(|_| { let start = rng.next(12) as u8; (start, start + rng.next(5) as u8, rng.next(20)).into() })
+        .collect();
+      let demands: Vec<[u8; 1]> = (0..n).map(|_| [rng.next(7) as u8]).collect();
+      let capacity = rng.next(9) as u8;
+
+      let weight = |set: &[usize]| set.iter().map(|&i| intervals[i].weight).sum::<u32>();
+      let best: u32 = brute_force_best(&intervals, |set| fits(&intervals, &demands, [capacity], set));
+
+      let selected = exact(&intervals, |i| demands[i][0], capacity);
+      assert!(fits(&intervals, &demands, [capacity], &selected), "{intervals:?} {demands:?} {capacity}");
+      assert_eq!(weight(&selected), best, "{intervals:?} {demands:?} {capacity}");
+      assert!(selected.windows(2).all(|pair| pair[0] < pair[1]));
+
+      let selected = approximate(&intervals, |i| demands[i][0], capacity);
+      assert!(fits(&intervals, &demands, [capacity], &selected), "{intervals:?} {demands:?} {capacity}");
+      assert!(4 * weight(&selected) >= best, "{intervals:?} {demands:?} {capacity}");
+      assert!(selected.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+  }
+
+  #[test]
+  fn several_resources_match_brute_force() {
+    let mut rng = Lcg::new(71);
+
+    for _ in 0..400 {
+      let n = rng.next(10) as usize;
+      let intervals: Vec<WeightedInterval<u32, u8>> = (0..n)
+        .map(|_| { let start = rng.next(12) as u8; (start, start + rng.next(5) as u8, rng.next(20)).into() })
+        .collect();
+      let demands: Vec<[u8; 2]> = (0..n).map(|_| [rng.next(7) as u8, rng.next(5) as u8]).collect();
+      let capacity = [rng.next(9) as u8, rng.next(7) as u8];
+
+      let weight = |set: &[usize]| set.iter().map(|&i| intervals[i].weight).sum::<u32>();
+      let best: u32 = brute_force_best(&intervals, |set| fits(&intervals, &demands, capacity, set));
+
+      let selected = exact_multi(&intervals, |i| demands[i], capacity);
+      assert!(fits(&intervals, &demands, capacity, &selected), "{intervals:?} {demands:?} {capacity:?}");
+      assert_eq!(weight(&selected), best, "{intervals:?} {demands:?} {capacity:?}");
+
+      let selected = approximate_multi(&intervals, |i| demands[i], capacity);
+      assert!(fits(&intervals, &demands, capacity, &selected), "{intervals:?} {demands:?} {capacity:?}");
+      assert!(7 * weight(&selected) >= best, "{intervals:?} {demands:?} {capacity:?}");
+      assert!(selected.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+  }
+}
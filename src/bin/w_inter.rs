@@ -0,0 +1,180 @@
+//! Solves a weighted interval scheduling problem read from a CSV or JSON file (behind the `cli` feature).
+
+use std::{fs::File, io::{self, BufWriter, Read, Write}, path::Path, process::ExitCode};
+use w_inter::{io as formats, unsorted, validate::InvertedPolicy, WeightedInterval};
+
+const USAGE: &str = "\
+Usage: w_inter [OPTIONS] [FILE]
+
+Reads intervals from FILE (or standard input when FILE is missing or `-`),
+and prints the optimal set along with its total weight.
+
+CSV input has one `start,end,weight` row per interval, JSON input is
+`{ \"intervals\": [ { \"start\": .., \"end\": .., \"weight\": .. }, .. ] }`.
+Times are signed and weights unsigned 64-bit integers.
+
+Options:
+  --format <csv|json>  input format (default: from the file extension, csv for standard input)
+  --no-headers         the CSV input has no header row
+  --inverted <reject|swap|skip>
+                       what to do with intervals starting after they end (default: reject)
+  --json               print the solution as JSON, rather than as CSV followed by the total
+  -h, --help           print this message
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format { Csv, Json }
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+  input:       Option<String>,
+  format:      Option<Format>,
+  headers:     bool,
+  inverted:    InvertedPolicy,
+  json_output: bool
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Options>, String> {
+  let mut options = Options { input: None, format: None, headers: true, inverted: InvertedPolicy::Reject, json_output: false };
+
+  let mut args = args.into_iter();
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "-h" | "--help" => return Ok(None),
+      "--no-headers"  => options.headers = false,
+      "--json"        => options.json_output = true,
+      "--format"      => options.format = Some(match args.next().as_deref() {
+        Some("csv")  => Format::Csv,
+        Some("json") => Format::Json,
+        Some(other)  => return Err(format!("unknown format {other:?}, expected `csv` or `json`")),
+        None         => return Err("`--format` needs a value".into())
+      }),
+      "--inverted"    => options.inverted = match args.next().as_deref() {
+        Some("reject") => InvertedPolicy::Reject,
+        Some("swap")   => InvertedPolicy::Swap,
+        Some("skip")   => InvertedPolicy::Skip,
+        Some(other)    => return Err(format!("unknown policy {other:?}, expected `reject`, `swap`, or `skip`")),
+        None           => return Err("`--inverted` needs a value".into())
+      },
+      flag if flag.starts_with("--") => return Err(format!("unknown option {flag:?}")),
+      path => {
+        if options.input.is_some() { return Err("only one input file can be given".into()); }
+        options.input = Some(path.into());
+      }
+    }
+  }
+
+  Ok(Some(options))
+}
+
+fn run(options: &Options, input: impl Read, output: impl Write) -> Result<(), Box<dyn std::error::Error>> {
+  let format = options.format.unwrap_or_else(|| {
+    let extension = options.input.as_deref().and_then(|p| Path::new(p).extension());
+    if extension.is_some_and(|e| e.eq_ignore_ascii_case("json")) { Format::Json } else { Format::Csv }
+  });
+
+  let mut problem: Vec<WeightedInterval<u64, i64>> = match format {
+    Format::Csv  => formats::csv::read_problem(input, options.headers)?,
+    Format::Json => formats::json::read_problem(input)?
+  };
+
+  if let Err(inverted) = options.inverted.apply(&mut problem) {
+    let more = match inverted.len() - 1 {
+      0    => String::new(),
+      more => format!(" (and {more} more inverted intervals)")
+    };
+    return Err(format!("{}{more}, see `--inverted`", inverted[0]).into());
+  }
+
+  // report in chronological order
+  let mut solution = unsorted(&problem);
+  solution.reverse();
+
+  let mut output = BufWriter::new(output);
+  if options.json_output {
+    formats::json::write_solution(&mut output, &solution)?;
+    writeln!(output)?;
+  }
+  else {
+    writeln!(output, "start,end,weight")?;
+    for i in &solution { writeln!(output, "{},{},{}", i.start, i.end, i.weight)?; }
+    writeln!(output, "total weight: {}", solution.iter().map(|i| i.weight).sum::<u64>())?;
+  }
+  output.flush()?;
+
+  Ok(())
+}
+
+fn main() -> ExitCode {
+  let options = match parse_args(std::env::args().skip(1)) {
+    Ok(Some(options)) => options,
+    Ok(None)          => { print!("{USAGE}"); return ExitCode::SUCCESS; }
+    Err(message)      => { eprint!("error: {message}\n\n{USAGE}"); return ExitCode::from(2); }
+  };
+
+  let result = match options.input.as_deref() {
+    None | Some("-") => run(&options, io::stdin().lock(), io::stdout().lock()),
+    Some(path)       => File::open(path)
+      .map_err(|e| format!("can't open {path:?}: {e}").into())
+      .and_then(|file| run(&options, file, io::stdout().lock()))
+  };
+
+  match result {
+    Ok(())     => ExitCode::SUCCESS,
+    Err(error) => { eprintln!("error: {error}"); ExitCode::FAILURE }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use w_inter::validate::InvertedPolicy;
+  use super::{parse_args, run, Format, Options};
+
+  fn args(a: &[&str]) -> Vec<String> { a.iter().map(|s| s.to_string()).collect() }
+
+  #[test]
+  fn arguments() {
+    assert_eq!(
+      parse_args(args(&["--format", "json", "--json", "problem.txt"])),
+      Ok(Some(Options {
+        input: Some("problem.txt".into()), format: Some(Format::Json), headers: true, inverted: InvertedPolicy::Reject, json_output: true
+      }))
+    );
+    assert_eq!(parse_args(args(&["--inverted", "skip"])).unwrap().unwrap().inverted, InvertedPolicy::Skip);
+    assert_eq!(parse_args(args(&["a.csv", "-h"])), Ok(None));
+    assert!(parse_args(args(&["--format", "xml"])).is_err());
+    assert!(parse_args(args(&["a.csv", "b.csv"])).is_err());
+  }
+
+  #[test]
+  fn csv_in_text_out() {
+    let options = parse_args(args(&["--no-headers"])).unwrap().unwrap();
+
+    let mut output = vec![];
+    run(&options, "0,1,2\n0,6,3\n1,4,5\n5,9,7\n".as_bytes(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "start,end,weight\n0,1,2\n1,4,5\n5,9,7\ntotal weight: 14\n");
+  }
+
+  #[test]
+  fn json_detected_by_extension() {
+    let options = parse_args(args(&["--json", "problem.JSON"])).unwrap().unwrap();
+
+    let mut output = vec![];
+    run(&options, r#"{"intervals":[{"start":-3,"end":2,"weight":1}]}"#.as_bytes(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "{\"total_weight\":1,\"intervals\":[{\"start\":-3,\"end\":2,\"weight\":1}]}\n");
+  }
+
+  #[test]
+  fn inverted_intervals() {
+    let problem = "0,1,2\n6,5,3\n";
+
+    let options = parse_args(args(&["--no-headers"])).unwrap().unwrap();
+    let error = run(&options, problem.as_bytes(), vec![]).unwrap_err();
+    assert_eq!(error.to_string(), "interval 1 starts after it ends, see `--inverted`");
+
+    let options = parse_args(args(&["--no-headers", "--inverted", "swap"])).unwrap().unwrap();
+    let mut output = vec![];
+    run(&options, problem.as_bytes(), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "start,end,weight\n0,1,2\n5,6,3\ntotal weight: 5\n");
+  }
+}
@@ -0,0 +1,316 @@
+use std::ops::Add;
+use crate::{search::predecessors, traits};
+
+/// Like [`sorted`](crate::sorted), but selects at most `k` intervals. `O(n log n + nk)` in interval number `n`.
+///
+/// - **`intervals` must be sorted ascending by interval end time.**
+/// - `memoization` is an existing buffer that will be written to, and must hold at least `n × min(k, n)` elements.
+///   There is no need to clear the contents between invocations.
+/// - `solution` is appended to, in descending end order.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let shifts: Vec<WeightedInterval<u32, u8>> = vec![
+///   (0, 4, 5).into(),
+///   (4, 8, 5).into(),
+///   (0, 8, 8).into(),
+///   (8, 12, 5).into()
+/// ];
+///
+/// let mut memo = vec![0; shifts.len() * 2];
+/// let mut solution = vec![];
+/// sorted_at_most_k(&shifts, 2, &mut memo, &mut solution);
+/// assert_eq!(solution, vec![(8, 12, 5).into(), (0, 8, 8).into()]);
+/// ```
+pub fn sorted_at_most_k<Weight, Time, Interval, InputContainer>(
+  intervals:   InputContainer,
+  k:           usize,
+  memoization: &mut [Weight],
+  solution:    &mut Vec<Interval>
+) where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  let n = intervals.len();
+  let k = k.min(n);
+  if k == 0 { return; }
+
+  let predecessors = predecessors(intervals);
+  let weight = |i: usize| intervals[i].weight();
+
+  // `memoization[b * n + i]`: the optimal weight using at most `b + 1` of the first `i + 1` intervals
+  let included = |memoization: &[Weight], b: usize, i: usize| match (b, predecessors[i]) {
+    (0, _) | (_, None) => weight(i),
+    (_, Some(p))       => weight(i) + memoization[(b - 1) * n + p].clone()
+  };
+
+  phase!("memoize", n, for b in 0..k {
+    memoization[b * n] = weight(0);
+    for i in 1..n {
+      let value = included(memoization, b, i).max(memoization[b * n + i - 1].clone());
+      memoization[b * n + i] = value;
+    }
+  });
+
+  phase!("reconstruct", n, {
+    let (mut b, mut j) = (k - 1, Some(n - 1));
+    while let Some(i) = j {
+      if i == 0 || included(memoization, b, i) > memoization[b * n + i - 1] {
+        solution.push(intervals[i].clone());
+        if b == 0 { break; }
+        b -= 1;
+        j = predecessors[i];
+      }
+      else { j = Some(i - 1); }
+    }
+  });
+}
+
+/// Like [`unsorted`](crate::unsorted), but selects at most `k` intervals. `O(n log n + nk)` in interval number `n`.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let shifts: Vec<WeightedInterval<u32, u8>> = vec![(8, 12, 5).into(), (0, 4, 5).into(), (4, 8, 5).into()];
+///
+/// assert_eq!(unsorted_at_most_k(&shifts, 3).len(), 3);
+/// assert_eq!(unsorted_at_most_k(&shifts, 1).len(), 1);
+/// ```
+#[must_use]
+pub fn unsorted_at_most_k<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  k:         usize
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let mut intervals = Vec::from(intervals.as_ref());
+  phase!("sort", intervals.len(), intervals.sort_unstable_by_key(|i| i.end()));
+
+  let mut memoization = match intervals.first() {
+    Some(i) => vec![i.weight(); intervals.len() * k.min(intervals.len())],
+    None    => vec![]
+  };
+  let mut optimal_solution = vec![];
+  sorted_at_most_k(&intervals, k, &mut memoization, &mut optimal_solution);
+  optimal_solution
+}
+
+/// Like [`sorted`](crate::sorted), but selects exactly `k` intervals, however light. `O(n log n + nk)` in interval number `n`.
+///
+/// The rules of `sorted` apply, **`intervals` must be sorted ascending by interval end time**.
+/// Returns `false` (leaving `solution` untouched) when no `k` intervals are compatible with one another.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let shifts: Vec<WeightedInterval<u32, u8>> = vec![(0, 4, 1).into(), (4, 8, 1).into(), (0, 8, 9).into(), (8, 12, 1).into()];
+///
+/// let mut solution = vec![];
+/// assert!(sorted_exactly_k(&shifts, 3, &mut solution));
+/// assert_eq!(solution, vec![(8, 12, 1).into(), (4, 8, 1).into(), (0, 4, 1).into()]);
+///
+/// assert!(!sorted_exactly_k(&shifts, 4, &mut solution));
+/// ```
+pub fn sorted_exactly_k<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  k:         usize,
+  solution:  &mut Vec<Interval>
+) -> bool
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  counted(intervals.as_ref(), k, false, solution)
+}
+
+/// Like [`sorted`](crate::sorted), but selects at least `k` intervals. `O(n log n + nk)` in interval number `n`.
+///
+/// The rules of `sorted` apply, **`intervals` must be sorted ascending by interval end time**.
+/// Returns `false` (leaving `solution` untouched) when no `k` intervals are compatible with one another.
+pub fn sorted_at_least_k<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  k:         usize,
+  solution:  &mut Vec<Interval>
+) -> bool
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // optimal sets are never empty (when there are intervals), so at least 1 is no bound at all
+  counted(intervals, k.max(intervals.len().min(1)), true, solution)
+}
+
+/// Like [`unsorted`](crate::unsorted), but selects exactly `k` intervals, however light.
+/// `O(n log n + nk)` in interval number `n`. `None` when no `k` intervals are compatible with one another.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // exactly three shifts per machine per day
+/// let shifts: Vec<WeightedInterval<u32, u8>> = vec![(16, 24, 1).into(), (0, 24, 9).into(), (0, 8, 1).into(), (8, 16, 1).into()];
+///
+/// assert_eq!(unsorted_exactly_k(&shifts, 3).map(|s| s.len()), Some(3));
+/// assert_eq!(unsorted_exactly_k(&shifts, 4), None);
+/// ```
+#[must_use]
+pub fn unsorted_exactly_k<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  k:         usize
+) -> Option<Vec<Interval>>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let mut intervals = Vec::from(intervals.as_ref());
+  phase!("sort", intervals.len(), intervals.sort_unstable_by_key(|i| i.end()));
+
+  let mut optimal_solution = vec![];
+  sorted_exactly_k(&intervals, k, &mut optimal_solution).then_some(optimal_solution)
+}
+
+/// Like [`unsorted`](crate::unsorted), but selects at least `k` intervals. `O(n log n + nk)` in interval number `n`.
+/// `None` when no `k` intervals are compatible with one another.
+#[must_use]
+pub fn unsorted_at_least_k<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  k:         usize
+) -> Option<Vec<Interval>>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let mut intervals = Vec::from(intervals.as_ref());
+  phase!("sort", intervals.len(), intervals.sort_unstable_by_key(|i| i.end()));
+
+  let mut optimal_solution = vec![];
+  sorted_at_least_k(&intervals, k, &mut optimal_solution).then_some(optimal_solution)
+}
+
+/// Solves over the sets of exactly `k` intervals (or, when `or_more`, of `k` or more), appending the optimal set to `solution`.
+/// `false` when there is no such set. With `k == 0`, the empty set is the only one considered.
+fn counted<Weight, Time, Interval>(
+  intervals: &[Interval],
+  k:         usize,
+  or_more:   bool,
+  solution:  &mut Vec<Interval>
+) -> bool
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone
+{
+  let n = intervals.len();
+  if k == 0 { return true; }
+  if k > n { return false; }
+
+  let predecessors = predecessors(intervals);
+  let weight = |i: usize| intervals[i].weight();
+
+  // `memoization[c * n + i]`: the optimal weight of sets of `c + 1` of the first `i + 1` intervals
+  // (of `k` or more for the last row, when `or_more`), `None` when there are none
+  let mut memoization: Vec<Option<Weight>> = vec![None; n * k];
+
+  // the ways interval `i` can end a set in row `c`, each as (weight, row of the rest of the set)
+  let endings = |memoization: &[Option<Weight>], c: usize, i: usize| {
+    let rest = |row: usize| predecessors[i].and_then(|p| memoization[row * n + p].clone()).map(|w| (weight(i) + w, Some(row)));
+    let alone  = (c == 0).then(|| (weight(i), None));
+    let fewer  = c.checked_sub(1).and_then(rest);
+    let longer = (or_more && c == k - 1).then(|| rest(c)).flatten();
+    [alone, fewer, longer].into_iter().flatten().max_by(|a, b| a.0.cmp(&b.0))
+  };
+
+  phase!("memoize", n, for c in 0..k {
+    for i in 0..n {
+      let included = endings(&memoization, c, i).map(|e| e.0);
+      let excluded = if i == 0 { None } else { memoization[c * n + i - 1].clone() };
+      memoization[c * n + i] = included.max(excluded);
+    }
+  });
+
+  if memoization[k * n - 1].is_none() { return false; }
+
+  phase!("reconstruct", n, {
+    let (mut c, mut j) = (k - 1, Some(n - 1));
+    while let Some(i) = j {
+      if i > 0 && memoization[c * n + i] == memoization[c * n + i - 1] { j = Some(i - 1); continue; }
+
+      solution.push(intervals[i].clone());
+      match endings(&memoization, c, i).and_then(|e| e.1) {
+        Some(row) => { c = row; j = predecessors[i]; },
+        None      => break
+      }
+    }
+  });
+
+  true
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{sorted_at_most_k, unsorted, unsorted_at_least_k, unsorted_at_most_k, unsorted_exactly_k, WeightedInterval};
+
+  fn total(solution: &[WeightedInterval<u8, u8>]) -> u8 { solution.iter().map(|i| i.weight).sum() }
+
+  #[test]
+  fn budgets() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (0, 6, 3).into(), (1, 4, 5).into(), (3, 5, 5).into(), (3, 8, 8).into(), (4, 7, 3).into(),
+      (5, 9, 7).into(), (6, 10, 3).into(), (8, 11, 4).into(), (0, 1, 2).into()
+    ];
+
+    assert!(unsorted_at_most_k(&intervals, 0).is_empty());
+    assert_eq!(total(&unsorted_at_most_k(&intervals, 1)), 8);
+    assert_eq!(total(&unsorted_at_most_k(&intervals, 2)), 12);
+    assert_eq!(total(&unsorted_at_most_k(&intervals, 3)), 14);
+    assert_eq!(unsorted_at_most_k(&intervals, 100), unsorted(&intervals));
+
+    for k in 1..5 { assert!(unsorted_at_most_k(&intervals, k).len() <= k); }
+
+    // one memo buffer, left dirty by each budget before the next
+    let mut sorted = intervals.clone();
+    sorted.sort_by_key(|i| i.end);
+    let (mut memo, mut solution) = (vec![u8::MAX; 4 * intervals.len()], vec![]);
+    for k in [3, 1, 4, 2] {
+      solution.clear();
+      sorted_at_most_k(&sorted, k, &mut memo, &mut solution);
+      assert_eq!(total(&solution), total(&unsorted_at_most_k(&intervals, k)));
+    }
+  }
+
+  #[test]
+  fn exact_and_minimum_counts() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (0, 6, 3).into(), (1, 4, 5).into(), (3, 5, 5).into(), (3, 8, 8).into(), (4, 7, 3).into(),
+      (5, 9, 7).into(), (6, 10, 3).into(), (8, 11, 4).into(), (0, 1, 2).into()
+    ];
+    let exactly  = |k| unsorted_exactly_k(&intervals, k).map(|s| (s.len(), total(&s)));
+    let at_least = |k| unsorted_at_least_k(&intervals, k).map(|s| (s.len(), total(&s)));
+
+    assert_eq!(exactly(0), Some((0, 0)));
+    assert_eq!(exactly(1), Some((1, 8)));
+    assert_eq!(exactly(3), Some((3, 14)));
+    assert_eq!(exactly(4), Some((4, 14))); // (0, 1), (1, 4), (4, 7), (8, 11)
+    assert_eq!(exactly(5), None);
+
+    assert_eq!(at_least(0).map(|s| s.1), Some(14));
+    assert_eq!(at_least(1).map(|s| s.1), Some(14));
+    assert_eq!(at_least(4), Some((4, 14)));
+    assert_eq!(at_least(5), None);
+  }
+
+  #[test]
+  fn at_least_takes_more_when_heavier() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 1, 1).into(), (1, 2, 1).into(), (2, 3, 1).into(), (0, 3, 2).into()];
+
+    assert_eq!(unsorted_at_least_k(&intervals, 1).map(|s| s.len()), Some(3));
+    assert_eq!(unsorted_exactly_k(&intervals, 1).map(|s| total(&s)), Some(2));
+    assert_eq!(unsorted_exactly_k(&intervals, 2).map(|s| total(&s)), Some(2));
+  }
+}
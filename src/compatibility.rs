@@ -0,0 +1,78 @@
+use std::ops::Add;
+
+/// Decides whether an interval ending at `end` may be followed by one starting at `start`.
+///
+/// Solvers search for compatible predecessors by bisection over end times, so `compatible` must be monotone:
+/// if an interval ending at `end` is compatible with `start`, so is every interval ending earlier.
+///
+/// Implemented for closures, so one-off rules need no new type:
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 3, 1).into(), (4, 6, 1).into()];
+///
+/// // at least 2 units of time between selected intervals (`MinGap(2)` does the same)
+/// let optimal = unsorted_with(&intervals, |end: &u8, start: &u8| end + 2 <= *start);
+/// assert_eq!(optimal.len(), 1);
+/// ```
+pub trait Compatibility<Time> {
+  fn compatible(&self, end: &Time, start: &Time) -> bool;
+}
+
+impl<Time, F: Fn(&Time, &Time) -> bool> Compatibility<Time> for F {
+  fn compatible(&self, end: &Time, start: &Time) -> bool { self(end, start) }
+}
+
+/// Intervals are half-open (`[start, end)`), so touching intervals (`end == start`) may both be selected.
+///
+/// This is what `sorted` and `unsorted` use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HalfOpen;
+
+impl<Time: Ord> Compatibility<Time> for HalfOpen {
+  fn compatible(&self, end: &Time, start: &Time) -> bool { end <= start }
+}
+
+/// Intervals are closed (`[start, end]`), so touching intervals (`end == start`) conflict.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let bookings: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 1).into(), (2, 4, 1).into()];
+///
+/// assert_eq!(unsorted_with(&bookings, HalfOpen).len(), 2);
+/// assert_eq!(unsorted_with(&bookings, Closed).len(), 1);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Closed;
+
+impl<Time: Ord> Compatibility<Time> for Closed {
+  fn compatible(&self, end: &Time, start: &Time) -> bool { end < start }
+}
+
+/// Selected intervals must be separated by at least a gap (setup, changeover, or travel time):
+/// an interval ending at `end` may only be followed by one starting at `end + gap` or later.
+///
+/// `end + gap` must not overflow `Time`.
+///
+/// ```rust
+/// # use w_inter::*;
+/// use std::time::{Duration, Instant};
+///
+/// let now = Instant::now();
+/// let at = |minutes: u64| now + Duration::from_secs(60 * minutes);
+/// let jobs: Vec<WeightedInterval<u8, Instant>> = vec![
+///   (at(0),  at(30), 2).into(),
+///   (at(35), at(60), 2).into(), // starts 5 minutes after the first ends
+///   (at(45), at(90), 3).into()
+/// ];
+///
+/// // changing over takes 10 minutes
+/// let optimal = unsorted_with(&jobs, MinGap(Duration::from_secs(600)));
+/// assert_eq!(optimal, vec![jobs[2], jobs[0]]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MinGap<Gap>(pub Gap);
+
+impl<Time: Ord + Clone + Add<Gap, Output = Time>, Gap: Clone> Compatibility<Time> for MinGap<Gap> {
+  fn compatible(&self, end: &Time, start: &Time) -> bool { end.clone() + self.0.clone() <= *start }
+}
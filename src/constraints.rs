@@ -0,0 +1,404 @@
+use std::{fmt, ops::Add};
+use crate::{solvers::solve_by, traits, Lexicographic};
+
+/// Two intervals that were both required, but overlap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MandatoryConflict {
+  /// Index (into the problem) of the interval ending first.
+  pub first:  usize,
+  /// Index (into the problem) of the interval overlapping it.
+  pub second: usize
+}
+
+impl fmt::Display for MandatoryConflict {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "mandatory intervals {} and {} overlap", self.first, self.second)
+  }
+}
+
+impl std::error::Error for MandatoryConflict { }
+
+/// Like [`unsorted`](crate::unsorted), but the intervals at the indices in `mandatory` are always selected.
+/// Everything overlapping them is left out, and the gaps between them are filled optimally. `O(n log n)` in interval number.
+///
+/// Fails if two mandatory intervals overlap. Out of bounds indices panic.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 4, 9).into(),
+///   (3, 5, 1).into(), // the board meeting
+///   (5, 9, 2).into()
+/// ];
+///
+/// assert_eq!(unsorted_with_mandatory(&intervals, &[1]), Ok(vec![(5, 9, 2).into(), (3, 5, 1).into()]));
+/// assert!(unsorted_with_mandatory(&intervals, &[0, 1]).is_err());
+/// ```
+pub fn unsorted_with_mandatory<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  mandatory: &[usize]
+) -> Result<Vec<Interval>, MandatoryConflict>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let mut required = vec![false; intervals.len()];
+  for &i in mandatory { required[i] = true; }
+
+  // mandatory intervals must form a chain, each ending by the time the next starts
+  let mut chain: Vec<usize> = (0..intervals.len()).filter(|&i| required[i]).collect();
+  chain.sort_by_key(|&i| intervals[i].end());
+  if let Some(w) = chain.windows(2).find(|w| intervals[w[0]].end() > intervals[w[1]].start()) {
+    return Err(MandatoryConflict { first: w[0], second: w[1] });
+  }
+
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  // selecting as many mandatory intervals as possible comes first, and all of them fit together
+  let weight      = |k: usize| Lexicographic(required[order[k]] as usize, intervals[order[k]].weight());
+  let predecessor = |k: usize| ends[..k].partition_point(|e| *e <= intervals[order[k]].start()).checked_sub(1);
+
+  let mut selected = vec![];
+  solve_by(order.len(), weight, predecessor, &mut selected);
+
+  Ok(selected.into_iter().map(|k| intervals[order[k]].clone()).collect())
+}
+
+/// Like [`unsorted`](crate::unsorted), but intervals for which `conflict` holds may not both be selected either,
+/// even when they don't overlap (say, two jobs needing the same tool).
+///
+/// `conflict(a, b)` receives indices into `intervals`, and is asked about each pair in both orders,
+/// so it only needs to hold in one. A list of pairs can be searched (`|a, b| pairs.contains(&(a, b))`).
+///
+/// This is exact, by branch and bound: whenever an optimal set contains a conflicting pair, both ways of leaving one
+/// of them out are solved. Each branch costs `O(n log n)`, and their number grows exponentially with the number of
+/// extra conflicts that matter, so keep those few.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 2, 3).into(), // needs the crane
+///   (2, 4, 3).into(), // needs the crane too
+///   (0, 4, 5).into()
+/// ];
+///
+/// assert_eq!(unsorted(&jobs).len(), 2);
+/// assert_eq!(unsorted_with_conflicts(&jobs, |a, b| (a, b) == (0, 1)), vec![(0, 4, 5).into()]);
+/// ```
+#[must_use]
+pub fn unsorted_with_conflicts<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  conflict:  impl Fn(usize, usize) -> bool
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+
+  let mut search = Search { intervals, order, conflict, excluded: vec![false; intervals.len()], best: None };
+  search.branch();
+
+  search.best.map(|(_, selected)| selected.into_iter().map(|i| intervals[i].clone()).collect()).unwrap_or_default()
+}
+
+/// Like [`unsorted`](crate::unsorted), but at most one interval per group is selected,
+/// as when each task can run in one of several candidate time slots.
+///
+/// Intervals sharing a group conflict with one another, see [`unsorted_with_conflicts`] (this problem is NP-hard in general,
+/// so expect exponential time when many heavy alternatives compete).
+///
+/// ```rust
+/// # use w_inter::*;
+/// // task "a" fits in two slots, task "b" in one
+/// let slots: Vec<TaggedInterval<u8, u8, &str>> = vec![
+///   (0, 2, 3, "a").into(),
+///   (2, 4, 3, "a").into(),
+///   (4, 6, 1, "b").into()
+/// ];
+///
+/// let tasks: Vec<&str> = unsorted_with_groups(&slots, |s| s.data).into_iter().map(|s| s.data).collect();
+/// assert_eq!(tasks, vec!["b", "a"]);
+/// ```
+#[must_use]
+pub fn unsorted_with_groups<Weight, Time, Group, Interval, InputContainer>(
+  intervals: InputContainer,
+  group:     impl Fn(&Interval) -> Group
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Group: Eq,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  let groups: Vec<Group> = intervals.iter().map(group).collect();
+
+  unsorted_with_conflicts(intervals, |a, b| groups[a] == groups[b])
+}
+
+/// A tree of groups over intervals, constraining which of them may be selected together (see [`unsorted_with_tree`]).
+///
+/// ```rust
+/// # use w_inter::*;
+/// // a task done in one slot, or in two parts, each in one of two slots
+/// let task = GroupTree::OneOf(vec![
+///   GroupTree::Interval(0),
+///   GroupTree::AnyOf(vec![
+///     GroupTree::OneOf(vec![GroupTree::Interval(1), GroupTree::Interval(2)]),
+///     GroupTree::OneOf(vec![GroupTree::Interval(3), GroupTree::Interval(4)])
+///   ])
+/// ]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GroupTree {
+  /// The interval at this index.
+  Interval(usize),
+  /// Any of these, as the parts of a task.
+  AnyOf(Vec<GroupTree>),
+  /// At most one of these, as the alternative ways of doing a task (all that is within the chosen one may be selected).
+  OneOf(Vec<GroupTree>)
+}
+
+/// Like [`unsorted`](crate::unsorted), but the selection must respect a `tree` of groups: of the children of each
+/// [`GroupTree::OneOf`], the intervals within at most one are selected. Intervals missing from the tree are
+/// unconstrained.
+///
+/// Two intervals can't both be selected exactly when the nearest group they are both within is a `OneOf`, so this is
+/// exact, by the branch and bound of [`unsorted_with_conflicts`] (the problem is NP-hard, as for
+/// [`unsorted_with_groups`], which is a `OneOf` per group, under an `AnyOf`).
+///
+/// ```rust
+/// # use w_inter::*;
+/// // get there by plane, or by car in two legs, and maybe see the sights
+/// let plans: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 5).into(), (0, 4, 3).into(), (4, 8, 5).into(), (2, 6, 2).into()];
+/// let travel = GroupTree::OneOf(vec![
+///   GroupTree::Interval(0),
+///   GroupTree::AnyOf(vec![GroupTree::Interval(1), GroupTree::Interval(2)])
+/// ]);
+///
+/// // flying, then driving the second leg, overlaps nothing, but mixes the plans
+/// assert_eq!(unsorted(&plans), vec![plans[2], plans[0]]);
+/// assert_eq!(unsorted_with_tree(&plans, &travel), vec![plans[2], plans[1]]);
+/// ```
+///
+/// # Panics
+/// If an index in the tree is out of bounds, or appears more than once.
+#[must_use]
+pub fn unsorted_with_tree<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  tree:      &GroupTree
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  /// Records the path to each interval within `tree`: the groups it is within, from the root, and which of their
+  /// children it is in. Groups are numbered as they are reached, `one_of` telling which are `OneOf`s.
+  fn walk(tree: &GroupTree, path: &mut Vec<(usize, usize)>, paths: &mut [Option<Vec<(usize, usize)>>], one_of: &mut Vec<bool>) {
+    match tree {
+      GroupTree::Interval(i) => {
+        assert!(paths[*i].is_none(), "interval {i} appears in the tree more than once");
+        paths[*i] = Some(path.clone());
+      },
+      GroupTree::AnyOf(children) | GroupTree::OneOf(children) => {
+        let group = one_of.len();
+        one_of.push(matches!(tree, GroupTree::OneOf(_)));
+        for (child, subtree) in children.iter().enumerate() {
+          path.push((group, child));
+          walk(subtree, path, paths, one_of);
+          path.pop();
+        }
+      }
+    }
+  }
+
+  let (mut paths, mut one_of) = (vec![None; intervals.len()], vec![]);
+  walk(tree, &mut vec![], &mut paths, &mut one_of);
+
+  // where the paths part, they are in different children of the same group
+  unsorted_with_conflicts(intervals, |a, b| match (&paths[a], &paths[b]) {
+    (Some(a), Some(b)) => a.iter().zip(b).find(|(a, b)| a != b).is_some_and(|((group, _), _)| one_of[*group]),
+    _ => false
+  })
+}
+
+/// Branch and bound state of `unsorted_with_conflicts`.
+struct Search<'a, Weight, Interval, Conflict> {
+  intervals: &'a [Interval],
+  order:     Vec<usize>,
+  conflict:  Conflict,
+  excluded:  Vec<bool>,
+  best:      Option<(Weight, Vec<usize>)>
+}
+
+impl<Weight, Interval, Conflict> Search<'_, Weight, Interval, Conflict>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Conflict: Fn(usize, usize) -> bool
+{
+  /// Solves leaving the excluded intervals out, then branches on a conflicting pair in the solution (if there's one).
+  fn branch<Time: Ord>(&mut self) where Interval: traits::Interval<Time> + traits::Weighted<Weight> {
+    let intervals = self.intervals;
+    let kept: Vec<usize> = self.order.iter().copied().filter(|&i| !self.excluded[i]).collect();
+    let ends: Vec<Time> = kept.iter().map(|&i| intervals[i].end()).collect();
+
+    let weight      = |k: usize| intervals[kept[k]].weight();
+    let predecessor = |k: usize| ends[..k].partition_point(|e| *e <= intervals[kept[k]].start()).checked_sub(1);
+
+    let mut selected = vec![];
+    solve_by(kept.len(), weight, predecessor, &mut selected);
+    let selected: Vec<usize> = selected.into_iter().map(|k| kept[k]).collect();
+
+    // the weight of this relaxation bounds what any branch below can achieve
+    let Some(total) = selected.iter().map(|&i| intervals[i].weight()).reduce(|a, b| a + b) else { return; };
+    if self.best.as_ref().is_some_and(|(best, _)| total <= *best) { return; }
+
+    let conflicting = selected.iter().enumerate()
+      .flat_map(|(x, &a)| selected[x + 1..].iter().map(move |&b| (a, b)))
+      .find(|&(a, b)| (self.conflict)(a, b) || (self.conflict)(b, a));
+
+    match conflicting {
+      None         => self.best = Some((total, selected)),
+      Some((a, b)) => for leave_out in [a, b] {
+        self.excluded[leave_out] = true;
+        self.branch();
+        self.excluded[leave_out] = false;
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{testing::{brute_force_best, pairwise, Lcg}, unsorted, unsorted_with_conflicts, unsorted_with_groups, unsorted_with_mandatory, unsorted_with_tree};
+  use crate::{GroupTree, MandatoryConflict, TaggedInterval, WeightedInterval};
+
+  #[test]
+  fn gaps_are_filled_optimally() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (0, 6, 3).into(), (1, 4, 5).into(), (3, 5, 5).into(), (3, 8, 8).into(), (4, 7, 3).into(),
+      (5, 9, 7).into(), (6, 10, 3).into(), (8, 11, 4).into(), (0, 1, 2).into()
+    ];
+
+    assert_eq!(unsorted_with_mandatory(&intervals, &[]), Ok(unsorted(&intervals)));
+    assert_eq!(
+      unsorted_with_mandatory(&intervals, &[4, 0]),
+      Err(MandatoryConflict { first: 0, second: 4 })
+    );
+
+    // forcing (4, 7) in leaves (0, 1), (1, 4) before it and (8, 11) after it
+    let solution = unsorted_with_mandatory(&intervals, &[4]).unwrap();
+    assert_eq!(solution, vec![intervals[7], intervals[4], intervals[1], intervals[8]]);
+  }
+
+  #[test]
+  fn light_mandatory_intervals() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 10, 100).into(), (4, 5, 0).into(), (4, 5, 0).into()];
+    assert_eq!(unsorted_with_mandatory(&intervals, &[2]), Ok(vec![intervals[2]]));
+    assert!(unsorted_with_mandatory(&intervals, &[1, 2]).is_err());
+  }
+
+  #[test]
+  fn extra_conflicts() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (0, 6, 3).into(), (1, 4, 5).into(), (3, 5, 5).into(), (3, 8, 8).into(), (4, 7, 3).into(),
+      (5, 9, 7).into(), (6, 10, 3).into(), (8, 11, 4).into(), (0, 1, 2).into()
+    ];
+    let total = |s: Vec<WeightedInterval<u8, u8>>| s.iter().map(|i| i.weight).sum::<u8>();
+
+    assert_eq!(unsorted_with_conflicts(&intervals, |_, _| false), unsorted(&intervals));
+
+    // every optimal set of weight 14 contains (0, 1), which now conflicts with everything
+    assert_eq!(total(unsorted_with_conflicts(&intervals, |a, _| a == 8)), 12);
+
+    // (1, 4) and (5, 9) may no longer go together, but there are other ways to 14
+    let pairs = [(1, 5)];
+    assert_eq!(total(unsorted_with_conflicts(&intervals, |a, b| pairs.contains(&(a, b)))), 14);
+
+    // everything conflicts with everything: the heaviest interval alone
+    assert_eq!(unsorted_with_conflicts(&intervals, |_, _| true), vec![intervals[3]]);
+    assert!(unsorted_with_conflicts(Vec::<WeightedInterval<u8, u8>>::new(), |_, _| true).is_empty());
+  }
+
+  #[test]
+  fn one_per_group() {
+    // three tasks, each with alternative slots
+    let slots: Vec<TaggedInterval<u8, u8, char>> = vec![
+      (0, 3, 4, 'a').into(), (3, 6, 4, 'a').into(), (6, 9, 4, 'a').into(),
+      (0, 3, 3, 'b').into(), (3, 6, 3, 'b').into(),
+      (6, 9, 2, 'c').into()
+    ];
+
+    let solution = unsorted_with_groups(&slots, |s| s.data);
+    let mut tasks: Vec<char> = solution.iter().map(|s| s.data).collect();
+    tasks.sort();
+    assert_eq!(tasks, vec!['a', 'b', 'c']);
+    assert_eq!(solution.iter().map(|s| s.weight).sum::<u8>(), 9);
+
+    // in groups of their own, intervals are unconstrained
+    let intervals: Vec<WeightedInterval<u8, u8>> = slots.into_iter().map(|s| s.into_parts().0).collect();
+    assert_eq!(unsorted_with_groups(&intervals, |i| *i), unsorted(&intervals));
+  }
+
+  #[test]
+  fn tree_matches_brute_force() {
+    let mut rng = Lcg::new(83);
+
+    /// A random tree over `leaves`.
+    fn build(leaves: &[usize], rng: &mut Lcg) -> GroupTree {
+      if leaves.len() == 1 && rng.next(3) != 0 { return GroupTree::Interval(leaves[0]); }
+      let mut children = vec![];
+      let mut rest = leaves;
+      while !rest.is_empty() {
+        let (part, others) = rest.split_at(1 + rng.next(rest.len() as u32) as usize);
+        children.push(build(part, rng));
+        rest = others;
+      }
+      if rng.next(2) == 0 { GroupTree::AnyOf(children) } else { GroupTree::OneOf(children) }
+    }
+
+    /// The intervals within `tree`, and whether the `set` keeps to its groups.
+    fn check(tree: &GroupTree, set: u32) -> (u32, bool) {
+      match tree {
+        GroupTree::Interval(i) => (1 << i, true),
+        GroupTree::AnyOf(children) | GroupTree::OneOf(children) => {
+          let checked: Vec<(u32, bool)> = children.iter().map(|child| check(child, set)).collect();
+          let within = checked.iter().fold(0, |within, (child, _)| within | child);
+          let chosen = checked.iter().filter(|(child, _)| child & set != 0).count();
+          let kept = checked.iter().all(|(_, kept)| *kept) && (matches!(tree, GroupTree::AnyOf(_)) || chosen <= 1);
+          (within, kept)
+        }
+      }
+    }
+
+    for _ in 0..200 {
+      let n = 1 + rng.next(9) as usize;
+      let intervals: Vec<TaggedInterval<u32, u8, usize>> = (0..n)
+        .map(|i| { let start = rng.next(10) as u8; (start, start + 1 + rng.next(4) as u8, rng.next(10), i).into() })
+        .collect();
+      let leaves: Vec<usize> = (0..n).filter(|_| rng.next(5) != 0).collect();
+      let tree = if leaves.is_empty() { GroupTree::AnyOf(vec![]) } else { build(&leaves, &mut rng) };
+
+      let best: u32 = brute_force_best(&intervals, |set| {
+        check(&tree, set.iter().fold(0, |mask, i| mask | 1 << i)).1
+          && pairwise(set, |a, b| intervals[a].end <= intervals[b].start || intervals[b].end <= intervals[a].start)
+      });
+
+      let solution = unsorted_with_tree(&intervals, &tree);
+      assert_eq!(solution.iter().map(|i| i.weight).sum::<u32>(), best, "{intervals:?} {tree:?}");
+      assert!(check(&tree, solution.iter().fold(0, |set, i| set | 1 << i.data)).1, "{intervals:?} {tree:?}");
+    }
+  }
+}
@@ -0,0 +1,276 @@
+use std::{fmt, ops::{Add, Range}};
+use crate::traits;
+
+/// A part of the window to cover that no interval covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Uncovered<Time> {
+  /// Where the uncovered part starts (the earliest uncovered time in the window).
+  pub from: Time,
+  /// Where the uncovered part ends: at the start of the next interval, or at the end of the window.
+  pub to:   Time
+}
+
+impl<Time: fmt::Debug> fmt::Display for Uncovered<Time> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "no interval covers {:?}..{:?}", self.from, self.to)
+  }
+}
+
+impl<Time: fmt::Debug> std::error::Error for Uncovered<Time> { }
+
+/// Selects the intervals of minimum total weight covering `window` (every time in it lies in some selected interval),
+/// returned in chronological order. `O(n log n)` in interval number.
+///
+/// Like the intervals, the window is half-open. Fails with the first uncovered part of the window if the intervals
+/// don't cover it all. Weights are assumed non-negative (as with a negative weight, selecting more is always cheaper).
+///
+/// ```rust
+/// # use w_inter::*;
+/// // opening hours are 9 to 17, shifts cost their wages
+/// let shifts: Vec<WeightedInterval<u32, u8>> = vec![
+///   (8, 13, 50).into(),
+///   (9, 17, 120).into(),
+///   (12, 17, 50).into(),
+///   (13, 18, 40).into()
+/// ];
+///
+/// assert_eq!(min_weight_cover(&shifts, 9..17), Ok(vec![shifts[0], shifts[3]]));
+/// assert_eq!(min_weight_cover(&shifts, 7..17), Err(Uncovered { from: 7, to: 8 }));
+/// ```
+pub fn min_weight_cover<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  window:    Range<Time>
+) -> Result<Vec<Interval>, Uncovered<Time>>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  if let Some(gap) = first_gap(intervals, &window) { return Err(gap); }
+  if window.is_empty() { return Ok(vec![]); }
+
+  // only intervals reaching into the window help
+  let mut order: Vec<usize> = (0..intervals.len())
+    .filter(|&i| intervals[i].end() > window.start && intervals[i].start() < window.end)
+    .collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  // the cheapest chain covering the window from its start up to the end of each interval, ending with it
+  let mut costs = MinTree::new(order.len());
+  let mut previous: Vec<Option<usize>> = vec![None; order.len()];
+  let mut best: Option<(Weight, usize)> = None;
+
+  phase!("memoize", order.len(), for k in 0..order.len() {
+    let interval = &intervals[order[k]];
+
+    let cost = if interval.start() <= window.start {
+      interval.weight()
+    } else {
+      let reaching = ends[..k].partition_point(|e| *e < interval.start());
+      let Some((cost, j)) = costs.min(reaching..k) else { continue };
+      previous[k] = Some(j);
+      cost + interval.weight()
+    };
+
+    if interval.end() >= window.end { best = lesser(best, Some((cost.clone(), k))); }
+    costs.set(k, cost);
+  });
+
+  let mut k = best.map(|(_, k)| k);
+  let mut cover = vec![];
+  phase!("reconstruct", order.len(), while let Some(j) = k {
+    cover.push(intervals[order[j]].clone());
+    k = previous[j];
+  });
+
+  cover.reverse();
+  Ok(cover)
+}
+
+/// Selects as few intervals as possible covering `window` (every time in it lies in some selected interval), returned
+/// in chronological order. `O(n log n)` in interval number.
+///
+/// Like the intervals, the window is half-open. Fails with the first uncovered part of the window if the intervals
+/// don't cover it all. Greedy: from the start of the window, each step selects the interval reaching the furthest
+/// among those starting early enough to continue the cover.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let shifts: Vec<(u8, u8)> = vec![(8, 13), (9, 17), (12, 17), (13, 18), (16, 20)];
+///
+/// assert_eq!(min_count_cover(&shifts, 9..20), Ok(vec![(9, 17), (16, 20)]));
+/// assert_eq!(min_count_cover(&shifts, 9..21), Err(Uncovered { from: 20, to: 21 }));
+/// ```
+pub fn min_count_cover<Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  window:    Range<Time>
+) -> Result<Vec<Interval>, Uncovered<Time>>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].start()));
+
+  let mut cover = vec![];
+  let mut reach = window.start.clone();
+  let mut next = 0;
+  while reach < window.end {
+    // among the intervals starting within the cover so far, the one reaching the furthest
+    let mut furthest: Option<usize> = None;
+    while next < order.len() && intervals[order[next]].start() <= reach {
+      if furthest.is_none_or(|f| intervals[order[next]].end() > intervals[f].end()) { furthest = Some(order[next]); }
+      next += 1;
+    }
+
+    match furthest.filter(|&f| intervals[f].end() > reach) {
+      Some(f) => {
+        reach = intervals[f].end();
+        cover.push(intervals[f].clone());
+      },
+      None => {
+        let to = order.get(next).map_or(window.end.clone(), |&i| intervals[i].start().min(window.end.clone()));
+        return Err(Uncovered { from: reach, to });
+      }
+    }
+  }
+
+  Ok(cover)
+}
+
+/// Finds the parts of `horizon` no interval covers, as ranges in chronological order. `O(n log n)` in interval number.
+///
+/// Like the intervals, the horizon and the gaps are half-open, so intervals touching end to start leave no gap between
+/// them, and zero-length intervals close no gap.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<WeightedInterval<u32, u8>> = vec![
+///   (9, 12, 5).into(),
+///   (10, 14, 3).into(),
+///   (14, 15, 1).into(),
+///   (16, 17, 2).into()
+/// ];
+///
+/// // the idle time left once the best schedule is run
+/// assert_eq!(gaps(unsorted(&jobs), 8..18), vec![8..9, 12..14, 15..16, 17..18]);
+/// ```
+#[must_use]
+pub fn gaps<Time, Interval, InputContainer>(intervals: InputContainer, horizon: Range<Time>) -> Vec<Range<Time>>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>,
+        InputContainer: AsRef<[Interval]>
+{
+  let mut bounds: Vec<(Time, Time)> = intervals.as_ref().iter()
+    .map(|i| (i.start(), i.end()))
+    .filter(|(start, end)| start < end)
+    .collect();
+  phase!("sort", bounds.len(), bounds.sort_unstable());
+
+  // how far the intervals starting so far cover the horizon without a break
+  let mut gaps = vec![];
+  let mut reach = horizon.start.clone();
+  for (start, end) in bounds {
+    if reach >= horizon.end { return gaps; }
+    if start > reach { gaps.push(reach.clone()..start.clone().min(horizon.end.clone())); }
+    reach = reach.max(end);
+  }
+
+  if reach < horizon.end { gaps.push(reach..horizon.end); }
+  gaps
+}
+
+/// The first part of `window` that no interval covers, if any.
+fn first_gap<Time, Interval>(intervals: &[Interval], window: &Range<Time>) -> Option<Uncovered<Time>>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>
+{
+  gaps(intervals, window.clone()).into_iter().next().map(|gap| Uncovered { from: gap.start, to: gap.end })
+}
+
+/// The lesser of two optional values, where `None` stands for "no value" (not for the least value).
+fn lesser<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+  match (a, b) {
+    (Some(a), Some(b)) => Some(a.min(b)),
+    (a, b)             => a.or(b)
+  }
+}
+
+/// Minimum of the values set so far in a range of slots (and the slot holding it), by segment tree.
+struct MinTree<Weight> {
+  size:  usize,
+  nodes: Vec<Option<(Weight, usize)>>
+}
+
+impl<Weight: Ord + Clone> MinTree<Weight> {
+  fn new(size: usize) -> Self { Self { size, nodes: vec![None; 2 * size] } }
+
+  fn set(&mut self, slot: usize, value: Weight) {
+    let mut node = slot + self.size;
+    self.nodes[node] = Some((value, slot));
+    while node > 1 {
+      node /= 2;
+      self.nodes[node] = lesser(self.nodes[2 * node].clone(), self.nodes[2 * node + 1].clone());
+    }
+  }
+
+  fn min(&self, slots: Range<usize>) -> Option<(Weight, usize)> {
+    let (mut low, mut high) = (slots.start + self.size, slots.end + self.size);
+    let mut min = None;
+    while low < high {
+      if low % 2 == 1 { min = lesser(min, self.nodes[low].clone()); low += 1; }
+      if high % 2 == 1 { high -= 1; min = lesser(min, self.nodes[high].clone()); }
+      low /= 2;
+      high /= 2;
+    }
+    min
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{gaps, min_count_cover, min_weight_cover, Uncovered, WeightedInterval};
+
+  #[test]
+  fn cheapest_cover() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = vec![
+      (0, 3, 4).into(), (2, 5, 1).into(), (4, 8, 2).into(), (1, 8, 9).into(),
+      (7, 10, 1).into(), (3, 7, 1).into(), (0, 2, 1).into(), (9, 9, 0).into()
+    ];
+
+    assert_eq!(min_weight_cover(&intervals, 0..10), Ok(vec![intervals[6], intervals[1], intervals[5], intervals[4]]));
+    assert_eq!(min_weight_cover(&intervals, 5..6), Ok(vec![intervals[5]]));
+    assert_eq!(min_weight_cover(&intervals, 6..6), Ok(vec![]));
+    assert_eq!(min_weight_cover(&intervals, 0..11), Err(Uncovered { from: 10, to: 11 }));
+  }
+
+  #[test]
+  fn uncovered_parts() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = vec![(0, 3, 1).into(), (2, 4, 1).into(), (6, 9, 1).into()];
+
+    assert_eq!(min_weight_cover(&intervals, 1..8), Err(Uncovered { from: 4, to: 6 }));
+    assert_eq!(min_weight_cover(&intervals, 1..5), Err(Uncovered { from: 4, to: 5 }));
+    assert_eq!(min_count_cover(&intervals, 1..8), Err(Uncovered { from: 4, to: 6 }));
+    assert_eq!(min_count_cover(&intervals, 1..5), Err(Uncovered { from: 4, to: 5 }));
+    assert_eq!(min_count_cover(&intervals, 5..5), Ok(vec![]));
+    assert_eq!(gaps(&intervals, 1..8), vec![4..6]);
+    assert_eq!(gaps(&intervals, 0..12), vec![4..6, 9..12]);
+    assert_eq!(gaps(&intervals, 10..12), vec![10..12]);
+    assert_eq!(gaps(&intervals, 1..3), vec![]);
+    assert_eq!(gaps([(5, 5)], 4..6), vec![4..6]);
+    assert_eq!(Uncovered { from: 4, to: 6 }.to_string(), "no interval covers 4..6");
+  }
+
+  #[test]
+  fn fewest_intervals() {
+    let intervals: Vec<(u32, u32)> = vec![(0, 3), (2, 5), (4, 8), (1, 8), (7, 10), (3, 7), (0, 2), (9, 9), (8, 9)];
+
+    assert_eq!(min_count_cover(&intervals, 0..10), Ok(vec![(0, 3), (1, 8), (7, 10)]));
+    assert_eq!(min_count_cover(&intervals, 8..9), Ok(vec![(7, 10)]));
+    assert_eq!(min_count_cover(&intervals, 0..11), Err(Uncovered { from: 10, to: 11 }));
+  }
+}
@@ -0,0 +1,88 @@
+use std::ops::Add;
+use crate::{solvers::solve_by, traits};
+
+/// Like [`unsorted`](crate::unsorted), but on a circular timeline (e.g. the hours of a recurring day), where an interval
+/// starting after it ends wraps around: `(22, 6)` runs from 22 to the end of the cycle, then from its beginning to 6.
+/// `O((m + 1) n log n)` in interval number `n` and wrapping interval number `m`.
+///
+/// All wrapping intervals overlap at the point where the cycle closes, so at most one of them is selected: the solver
+/// tries no wrapping interval, then each in turn, solving what's left of the cycle around it as a line. No period needs
+/// to be given, as whether an interval wraps is all that matters.
+///
+/// The selection is in descending order of end, counting the end of a wrapping interval as being in the next cycle
+/// (so that it comes first). Weights are assumed non-negative.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // the nightly backup wraps midnight
+/// let daily: Vec<WeightedInterval<u32, u8>> = vec![
+///   (22, 6, 5).into(),
+///   (1, 3, 4).into(),
+///   (6, 18, 8).into(),
+///   (17, 23, 2).into()
+/// ];
+///
+/// assert_eq!(unsorted_cyclic(&daily), vec![daily[0], daily[2]]);
+/// ```
+#[must_use]
+pub fn unsorted_cyclic<Weight, Time, Interval, InputContainer>(intervals: InputContainer) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let (wrapping, mut linear): (Vec<usize>, Vec<usize>) = (0..intervals.len()).partition(|&i| intervals[i].start() > intervals[i].end());
+  phase!("sort", linear.len(), linear.sort_by_key(|&i| intervals[i].end()));
+
+  // the best selection among the (end ordered) `order`, and its weight
+  let solve = |order: &[usize]| -> (Option<Weight>, Vec<usize>) {
+    let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+    let mut selected = vec![];
+    solve_by(
+      order.len(),
+      |k| intervals[order[k]].weight(),
+      |k| ends[..k].partition_point(|e| *e <= intervals[order[k]].start()).checked_sub(1),
+      &mut selected
+    );
+
+    let selected: Vec<usize> = selected.into_iter().map(|k| order[k]).collect();
+    (selected.iter().map(|&i| intervals[i].weight()).reduce(|a, b| a + b), selected)
+  };
+
+  let mut best = solve(&linear);
+  for w in wrapping {
+    // what's left of the cycle runs from the end of the wrapping interval to its start
+    let (start, end) = (intervals[w].start(), intervals[w].end());
+    let inside: Vec<usize> = linear.iter().copied().filter(|&i| intervals[i].start() >= end && intervals[i].end() <= start).collect();
+
+    let (weight, mut selected) = solve(&inside);
+    let weight = weight.map_or(intervals[w].weight(), |weight| intervals[w].weight() + weight);
+    if best.0.as_ref().is_none_or(|best| weight > *best) {
+      selected.insert(0, w);
+      best = (Some(weight), selected);
+    }
+  }
+
+  best.1.into_iter().map(|i| intervals[i].clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{unsorted, unsorted_cyclic, WeightedInterval};
+
+  #[test]
+  fn at_most_one_wrapping() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (20, 4, 3).into(), (23, 1, 3).into(), (2, 8, 2).into(), (8, 20, 3).into(), (4, 9, 1).into(), (21, 23, 1).into()
+    ];
+    assert_eq!(unsorted_cyclic(&intervals), vec![intervals[1], intervals[5], intervals[3], intervals[2]]);
+
+    // with nothing wrapping, the cycle is a line
+    let line: Vec<WeightedInterval<u8, u8>> = intervals.iter().copied().filter(|i| i.start <= i.end).collect();
+    assert_eq!(unsorted_cyclic(&line), unsorted(&line));
+    assert!(unsorted_cyclic(Vec::<WeightedInterval<u8, u8>>::new()).is_empty());
+  }
+}
@@ -0,0 +1,145 @@
+//! Heaviest paths in directed acyclic graphs, and heaviest chains in partial orders.
+//!
+//! An optimal set of intervals is a heaviest chain in the order of intervals ending by the start of the next, so these
+//! solve the problem for any other relation deciding what may follow what, at the cost of looking at every pair.
+
+use std::ops::Add;
+
+/// Finds a heaviest path through a directed acyclic graph of `nodes` nodes and the given `edges` (pairs of nodes, from
+/// and to), node `v` weighing `weight(v)`. `O(V + E)` in node and edge number.
+///
+/// A path may start and end anywhere. Returns the nodes of the path, from the last back to the first (empty when there
+/// are no nodes).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let weights = [1, 2, 5, 3, 1];
+/// let edges = [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)];
+///
+/// assert_eq!(dag::heaviest_path(5, &edges, |v| weights[v]), vec![4, 3, 2, 0]);
+/// ```
+///
+/// # Panics
+/// If the edges form a cycle.
+#[must_use]
+pub fn heaviest_path<Weight>(nodes: usize, edges: &[(usize, usize)], weight: impl Fn(usize) -> Weight) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone
+{
+  let mut successors: Vec<Vec<usize>> = vec![vec![]; nodes];
+  let mut predecessors = vec![0; nodes];
+  for &(from, to) in edges {
+    successors[from].push(to);
+    predecessors[to] += 1;
+  }
+
+  // visit each node once all those before it have been
+  let mut order: Vec<usize> = (0..nodes).filter(|&v| predecessors[v] == 0).collect();
+  let mut k = 0;
+  while let Some(&v) = order.get(k) {
+    k += 1;
+    for &next in &successors[v] {
+      predecessors[next] -= 1;
+      if predecessors[next] == 0 { order.push(next); }
+    }
+  }
+  assert_eq!(order.len(), nodes, "the edges must not form a cycle");
+
+  // `best[v]` is the weight of the heaviest path ending at `v`, reached `through[v]`
+  let mut best: Vec<Weight> = (0..nodes).map(&weight).collect();
+  let mut through: Vec<Option<usize>> = vec![None; nodes];
+  phase!("memoize", nodes, for &v in &order {
+    for &next in &successors[v] {
+      let with = best[v].clone() + weight(next);
+      if with > best[next] {
+        best[next] = with;
+        through[next] = Some(v);
+      }
+    }
+  });
+
+  let mut path = vec![];
+  let mut node = (0..nodes).max_by_key(|&v| best[v].clone());
+  phase!("reconstruct", nodes, while let Some(v) = node {
+    path.push(v);
+    node = through[v];
+  });
+  path
+}
+
+/// Finds a heaviest chain of `count` elements, each of which `precedes` the next, element `i` weighing `weight(i)`.
+/// `O(n²)` in element number, asking `precedes` about every ordered pair.
+///
+/// `precedes` must be a strict partial order (or at least never go round in a cycle). Returns the elements of the
+/// chain, from the last back to the first.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // meetings that must be at least 2 apart, in a different room from the one before
+/// let meetings = [(0, 2, 'a', 3), (3, 5, 'a', 4), (4, 6, 'b', 2), (8, 9, 'a', 1)];
+///
+/// let chain = dag::heaviest_chain(meetings.len(), |i| meetings[i].3, |i, j| {
+///   meetings[i].1 + 2 <= meetings[j].0 && meetings[i].2 != meetings[j].2
+/// });
+/// assert_eq!(chain, vec![3, 2, 0]);
+/// ```
+///
+/// # Panics
+/// If `precedes` goes round in a cycle.
+#[must_use]
+pub fn heaviest_chain<Weight>(
+  count:    usize,
+  weight:   impl Fn(usize) -> Weight,
+  precedes: impl Fn(usize, usize) -> bool
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone
+{
+  let edges: Vec<(usize, usize)> = (0..count)
+    .flat_map(|i| (0..count).map(move |j| (i, j)))
+    .filter(|&(i, j)| i != j && precedes(i, j))
+    .collect();
+  heaviest_path(count, &edges, weight)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{dag::{heaviest_chain, heaviest_path}, testing::Lcg, unsorted, WeightedInterval};
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(61);
+
+    /// The heaviest path starting at `v`.
+    fn heaviest_from(v: usize, edges: &[(usize, usize)], weights: &[i32]) -> i32 {
+      weights[v] + edges.iter().filter(|e| e.0 == v).map(|e| heaviest_from(e.1, edges, weights).max(0)).max().unwrap_or(0)
+    }
+
+    for _ in 0..300 {
+      let nodes = rng.next(9) as usize;
+      let weights: Vec<i32> = (0..nodes).map(|_| rng.next(20) as i32 - 5).collect();
+      // edges go from lower numbered nodes to higher ones, relabeled so they don't in the input
+      let label: Vec<usize> = { let mut label: Vec<usize> = (0..nodes).collect(); label.sort_by_key(|_| rng.next(100)); label };
+      let edges: Vec<(usize, usize)> = (0..nodes)
+        .flat_map(|a| (a + 1..nodes).map(move |b| (a, b)))
+        .filter(|_| rng.next(3) == 0)
+        .map(|(a, b)| (label[a], label[b]))
+        .collect();
+      let relabeled: Vec<i32> = { let mut w = vec![0; nodes]; for v in 0..nodes { w[label[v]] = weights[v]; } w };
+
+      let path = heaviest_path(nodes, &edges, |v| relabeled[v]);
+      let expected = (0..nodes).map(|v| heaviest_from(v, &edges, &relabeled)).max();
+      assert_eq!(path.iter().map(|&v| relabeled[v]).sum::<i32>(), expected.unwrap_or(0), "{edges:?} {relabeled:?}");
+      assert_eq!(path.is_empty(), nodes == 0);
+      assert!(path.windows(2).all(|pair| edges.contains(&(pair[1], pair[0]))));
+    }
+  }
+
+  #[test]
+  fn chains_of_intervals_are_optimal_sets() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..80u32).map(|i| (i * 13 % 61, i * 13 % 61 + 1 + i % 7, 1 + i % 9).into()).collect();
+
+    let chain = heaviest_chain(intervals.len(), |i| intervals[i].weight, |i, j| intervals[i].end <= intervals[j].start);
+    let expected = unsorted(&intervals).iter().map(|i| i.weight).sum::<u32>();
+    assert_eq!(chain.iter().map(|&i| intervals[i].weight).sum::<u32>(), expected);
+    assert!(chain.windows(2).all(|pair| intervals[pair[1]].end <= intervals[pair[0]].start));
+  }
+}
@@ -0,0 +1,159 @@
+use std::ops::Add;
+use crate::{solvers::reconstruct, traits};
+
+/// Keeps the optimum up to date as single intervals are inserted into, or removed from, the problem.
+///
+/// Intervals are kept sorted by end, and the memoization of each is the optimum of those up to it, so an edit only
+/// changes the memoization from the position edited on: repairing it is `O((n - p) log n)` for `n` intervals and an edit
+/// at position `p` (plus `O(n)` to shift the intervals), rather than the `O(n log n)` of solving anew. Edits near the
+/// end of the timeline are the cheapest.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let mut schedule = DynamicSolver::new(vec![
+///   WeightedInterval::new(0u8, 3u8, 5u32),
+///   WeightedInterval::new(2, 5, 4),
+///   WeightedInterval::new(4, 7, 3)
+/// ]);
+/// assert_eq!(schedule.optimal_weight(), Some(8));
+///
+/// let position = schedule.insert((5, 9, 6).into());
+/// assert_eq!(schedule.optimal_weight(), Some(11));
+///
+/// schedule.remove(position);
+/// assert_eq!(schedule.solution(), vec![(4, 7, 3).into(), (0, 3, 5).into()]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DynamicSolver<Weight, Time, Interval> {
+  /// The intervals, sorted by end.
+  intervals:   Vec<Interval>,
+  ends:        Vec<Time>,
+  memoization: Vec<Weight>
+}
+
+impl<Weight, Time, Interval> Default for DynamicSolver<Weight, Time, Interval> {
+  fn default() -> Self {
+    Self { intervals: vec![], ends: vec![], memoization: vec![] }
+  }
+}
+
+impl<Weight, Time, Interval> DynamicSolver<Weight, Time, Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  /// Solves `intervals`, in any order. `O(n log n)`.
+  pub fn new(mut intervals: Vec<Interval>) -> Self {
+    phase!("sort", intervals.len(), intervals.sort_by_key(|i| i.end()));
+
+    let ends = intervals.iter().map(|i| i.end()).collect();
+    let memoization = intervals.iter().map(|i| i.weight()).collect();
+    let mut solver = Self { intervals, ends, memoization };
+    phase!("memoize", solver.len(), solver.repair(0));
+    solver
+  }
+
+  /// Adds `interval`, returning its position among the intervals (sorted by end), after any ending at the same time.
+  /// `O((n - p) log n)` for its position `p`.
+  pub fn insert(&mut self, interval: Interval) -> usize {
+    let end = interval.end();
+    let position = self.ends.partition_point(|e| *e <= end);
+
+    self.memoization.insert(position, interval.weight());
+    self.intervals.insert(position, interval);
+    self.ends.insert(position, end);
+    self.repair(position);
+    position
+  }
+
+  /// Removes and returns the interval at `position` among the intervals (sorted by end). `O((n - p) log n)`.
+  ///
+  /// # Panics
+  /// If `position` is out of bounds.
+  pub fn remove(&mut self, position: usize) -> Interval {
+    let interval = self.intervals.remove(position);
+    self.ends.remove(position);
+    self.memoization.remove(position);
+    self.repair(position);
+    interval
+  }
+
+  /// The weight of the optimal selection (`None` if there are no intervals). `O(1)`.
+  #[must_use]
+  pub fn optimal_weight(&self) -> Option<Weight> { self.memoization.last().cloned() }
+
+  /// The optimal selection, in descending order of end (as from [`sorted`](crate::sorted)). `O(n log n)`.
+  #[must_use]
+  pub fn solution(&self) -> Vec<Interval>
+    where Interval: Clone
+  {
+    let mut solution = vec![];
+    reconstruct(
+      self.intervals.len(),
+      &self.memoization,
+      |k| self.intervals[k].weight(),
+      |k| self.predecessor(k),
+      |k| solution.push(self.intervals[k].clone())
+    );
+    solution
+  }
+
+  /// The intervals, sorted by end (positions in this slice are those `insert` returns and `remove` takes).
+  pub fn intervals(&self) -> &[Interval] { &self.intervals }
+
+  /// How many intervals there are.
+  pub fn len(&self) -> usize { self.intervals.len() }
+
+  /// Whether there are no intervals.
+  pub fn is_empty(&self) -> bool { self.intervals.is_empty() }
+
+  /// The last interval (before `k`) ending by the time the interval at `k` starts.
+  fn predecessor(&self, k: usize) -> Option<usize> {
+    self.ends[..k].partition_point(|e| *e <= self.intervals[k].start()).checked_sub(1)
+  }
+
+  /// Brings the memoization up to date from `first` on, that before it being so.
+  fn repair(&mut self, first: usize) {
+    for k in first..self.intervals.len() {
+      let included = match self.predecessor(k) {
+        Some(p) => self.intervals[k].weight() + self.memoization[p].clone(),
+        None    => self.intervals[k].weight()
+      };
+      self.memoization[k] = match k.checked_sub(1) {
+        Some(p) => included.max(self.memoization[p].clone()),
+        None    => included
+      };
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{unsorted, DynamicSolver, WeightedInterval};
+
+  #[test]
+  fn edits_match_solving_anew() {
+    let pool: Vec<WeightedInterval<u32, u32>> = (0..40u32).map(|i| (i * 11 % 31, i * 11 % 31 + 1 + i % 6, 1 + i % 8).into()).collect();
+
+    let mut solver = DynamicSolver::new(pool[..10].to_vec());
+    let mut current = pool[..10].to_vec();
+    for (step, interval) in pool[10..].iter().enumerate() {
+      solver.insert(*interval);
+      current.push(*interval);
+
+      // every third step, take one out again
+      if step % 3 == 0 {
+        let position = (step * 7) % solver.len();
+        let removed = solver.remove(position);
+        current.swap_remove(current.iter().position(|i| *i == removed).unwrap());
+      }
+
+      let expected = unsorted(&current).iter().map(|i| i.weight).sum::<u32>();
+      assert_eq!(solver.optimal_weight(), Some(expected));
+      assert_eq!(solver.solution().iter().map(|i| i.weight).sum::<u32>(), expected);
+      assert!(solver.intervals().windows(2).all(|pair| pair[0].end <= pair[1].end));
+    }
+
+    assert!(DynamicSolver::<u32, u32, WeightedInterval<u32, u32>>::default().optimal_weight().is_none());
+  }
+}
@@ -0,0 +1,335 @@
+//! Enumerations of feasible selections, for when a single optimum isn't enough.
+
+use std::{marker::PhantomData, ops::Add};
+use crate::{solvers::memoize, traits};
+
+/// How an entry of the k-best lists came about.
+#[derive(Clone, Copy)]
+enum Step {
+  /// Leaving the interval out, from the entry of that rank among the sets of the intervals before it.
+  Skip(usize),
+  /// Taking the interval, alone or after the entry of that rank among the sets of its predecessors.
+  Take(Option<usize>)
+}
+
+/// The `k` heaviest selections of pairwise compatible intervals, heaviest first, each in descending order of end (as
+/// from [`unsorted`](crate::unsorted)). `O(n log n + n k log k)` in interval number `n`.
+///
+/// Every nonempty selection is a candidate (fewer than `k` are returned if there aren't as many), so the first is an
+/// optimum, and the rest are the next best alternatives to it. Among selections of equal weight, the order is
+/// unspecified.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 3, 5).into(), (2, 5, 6).into(), (5, 8, 2).into()];
+///
+/// let alternatives = enumerate::top_k(&intervals, 3);
+/// assert_eq!(alternatives, vec![
+///   vec![intervals[2], intervals[1]],
+///   vec![intervals[2], intervals[0]],
+///   vec![intervals[1]]
+/// ]);
+/// ```
+#[must_use]
+pub fn top_k<Weight, Time, Interval, InputContainer>(intervals: InputContainer, k: usize) -> Vec<Vec<Interval>>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  if k == 0 { return vec![]; }
+
+  // by start among equal ends, so that zero-length intervals follow those ending where they are
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].end(), intervals[i].start())));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+  let predecessor = |p: usize| ends[..p].partition_point(|e| *e <= intervals[order[p]].start()).checked_sub(1);
+
+  // for each position, the k heaviest selections among the intervals up to it, heaviest first
+  let mut best: Vec<Vec<(Weight, Step)>> = Vec::with_capacity(order.len());
+  phase!("memoize", order.len(), for p in 0..order.len() {
+    let weight = intervals[order[p]].weight();
+
+    let skipped = p.checked_sub(1).map_or(&[][..], |q| &best[q]);
+    let taken = predecessor(p).map_or(&[][..], |q| &best[q]);
+
+    let mut candidates: Vec<(Weight, Step)> = skipped.iter()
+      .enumerate()
+      .map(|(rank, (total, _))| (total.clone(), Step::Skip(rank)))
+      .chain(taken.iter().enumerate().map(|(rank, (total, _))| (weight.clone() + total.clone(), Step::Take(Some(rank)))))
+      .chain([(weight.clone(), Step::Take(None))])
+      .collect();
+    candidates.sort_by(|a, b| b.0.cmp(&a.0));
+    candidates.truncate(k);
+    best.push(candidates);
+  });
+
+  let Some(last) = best.last() else { return vec![] };
+  (0..last.len())
+    .map(|rank| {
+      let mut selection = vec![];
+      let (mut p, mut rank) = (order.len() - 1, rank);
+      loop {
+        match best[p][rank].1 {
+          Step::Skip(r)       => (p, rank) = (p - 1, r),
+          Step::Take(None)    => { selection.push(intervals[order[p]].clone()); break; }
+          Step::Take(Some(r)) => {
+            selection.push(intervals[order[p]].clone());
+            (p, rank) = (predecessor(p).expect("taken after a predecessor"), r);
+          }
+        }
+      }
+      selection
+    })
+    .collect()
+}
+
+/// Calls `each` with every selection of maximum total weight, each in descending order of end (as from
+/// [`unsorted`](crate::unsorted)), stopping after `limit` of them. Returns how many there were (up to `limit`).
+/// `O(n log n)` in interval number `n`, plus `O(n)` per selection.
+///
+/// Ties can make for exponentially many optima, hence the limit. The optima are found by walking back the
+/// memoization along every choice that keeps the selection optimal, so none is ever found twice, and no time is
+/// spent on selections that aren't optimal.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 2).into(), (2, 4, 2).into(), (0, 4, 4).into(), (4, 6, 1).into()];
+///
+/// let mut optima = vec![];
+/// assert_eq!(enumerate::optima(&intervals, 10, |optimum| optima.push(optimum)), 2);
+/// assert_eq!(optima, vec![
+///   vec![intervals[3], intervals[2]],
+///   vec![intervals[3], intervals[1], intervals[0]]
+/// ]);
+/// ```
+pub fn optima<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  limit:     usize,
+  mut each:  impl FnMut(Vec<Interval>)
+) -> usize
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  if limit == 0 || intervals.is_empty() { return 0; }
+
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].end(), intervals[i].start())));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  let weight      = |p: usize| intervals[order[p]].weight();
+  let predecessor = |p: usize| ends[..p].partition_point(|e| *e <= intervals[order[p]].start()).checked_sub(1);
+
+  let mut memoization = vec![weight(0); order.len()];
+  phase!("memoize", order.len(), memoize(order.len(), &mut memoization, weight, predecessor));
+
+  // depth first, each frame being a position, the next choice to try there, and the selection's length on arrival
+  let mut found = 0;
+  let mut selection: Vec<usize> = vec![];
+  let mut stack = vec![(order.len() - 1, 0, 0)];
+  while let Some(frame) = stack.last_mut() {
+    let (p, choice, length) = *frame;
+    frame.1 += 1;
+    selection.truncate(length);
+
+    match choice {
+      // without the interval, if that is as good
+      0 => if p > 0 && memoization[p - 1] == memoization[p] { stack.push((p - 1, 0, length)); },
+      // after an optimum of its predecessors
+      1 => if let Some(q) = predecessor(p) {
+        if weight(p) + memoization[q].clone() == memoization[p] {
+          selection.push(p);
+          stack.push((q, 0, length + 1));
+        }
+      },
+      // alone
+      2 => if weight(p) == memoization[p] {
+        selection.push(p);
+        each(selection.iter().map(|&p| intervals[order[p]].clone()).collect());
+        found += 1;
+        if found == limit { break; }
+      },
+      _ => { stack.pop(); }
+    }
+  }
+
+  found
+}
+
+/// Iterates over the maximal selections of pairwise compatible intervals (those to which no interval can be added),
+/// whatever the weights, each in descending order of end (as from [`unsorted`](crate::unsorted)). Built with
+/// [`maximal_sets`].
+///
+/// Each step of the iteration is `O(n²)` in interval number, while there can be exponentially many maximal selections.
+pub struct MaximalSets<'a, Time, Interval> {
+  intervals: &'a [Interval],
+  /// The positions of the intervals by end (then start).
+  order:     Vec<usize>,
+  /// The positions (in `order`) chosen so far, chronologically.
+  chain:     Vec<usize>,
+  /// For each interval of the chain (and before the first), the next position to try after it.
+  next:      Vec<usize>,
+  _time:     PhantomData<Time>
+}
+
+/// The maximal selections of pairwise compatible intervals, whatever the weights, for scoring them in ways that aren't
+/// additive. `O(n log n)` in interval number to start iterating; see [`MaximalSets`].
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<(u8, u8)> = vec![(0, 2), (1, 4), (2, 5), (5, 6)];
+///
+/// let sets: Vec<Vec<(u8, u8)>> = enumerate::maximal_sets(&intervals).collect();
+/// assert_eq!(sets, vec![
+///   vec![(5, 6), (2, 5), (0, 2)],
+///   vec![(5, 6), (1, 4)]
+/// ]);
+/// ```
+#[must_use]
+pub fn maximal_sets<Time, Interval>(intervals: &[Interval]) -> MaximalSets<'_, Time, Interval>
+  where Time: Ord,
+        Interval: traits::Interval<Time>
+{
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].end(), intervals[i].start())));
+
+  let next = if intervals.is_empty() { vec![] } else { vec![0] };
+  MaximalSets { intervals, order, chain: vec![], next, _time: PhantomData }
+}
+
+impl<Time, Interval> MaximalSets<'_, Time, Interval>
+  where Time: Ord,
+        Interval: traits::Interval<Time>
+{
+  fn at(&self, p: usize) -> &Interval { &self.intervals[self.order[p]] }
+
+  /// Whether the interval at `p` may follow the interval at `last` (or come first), leaving nothing that would fit
+  /// between them (or before it). Intervals are only ever placed after those before them in `order`.
+  fn follows(&self, last: Option<usize>, p: usize) -> bool {
+    let after = |x: usize| last.is_none_or(|a| self.at(x).start() >= self.at(a).end());
+    let from = last.map_or(0, |a| a + 1);
+    after(p) && !(from..p).any(|x| after(x) && self.at(x).end() <= self.at(p).start())
+  }
+
+  /// Whether nothing can follow the interval at `last`.
+  fn ends_chain(&self, last: usize) -> bool {
+    !(last + 1..self.order.len()).any(|x| self.at(x).start() >= self.at(last).end())
+  }
+}
+
+impl<Time, Interval> Iterator for MaximalSets<'_, Time, Interval>
+  where Time: Ord,
+        Interval: traits::Interval<Time> + Clone
+{
+  type Item = Vec<Interval>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while let Some(&from) = self.next.last() {
+      let last = self.chain.last().copied();
+      let Some(p) = (from..self.order.len()).find(|&p| self.follows(last, p)) else {
+        // tried everything after the last interval chosen
+        self.next.pop();
+        self.chain.pop();
+        continue;
+      };
+
+      *self.next.last_mut().expect("being iterated over") = p + 1;
+      if self.ends_chain(p) {
+        return Some(self.chain.iter().chain([&p]).rev().map(|&p| self.at(p).clone()).collect());
+      }
+      self.chain.push(p);
+      self.next.push(p + 1);
+    }
+
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{enumerate, testing::{pairwise, subsets}, WeightedInterval};
+
+  /// Every nonempty feasible selection's weight, heaviest first.
+  fn all_weights(intervals: &[WeightedInterval<u32, u32>]) -> Vec<u32> {
+    let compatible = |a: &WeightedInterval<u32, u32>, b: &WeightedInterval<u32, u32>| a.end <= b.start || b.end <= a.start;
+
+    let mut weights: Vec<u32> = subsets(intervals.len())
+      .skip(1)
+      .filter(|set| pairwise(set, |a, b| compatible(&intervals[a], &intervals[b])))
+      .map(|set| set.iter().map(|&i| intervals[i].weight).sum())
+      .collect();
+    weights.sort_by(|a, b| b.cmp(a));
+    weights
+  }
+
+  #[test]
+  fn top_k_matches_brute_force() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..11u32).map(|i| (i * 5 % 9, i * 5 % 9 + i % 3, 1 + i % 4).into()).collect();
+    let expected = all_weights(&intervals);
+
+    for k in [0, 1, 5, 40, 1000] {
+      let alternatives = enumerate::top_k(&intervals, k);
+      let weights: Vec<u32> = alternatives.iter().map(|s| s.iter().map(|i| i.weight).sum()).collect();
+      assert_eq!(weights, expected[..k.min(expected.len())]);
+
+      for selection in &alternatives {
+        assert!(selection.windows(2).all(|pair| pair[1].end <= pair[0].start));
+      }
+      let mut distinct = alternatives.clone();
+      distinct.sort();
+      distinct.dedup();
+      assert_eq!(distinct.len(), alternatives.len());
+    }
+  }
+
+  #[test]
+  fn optima_match_brute_force() {
+    // unit weights, so plenty of ties, and zero-length intervals among them
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..12u32).map(|i| (i * 5 % 8, i * 5 % 8 + i % 3, 1).into()).collect();
+    let best = all_weights(&intervals)[0];
+    let optimal = all_weights(&intervals).into_iter().filter(|w| *w == best).count();
+
+    let mut optima = vec![];
+    assert_eq!(enumerate::optima(&intervals, usize::MAX, |optimum| optima.push(optimum)), optimal);
+    assert!(optima.iter().all(|o| o.iter().map(|i| i.weight).sum::<u32>() == best));
+    assert!(optima.iter().all(|o| o.windows(2).all(|pair| pair[1].end <= pair[0].start)));
+
+    let mut distinct = optima.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), optimal);
+
+    assert_eq!(enumerate::optima(&intervals, 3, |_| ()), 3.min(optimal));
+    assert_eq!(enumerate::optima(Vec::<WeightedInterval<u32, u32>>::new(), 3, |_| ()), 0);
+  }
+
+  #[test]
+  fn maximal_sets_match_brute_force() {
+    let intervals: Vec<(u32, u32)> = (0..11u32).map(|i| (i * 5 % 9, i * 5 % 9 + i % 3)).chain([(4, 4), (4, 4)]).collect();
+    let compatible = |a: &(u32, u32), b: &(u32, u32)| a.1 <= b.0 || b.1 <= a.0;
+
+    let mut expected: Vec<Vec<(u32, u32)>> = subsets(intervals.len())
+      .skip(1)
+      .filter(|set| pairwise(set, |a, b| compatible(&intervals[a], &intervals[b])))
+      .filter(|set| (0..intervals.len()).all(|x| set.contains(&x) || set.iter().any(|&a| !compatible(&intervals[a], &intervals[x]))))
+      .map(|set| {
+        let mut set: Vec<(u32, u32)> = set.into_iter().map(|i| intervals[i]).collect();
+        set.sort();
+        set
+      })
+      .collect();
+    expected.sort();
+
+    let mut sets: Vec<Vec<(u32, u32)>> = enumerate::maximal_sets(&intervals).collect();
+    assert!(sets.iter().all(|set| set.windows(2).all(|pair| pair[1].1 <= pair[0].0)));
+    for set in &mut sets { set.sort(); }
+    sets.sort();
+    assert_eq!(sets, expected);
+
+    assert_eq!(enumerate::maximal_sets(&Vec::<(u32, u32)>::new()).count(), 0);
+  }
+}
@@ -0,0 +1,129 @@
+use std::ops::Add;
+use crate::{solvers::{memoize, reconstruct}, traits};
+
+/// The ends of a problem (sorted by end), copied into Eytzinger order for [`sorted_with_index`] to search.
+///
+/// Eytzinger order lays a balanced binary search tree out breadth-first, so a search walks down a single array, the
+/// first levels (visited by every search) sharing a few cache lines, and each step choosing its next position without
+/// a branch. On problems too large for the cache, this speeds the predecessor searches up severalfold.
+///
+/// Building the index is `O(n)` in interval number, and it can be reused for as long as the ends don't change (e.g.
+/// while only the weights are being changed).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u32, u8>> = vec![(0, 4, 2).into(), (1, 5, 3).into(), (4, 9, 3).into()];
+/// let index = EytzingerIndex::new(&intervals);
+///
+/// let mut memo = vec![0; intervals.len()];
+/// let mut solution = vec![];
+/// sorted_with_index(&intervals, &index, &mut memo, &mut solution);
+///
+/// assert_eq!(solution, vec![(4, 9, 3).into(), (0, 4, 2).into()]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EytzingerIndex<Time> {
+  /// The ends, breadth-first: the children of position `k` are at `2k + 1` and `2k + 2`.
+  ends:  Vec<Time>,
+  /// The position of each end among the sorted ends.
+  ranks: Vec<usize>
+}
+
+impl<Time: Ord + Clone> EytzingerIndex<Time> {
+  /// Indexes the ends of `intervals`, which must be sorted by end (as for [`sorted`](crate::sorted)). `O(n)`.
+  pub fn new<Interval, InputContainer>(intervals: InputContainer) -> Self
+    where Interval: traits::Interval<Time>,
+          InputContainer: AsRef<[Interval]>
+  {
+    let intervals = intervals.as_ref();
+    let mut order = vec![0; intervals.len()];
+
+    // an in-order walk of the tree visits the positions in sorted order
+    let mut rank = 0;
+    let mut stack = vec![];
+    let mut k = 0;
+    while k < order.len() || !stack.is_empty() {
+      while k < order.len() { stack.push(k); k = 2 * k + 1; }
+      let Some(node) = stack.pop() else { break };
+      order[node] = rank;
+      rank += 1;
+      k = 2 * node + 2;
+    }
+
+    Self { ends: order.iter().map(|&r| intervals[r].end()).collect(), ranks: order }
+  }
+
+  /// How many of the ends are at most `time`. `O(log n)`.
+  pub fn count_at_most(&self, time: &Time) -> usize {
+    let mut k = 0;
+    while k < self.ends.len() { k = 2 * k + 1 + usize::from(self.ends[k] <= *time); }
+
+    // back up to where the search last went left: that end is the first after `time`
+    let k = (k + 1) >> ((k + 1).trailing_ones() + 1);
+    match k.checked_sub(1) {
+      Some(k) => self.ranks[k],
+      None    => self.ends.len()
+    }
+  }
+
+  /// How many ends are indexed.
+  pub fn len(&self) -> usize { self.ends.len() }
+
+  /// Whether no end is indexed.
+  pub fn is_empty(&self) -> bool { self.ends.is_empty() }
+}
+
+/// Like [`sorted`](crate::sorted), but searching for predecessors in `index` (built from the same intervals) rather than
+/// in the intervals themselves. `O(n log n)` in interval number. The rules of `sorted` apply.
+///
+/// # Panics
+/// If `index` doesn't index as many ends as there are intervals.
+pub fn sorted_with_index<Weight, Time, Interval, InputContainer>(
+  intervals:   InputContainer,
+  index:       &EytzingerIndex<Time>,
+  memoization: &mut [Weight],
+  solution:    &mut Vec<Interval>
+) where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  assert_eq!(index.len(), intervals.len(), "the index must be built from the same intervals");
+
+  if let Some(first) = intervals.first() { memoization[0] = first.weight(); }
+  else { return; }
+
+  // the ends from `i` on are no earlier than the start of the interval at `i`, so clamping the count keeps the search
+  // among those before it
+  let weight      = |i: usize| intervals[i].weight();
+  let predecessor = |i: usize| index.count_at_most(&intervals[i].start()).min(i).checked_sub(1);
+
+  phase!("memoize", intervals.len(), memoize(intervals.len(), memoization, weight, predecessor));
+  phase!("reconstruct", intervals.len(), reconstruct(intervals.len(), memoization, weight, predecessor, |i| {
+    solution.push(intervals[i].clone())
+  }));
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{sorted, sorted_with_index, EytzingerIndex, WeightedInterval};
+
+  #[test]
+  fn matches_sorted() {
+    for n in [0u32, 1, 2, 3, 7, 8, 100, 1_000] {
+      let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..n).map(|i| (i * 13 % 97, i * 13 % 97 + i % 5, 1 + i % 7).into()).collect();
+      intervals.sort_by_key(|i| i.end);
+      let index = EytzingerIndex::new(&intervals);
+
+      for time in 0..110 {
+        assert_eq!(index.count_at_most(&time), intervals.partition_point(|i| i.end <= time));
+      }
+
+      let (mut memo, mut expected, mut solution) = (vec![0; intervals.len()], vec![], vec![]);
+      sorted(&intervals, &mut memo, &mut expected);
+      sorted_with_index(&intervals, &index, &mut memo, &mut solution);
+      assert_eq!(solution, expected);
+    }
+  }
+}
@@ -0,0 +1,97 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// Minimum cost flow by successive shortest paths (Dijkstra with potentials).
+///
+/// Costs may be negative, as long as every edge goes from a lower numbered node to a higher numbered one
+/// (the nodes are numbered in topological order), which is how the initial potentials are found.
+pub(crate) struct MinCostFlow {
+  edges:     Vec<Edge>,
+  adjacency: Vec<Vec<usize>>
+}
+
+struct Edge {
+  to:       usize,
+  capacity: usize,
+  cost:     i128
+}
+
+impl MinCostFlow {
+  pub fn new(nodes: usize) -> Self { Self { edges: vec![], adjacency: vec![vec![]; nodes] } }
+
+  /// Adds an edge (and its residual twin), returning its id.
+  pub fn add_edge(&mut self, from: usize, to: usize, capacity: usize, cost: i128) -> usize {
+    debug_assert!(from < to, "edges must follow the topological numbering of the nodes");
+
+    let id = self.edges.len();
+    self.edges.push(Edge { to, capacity, cost });
+    self.edges.push(Edge { to: from, capacity: 0, cost: -cost });
+    self.adjacency[from].push(id);
+    self.adjacency[to].push(id + 1);
+    id
+  }
+
+  /// The flow through the edge with the given id.
+  pub fn flow(&self, edge: usize) -> usize { self.edges[edge ^ 1].capacity }
+
+  /// Sends up to `amount` units of flow from `source` to `sink` at minimum cost, returning how much was sent.
+  pub fn run(&mut self, source: usize, sink: usize, amount: usize) -> usize {
+    let nodes = self.adjacency.len();
+
+    // shortest distances over the (acyclic) original edges make for valid initial potentials
+    let mut potential = vec![i128::MAX; nodes];
+    potential[source] = 0;
+    for u in 0..nodes {
+      if potential[u] == i128::MAX { continue; }
+      for &e in &self.adjacency[u] {
+        let edge = &self.edges[e];
+        if edge.capacity > 0 { potential[edge.to] = potential[edge.to].min(potential[u] + edge.cost); }
+      }
+    }
+
+    let mut sent = 0;
+    while sent < amount {
+      // Dijkstra over reduced costs, which the potentials keep non-negative
+      let mut distance = vec![i128::MAX; nodes];
+      let mut through: Vec<Option<usize>> = vec![None; nodes];
+      let mut queue = BinaryHeap::from([Reverse((0, source))]);
+      distance[source] = 0;
+
+      while let Some(Reverse((d, u))) = queue.pop() {
+        if d > distance[u] { continue; }
+        for &e in &self.adjacency[u] {
+          let edge = &self.edges[e];
+          if edge.capacity == 0 || potential[edge.to] == i128::MAX { continue; }
+
+          let next = d + edge.cost + potential[u] - potential[edge.to];
+          if next < distance[edge.to] {
+            distance[edge.to] = next;
+            through[edge.to] = Some(e);
+            queue.push(Reverse((next, edge.to)));
+          }
+        }
+      }
+      if distance[sink] == i128::MAX { break; }
+
+      for v in 0..nodes {
+        if distance[v] != i128::MAX { potential[v] += distance[v]; }
+      }
+
+      // push as much as the path allows
+      let mut push = amount - sent;
+      let mut v = sink;
+      while let Some(e) = through[v] {
+        push = push.min(self.edges[e].capacity);
+        v = self.edges[e ^ 1].to;
+      }
+      let mut v = sink;
+      while let Some(e) = through[v] {
+        self.edges[e].capacity -= push;
+        self.edges[e ^ 1].capacity += push;
+        v = self.edges[e ^ 1].to;
+      }
+      sent += push;
+    }
+
+    sent
+  }
+}
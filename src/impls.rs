@@ -0,0 +1,115 @@
+//! Implementations of this crate's traits for standard library types.
+
+use std::{ops::{Add, Range}, rc::Rc, sync::Arc};
+use crate::traits;
+
+/// References and smart pointers are whatever they point to, so e.g. `&[&Job]` can be solved
+/// without copying the jobs out of wherever they live.
+macro_rules! delegate_through_pointer {
+  ($($pointer:ident),*) => {$(
+    impl<Time: Ord, Interval: traits::Interval<Time> + ?Sized> traits::Interval<Time> for delegate_through_pointer!(@type $pointer, Interval) {
+      fn start(&self) -> Time { (**self).start() }
+      fn end(&self) -> Time { (**self).end() }
+    }
+
+    impl<Weight: Ord + Add, Interval: traits::Weighted<Weight> + ?Sized> traits::Weighted<Weight> for delegate_through_pointer!(@type $pointer, Interval) {
+      fn weight(&self) -> Weight { (**self).weight() }
+    }
+  )*};
+
+  (@type Ref, $t:ident) => { &$t };
+  (@type $pointer:ident, $t:ident) => { $pointer<$t> };
+}
+
+delegate_through_pointer!(Ref, Box, Rc, Arc);
+
+/// `(start, end)`, for when a struct would be overkill.
+impl<Time: Ord + Clone> traits::Interval<Time> for (Time, Time) {
+  fn start(&self) -> Time { self.0.clone() }
+  fn end(&self) -> Time { self.1.clone() }
+}
+
+/// `((start, end), weight)`, an interval paired with a weight, ready for the solvers.
+/// 
+/// ```rust
+/// # use w_inter::unsorted;
+/// let optimal = unsorted([((0, 2), 3u8), ((1, 3), 5), ((2, 4), 1)]);
+/// assert_eq!(optimal, vec![((1, 3), 5)]);
+/// 
+/// // or, with weights kept apart
+/// let (intervals, weights) = ([(0, 2), (1, 3), (2, 4)], [3u8, 1, 1]);
+/// let paired: Vec<_> = intervals.into_iter().zip(weights).collect();
+/// assert_eq!(unsorted(&paired), vec![((2, 4), 1), ((0, 2), 3)]);
+/// ```
+impl<Time: Ord + Clone, Weight> traits::Interval<Time> for ((Time, Time), Weight) {
+  fn start(&self) -> Time { self.0.0.clone() }
+  fn end(&self) -> Time { self.0.1.clone() }
+}
+
+impl<Time, Weight: Ord + Add + Clone> traits::Weighted<Weight> for ((Time, Time), Weight) {
+  fn weight(&self) -> Weight { self.1.clone() }
+}
+
+/// A `Range` is an interval as-is: `start..end` contains neither more nor less than the interval from `start` to `end`.
+impl<Time: Ord + Clone> traits::Interval<Time> for Range<Time> {
+  fn start(&self) -> Time { self.start.clone() }
+  fn end(&self) -> Time { self.end.clone() }
+}
+
+/// `(start..end, weight)`, a range paired with a weight, ready for the solvers.
+impl<Time: Ord + Clone, Weight> traits::Interval<Time> for (Range<Time>, Weight) {
+  fn start(&self) -> Time { self.0.start.clone() }
+  fn end(&self) -> Time { self.0.end.clone() }
+}
+
+impl<Time, Weight: Ord + Add + Clone> traits::Weighted<Weight> for (Range<Time>, Weight) {
+  fn weight(&self) -> Weight { self.1.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{rc::Rc, sync::Arc};
+  use crate::{unsorted, util::final_compatible, HalfOpen, WeightedInterval};
+
+  #[test]
+  fn ranges_are_intervals() {
+    let sorted_by_end = [0..2, 2..5, 1..6, 5..7];
+    assert_eq!(final_compatible(&sorted_by_end, 1, &HalfOpen), Some(0));
+    assert_eq!(final_compatible(&sorted_by_end, 2, &HalfOpen), None);
+    assert_eq!(final_compatible(&sorted_by_end, 3, &HalfOpen), Some(1));
+  }
+
+  #[test]
+  fn tuples_are_intervals() {
+    assert_eq!(final_compatible(&[(0, 2), (2, 5), (1, 6)], 1, &HalfOpen), Some(0));
+  }
+
+  #[test]
+  fn weighted_ranges() {
+    let optimal = unsorted([(0..2, 3u8), (1..3, 5), (3..4, 1)]);
+    assert_eq!(optimal, vec![(3..4, 1), (1..3, 5)]);
+  }
+
+  #[test]
+  fn references_and_smart_pointers() {
+    let arena = [
+      WeightedInterval::new(0u8, 6u8, 3u8),
+      WeightedInterval::new(1u8, 4u8, 5u8),
+      WeightedInterval::new(5u8, 9u8, 7u8)
+    ];
+    let expected = vec![arena[2], arena[1]];
+
+    let references: Vec<&WeightedInterval<u8, u8>> = arena.iter().collect();
+    let optimal: Vec<WeightedInterval<u8, u8>> = unsorted(&references).into_iter().cloned().collect();
+    assert_eq!(optimal, expected);
+
+    let boxed: Vec<Box<WeightedInterval<u8, u8>>> = arena.iter().cloned().map(Box::new).collect();
+    assert_eq!(unsorted(&boxed).into_iter().map(|b| *b).collect::<Vec<_>>(), expected);
+
+    let shared: Vec<Rc<WeightedInterval<u8, u8>>> = arena.iter().cloned().map(Rc::new).collect();
+    assert!(Rc::ptr_eq(&unsorted(&shared)[0], &shared[2]));
+
+    let threaded: Vec<Arc<WeightedInterval<u8, u8>>> = arena.iter().cloned().map(Arc::new).collect();
+    assert!(Arc::ptr_eq(&unsorted(&threaded)[1], &threaded[1]));
+  }
+}
@@ -0,0 +1,197 @@
+//! The graph-theoretic view of a problem: intervals are vertices, and two vertices are adjacent if their intervals overlap.
+
+use std::{cmp::Reverse, collections::{BTreeSet, BinaryHeap}, ops::Add};
+use crate::{solvers::solve_by, traits};
+
+/// Builds the edge list of the conflict graph of `intervals`. `O(n log n + e)` in interval and edge number.
+///
+/// - Edges are pairs of indices into `intervals`, with the smaller index first, in no particular order.
+/// - Two intervals conflict when neither ends before (or exactly when) the other starts, matching the solvers,
+///   which happily select intervals that merely touch.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 2, 1).into(),
+///   (1, 3, 1).into(),
+///   (2, 4, 1).into()
+/// ];
+///
+/// let mut edges = interval_graph::conflicts(&intervals);
+/// edges.sort();
+/// assert_eq!(edges, vec![(0, 1), (1, 2)]);
+/// ```
+#[must_use]
+pub fn conflicts<Weight, Time, Interval>(intervals: &[Interval]) -> Vec<(usize, usize)>
+  where Weight: Ord + Add,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  // sweep in order of start time, every interval only needs to look ahead while the others start before it ends
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  order.sort_by_key(|&i| intervals[i].start());
+
+  let mut edges = vec![];
+  for (position, &a) in order.iter().enumerate() {
+    let end = intervals[a].end();
+
+    for &b in order[position + 1..].iter().take_while(|&&b| intervals[b].start() < end) {
+      // an empty interval sitting on another's start touches it, without overlapping it
+      if intervals[b].end() > intervals[b].start() || intervals[b].start() > intervals[a].start() {
+        edges.push((a.min(b), a.max(b)));
+      }
+    }
+  }
+
+  edges
+}
+
+/// Colors the conflict graph of `intervals` with as few colors as possible, returning the color (numbered from `0`)
+/// of each interval: conflicting intervals never share one. `O(n log n)` in interval number.
+///
+/// By increasing start time, each interval takes the smallest color that no interval still running has. On interval
+/// graphs this is optimal: the number of colors is the greatest number of intervals overlapping at one time, as is
+/// the number of rooms (or machines) needed to host them all, which the colors assign.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let meetings: Vec<WeightedInterval<u8, u8>> = vec![
+///   (9, 11, 1).into(),
+///   (10, 12, 1).into(),
+///   (11, 13, 1).into(),
+///   (9, 13, 1).into()
+/// ];
+///
+/// let rooms = interval_graph::coloring(&meetings);
+/// assert_eq!(rooms, vec![0, 2, 0, 1]);
+/// ```
+#[must_use]
+pub fn coloring<Weight, Time, Interval>(intervals: &[Interval]) -> Vec<usize>
+  where Weight: Ord + Add,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  // ties on start go to the earlier end, so that an empty interval passes before those it touches
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  order.sort_by_key(|&i| (intervals[i].start(), intervals[i].end()));
+
+  let mut colors = vec![0; intervals.len()];
+  let mut running: BinaryHeap<Reverse<(Time, usize)>> = BinaryHeap::new();
+  let mut free: BTreeSet<usize> = BTreeSet::new();
+  let mut used = 0;
+
+  for i in order {
+    let start = intervals[i].start();
+    while running.peek().is_some_and(|Reverse((end, _))| *end <= start) {
+      if let Some(Reverse((_, color))) = running.pop() { free.insert(color); }
+    }
+
+    let color = free.pop_first().unwrap_or_else(|| { used += 1; used - 1 });
+    colors[i] = color;
+    running.push(Reverse((intervals[i].end(), color)));
+  }
+
+  colors
+}
+
+/// Finds a maximum weight independent set of the conflict graph of `intervals`, vertex `v` weighing `weights[v]`:
+/// the heaviest set of vertices no two of which are adjacent. `O(n log n)` in vertex number.
+///
+/// This is the weighted interval scheduling problem under another name, solved by the same recurrence as
+/// [`unsorted`](crate::unsorted): an independent set of the conflict graph is a set of intervals that can all be
+/// selected together. Only the intervals' bounds are read, any weights they carry being ignored for `weights`.
+/// Returns the vertices of the set, ascending.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // a path on four vertices (0 - 1 - 2 - 3), as the conflict graph of these intervals
+/// let intervals: Vec<(u8, u8)> = vec![(0, 2), (1, 3), (2, 4), (3, 5)];
+/// let weights = [3, 5, 1, 4];
+///
+/// assert_eq!(interval_graph::max_weight_independent_set(&intervals, &weights), vec![1, 3]);
+/// ```
+///
+/// # Panics
+/// If there are fewer weights than vertices.
+#[must_use]
+pub fn max_weight_independent_set<Weight, Time, Interval>(intervals: &[Interval], weights: &[Weight]) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time>
+{
+  assert!(weights.len() >= intervals.len(), "every vertex must have a weight");
+
+  // ties on end go to the earlier start, so that an empty interval comes after those it touches, and can follow them
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].end(), intervals[i].start())));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  let mut positions = vec![];
+  solve_by(
+    order.len(),
+    |k| weights[order[k]].clone(),
+    |k| ends[..k].partition_point(|e| *e <= intervals[order[k]].start()).checked_sub(1),
+    &mut positions
+  );
+
+  let mut set: Vec<usize> = positions.into_iter().map(|k| order[k]).collect();
+  set.sort_unstable();
+  set
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{sorted, WeightedInterval, interval_graph::{coloring, conflicts, max_weight_independent_set}, k_machines};
+
+  #[test]
+  fn touching_intervals_do_not_conflict() {
+    let intervals = [
+      WeightedInterval { start: 0u8, end: 1u8, weight: 1u8 },
+      WeightedInterval { start: 1u8, end: 2u8, weight: 1u8 },
+      WeightedInterval { start: 2u8, end: 2u8, weight: 1u8 }
+    ];
+    assert!(conflicts(&intervals).is_empty());
+  }
+
+  #[test]
+  fn nested_and_identical() {
+    let intervals = [
+      WeightedInterval { start: 0u8, end: 10u8, weight: 1u8 },
+      WeightedInterval { start: 2u8, end: 3u8,  weight: 1u8 },
+      WeightedInterval { start: 2u8, end: 3u8,  weight: 1u8 },
+      WeightedInterval { start: 5u8, end: 5u8,  weight: 1u8 }
+    ];
+
+    let mut edges = conflicts(&intervals);
+    edges.sort();
+    assert_eq!(edges, vec![(0, 1), (0, 2), (0, 3), (1, 2)]);
+  }
+
+  #[test]
+  fn coloring_is_proper_and_minimal() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = (0..50u8)
+      .map(|i| (i * 5 % 17, i * 5 % 17 + i % 4, 1).into())
+      .collect();
+
+    let colors = coloring(&intervals);
+    assert!(conflicts(&intervals).into_iter().all(|(a, b)| colors[a] != colors[b]));
+    assert_eq!(colors.iter().max().map_or(0, |c| c + 1), k_machines::min_machines(&intervals).0);
+  }
+
+  #[test]
+  fn independent_sets_are_optimal_sets() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..60u32).map(|i| (i * 7 % 31, i * 7 % 31 + i % 5, 1 + i % 8).into()).collect();
+    let weights: Vec<u32> = intervals.iter().map(|i| i.weight).collect();
+
+    // sorted by end, then start, so that empty intervals can follow those they touch
+    let mut by_end = intervals.clone();
+    by_end.sort();
+    let (mut memo, mut optimal) = (vec![0; by_end.len()], vec![]);
+    sorted(&by_end, &mut memo, &mut optimal);
+
+    let set = max_weight_independent_set(&intervals, &weights);
+    assert_eq!(set.iter().map(|&v| weights[v]).sum::<u32>(), optimal.iter().map(|i| i.weight).sum::<u32>());
+    assert!(conflicts(&intervals).into_iter().all(|(a, b)| !(set.contains(&a) && set.contains(&b))));
+    assert!(set.windows(2).all(|pair| pair[0] < pair[1]));
+  }
+}
@@ -0,0 +1,200 @@
+use std::{cmp::Reverse, ops::Range};
+use crate::traits;
+
+/// An index over intervals, answering which of them overlap a range, or contain a point,
+/// in `O(log n + k)` for `n` intervals and `k` answers. Builds in `O(n log n)`.
+///
+/// A centered interval tree: each node keeps the intervals containing its center (sorted both by start and by end),
+/// and hands those ending before it to its left subtree, and those starting after it to its right subtree.
+/// As everywhere else in the crate, intervals are half-open.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let bookings: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 4, 1).into(),
+///   (3, 6, 1).into(),
+///   (6, 9, 1).into(),
+///   (8, 10, 1).into()
+/// ];
+/// let tree = IntervalTree::new(bookings);
+///
+/// let mut clashing = tree.overlapping(5..7);
+/// clashing.sort();
+/// assert_eq!(clashing, vec![&(3, 6, 1).into(), &(6, 9, 1).into()]);
+///
+/// assert_eq!(tree.stabbing(8).len(), 2);
+/// assert!(tree.stabbing(10).is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct IntervalTree<Time, Interval> {
+  intervals: Vec<Interval>,
+  nodes:     Vec<Node<Time>>
+}
+
+#[derive(Clone, Debug)]
+struct Node<Time> {
+  center:   Time,
+  /// The intervals containing the center, by increasing start.
+  by_start: Vec<usize>,
+  /// The intervals containing the center, by decreasing end.
+  by_end:   Vec<usize>,
+  left:     Option<usize>,
+  right:    Option<usize>
+}
+
+impl<Time, Interval> IntervalTree<Time, Interval>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>
+{
+  /// Builds the tree over `intervals`, which it takes ownership of.
+  pub fn new(intervals: Vec<Interval>) -> Self {
+    let mut tree = Self { intervals, nodes: vec![] };
+
+    let mut by_start: Vec<usize> = (0..tree.intervals.len()).collect();
+    phase!("sort", by_start.len(), by_start.sort_by_key(|&i| tree.intervals[i].start()));
+    tree.build(by_start);
+
+    tree
+  }
+
+  /// Adds the node for `indices` (sorted by start), returning where it is (or `None` if there are no intervals).
+  fn build(&mut self, indices: Vec<usize>) -> Option<usize> {
+    // the median start is a center which at least its own interval contains (zero-length intervals contain their
+    // start here, so that each node holds some interval and the tree stays finite)
+    let center = self.intervals[*indices.get(indices.len() / 2)?].start();
+    let contains = |i: &Interval| i.start() <= center && (center < i.end() || i.end() == center && i.start() == center);
+
+    let (mut left, mut here, mut right) = (vec![], vec![], vec![]);
+    for i in indices {
+      if contains(&self.intervals[i])          { here.push(i); }
+      else if self.intervals[i].end() <= center { left.push(i); }
+      else                                      { right.push(i); }
+    }
+
+    let mut by_end = here.clone();
+    by_end.sort_by_key(|&i| Reverse(self.intervals[i].end()));
+
+    let node = self.nodes.len();
+    self.nodes.push(Node { center, by_start: here, by_end, left: None, right: None });
+    self.nodes[node].left = self.build(left);
+    self.nodes[node].right = self.build(right);
+
+    Some(node)
+  }
+
+  /// The intervals overlapping `range` (those starting before it ends, and ending after it starts), in no particular
+  /// order. An empty `range` finds the intervals strictly containing its position.
+  #[must_use]
+  pub fn overlapping(&self, range: Range<Time>) -> Vec<&Interval> {
+    let mut found = vec![];
+    let mut pending: Vec<usize> = (!self.nodes.is_empty()).then_some(0).into_iter().collect();
+
+    while let Some(n) = pending.pop() {
+      let node = &self.nodes[n];
+
+      if range.end <= node.center {
+        // everything here starts by the center, and ends after the range starts (barring intervals sitting on it)
+        found.extend(node.by_start.iter()
+          .take_while(|&&i| self.intervals[i].start() < range.end)
+          .filter(|&&i| self.intervals[i].end() > range.start)
+          .map(|&i| &self.intervals[i]));
+        pending.extend(node.left);
+      } else if range.start >= node.center {
+        found.extend(node.by_end.iter()
+          .take_while(|&&i| self.intervals[i].end() > range.start)
+          .filter(|&&i| self.intervals[i].start() < range.end)
+          .map(|&i| &self.intervals[i]));
+        pending.extend(node.right);
+      } else {
+        // the range contains the center, so it overlaps everything here, and maybe things on both sides
+        found.extend(node.by_start.iter().map(|&i| &self.intervals[i]));
+        pending.extend(node.left);
+        pending.extend(node.right);
+      }
+    }
+
+    found
+  }
+
+  /// The intervals containing `point` (those starting before or at it, and ending after it), in no particular order.
+  #[must_use]
+  pub fn stabbing(&self, point: Time) -> Vec<&Interval> {
+    let mut found = vec![];
+    let mut next = (!self.nodes.is_empty()).then_some(0);
+
+    while let Some(n) = next {
+      let node = &self.nodes[n];
+
+      next = if point < node.center {
+        found.extend(node.by_start.iter()
+          .take_while(|&&i| self.intervals[i].start() <= point)
+          .map(|&i| &self.intervals[i]));
+        node.left
+      } else {
+        found.extend(node.by_end.iter()
+          .take_while(|&&i| self.intervals[i].end() > point)
+          .map(|&i| &self.intervals[i]));
+        node.right
+      };
+    }
+
+    found
+  }
+
+  /// The number of intervals in the tree.
+  #[must_use]
+  pub fn len(&self) -> usize { self.intervals.len() }
+
+  /// Whether the tree holds no intervals.
+  #[must_use]
+  pub fn is_empty(&self) -> bool { self.intervals.is_empty() }
+
+  /// The intervals in the tree, in the order they were given.
+  #[must_use]
+  pub fn intervals(&self) -> &[Interval] { &self.intervals }
+}
+
+impl<Time, Interval> FromIterator<Interval> for IntervalTree<Time, Interval>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>
+{
+  fn from_iter<Iter: IntoIterator<Item = Interval>>(iter: Iter) -> Self { Self::new(iter.into_iter().collect()) }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::IntervalTree;
+
+  #[test]
+  fn matches_scanning() {
+    let intervals: Vec<(u32, u32)> = (0..200u32).map(|i| (i * 37 % 101, i * 37 % 101 + i % 9)).collect();
+    let tree: IntervalTree<u32, (u32, u32)> = intervals.iter().copied().collect();
+    assert_eq!(tree.len(), intervals.len());
+
+    for a in 0..110 {
+      let mut stabbed: Vec<_> = tree.stabbing(a).into_iter().copied().collect();
+      let mut expected: Vec<_> = intervals.iter().copied().filter(|&(start, end)| start <= a && a < end).collect();
+      stabbed.sort();
+      expected.sort();
+      assert_eq!(stabbed, expected);
+
+      for b in a..a + 12 {
+        let mut overlapping: Vec<_> = tree.overlapping(a..b).into_iter().copied().collect();
+        let mut expected: Vec<_> = intervals.iter().copied().filter(|&(start, end)| start < b && end > a).collect();
+        overlapping.sort();
+        expected.sort();
+        assert_eq!(overlapping, expected);
+      }
+    }
+  }
+
+  #[test]
+  fn empty_and_zero_length() {
+    let empty: IntervalTree<u8, (u8, u8)> = IntervalTree::new(vec![]);
+    assert!(empty.is_empty() && empty.overlapping(0..9).is_empty() && empty.stabbing(0).is_empty());
+
+    let points = IntervalTree::new(vec![(3u8, 3u8); 50]);
+    assert_eq!(points.overlapping(2..4).len(), 50);
+    assert!(points.overlapping(3..4).is_empty() && points.stabbing(3).is_empty());
+  }
+}
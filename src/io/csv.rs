@@ -0,0 +1,119 @@
+//! CSV ingestion (behind the `csv` feature).
+//!
+//! Rows are `start,end,weight`, each field parsed with `FromStr`. Whitespace around fields is ignored,
+//! and the usual CSV quoting rules apply.
+
+use std::{fmt, io::Read, ops::Add, str::FromStr};
+use crate::WeightedInterval;
+
+/// Reasons a CSV problem couldn't be read.
+#[derive(Debug)]
+pub enum Error {
+  /// The underlying reader failed, or the input isn't valid CSV (e.g. not UTF-8).
+  Csv(::csv::Error),
+  /// A row doesn't have exactly three fields.
+  FieldCount { line: u64, found: usize },
+  /// A field couldn't be parsed into its type.
+  Parse { line: u64, column: &'static str, value: String, reason: String }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::Csv(e) => write!(f, "{e}"),
+      Error::FieldCount { line, found } => write!(f, "line {line}: expected 3 fields (start, end, weight), found {found}"),
+      Error::Parse { line, column, value, reason } => write!(f, "line {line}: invalid {column} {value:?}: {reason}")
+    }
+  }
+}
+
+impl std::error::Error for Error {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Error::Csv(e) => Some(e),
+      _             => None
+    }
+  }
+}
+
+impl From<::csv::Error> for Error {
+  fn from(e: ::csv::Error) -> Self { Error::Csv(e) }
+}
+
+/// Reads every `start,end,weight` row of `reader` into a `WeightedInterval`.
+///
+/// - If `has_headers`, the first row is skipped (whatever it contains).
+/// - Reading stops at the first malformed row, whose line number is reported in the error.
+///
+/// ```rust
+/// # use w_inter::{io, WeightedInterval};
+/// let input = "start,end,weight\n0,6,3\n1, 4, 5\n";
+///
+/// let problem: Vec<WeightedInterval<u32, i64>> = io::csv::read_problem(input.as_bytes(), true).unwrap();
+/// assert_eq!(problem, vec![(0, 6, 3).into(), (1, 4, 5).into()]);
+///
+/// let error = io::csv::read_problem::<u32, i64>("0,6,3\n1,four,5\n".as_bytes(), false).unwrap_err();
+/// assert_eq!(error.to_string(), r#"line 2: invalid end "four": invalid digit found in string"#);
+/// ```
+pub fn read_problem<Weight, Time>(
+  reader:      impl Read,
+  has_headers: bool
+) -> Result<Vec<WeightedInterval<Weight, Time>>, Error>
+  where Weight: Ord + Add + Clone + FromStr,
+        Time: Ord + Clone + FromStr,
+        Weight::Err: fmt::Display,
+        Time::Err: fmt::Display
+{
+  let mut reader = ::csv::ReaderBuilder::new()
+    .has_headers(has_headers)
+    .flexible(true) // field counts are checked below, for a friendlier error
+    .trim(::csv::Trim::All)
+    .from_reader(reader);
+
+  let mut problem = vec![];
+  for record in reader.records() {
+    let record = record?;
+    let line = record.position().map_or(0, |p| p.line());
+
+    if record.len() != 3 { return Err(Error::FieldCount { line, found: record.len() }); }
+
+    problem.push(WeightedInterval::new(
+      parse(line, "start",  &record[0])?,
+      parse(line, "end",    &record[1])?,
+      parse(line, "weight", &record[2])?
+    ));
+  }
+
+  Ok(problem)
+}
+
+fn parse<T>(line: u64, column: &'static str, value: &str) -> Result<T, Error>
+  where T: FromStr,
+        T::Err: fmt::Display
+{
+  value.parse().map_err(|e: T::Err| Error::Parse { line, column, value: value.to_owned(), reason: e.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{io::csv::{read_problem, Error}, WeightedInterval};
+
+  #[test]
+  fn quoted_fields_and_blank_lines() {
+    let input = "\"0\",\"6\",3\n\n8 , 11 , 4\n";
+    let problem: Vec<WeightedInterval<u8, u8>> = read_problem(input.as_bytes(), false).unwrap();
+    assert_eq!(problem, vec![(0, 6, 3).into(), (8, 11, 4).into()]);
+  }
+
+  #[test]
+  fn wrong_field_count() {
+    let error = read_problem::<u8, u8>("start,end,weight\n0,6,3\n1,4\n".as_bytes(), true).unwrap_err();
+    assert!(matches!(error, Error::FieldCount { line: 3, found: 2 }), "{error:?}");
+  }
+
+  #[test]
+  fn overflowing_weight() {
+    let error = read_problem::<u8, u8>("0,6,300\n".as_bytes(), false).unwrap_err();
+    assert!(matches!(error, Error::Parse { line: 1, column: "weight", .. }), "{error:?}");
+  }
+}
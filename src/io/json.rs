@@ -0,0 +1,97 @@
+//! A small JSON format for exchanging problems and solutions (behind the `serde_json` feature).
+//!
+//! A problem is an object holding its intervals, any other keys are ignored:
+//!
+//! ```json
+//! { "intervals": [ { "start": 0, "end": 6, "weight": 3 }, { "start": 1, "end": 4, "weight": 5 } ] }
+//! ```
+//!
+//! A solution additionally carries the total weight of its intervals (`null` when there are none,
+//! as weight types have no notion of zero):
+//!
+//! ```json
+//! { "total_weight": 5, "intervals": [ { "start": 1, "end": 4, "weight": 5 } ] }
+//! ```
+
+use std::{io::{Read, Write}, ops::Add};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use crate::WeightedInterval;
+
+#[derive(Deserialize)]
+#[serde(bound = "Weight: Deserialize<'de>, Time: Deserialize<'de>")]
+struct Problem<Weight: Ord + Add + Clone, Time: Ord + Clone> {
+  intervals: Vec<WeightedInterval<Weight, Time>>
+}
+
+#[derive(Serialize)]
+struct Solution<'a, Weight: Ord + Add + Clone + Serialize, Time: Ord + Clone + Serialize> {
+  total_weight: Option<Weight>,
+  intervals:    &'a [WeightedInterval<Weight, Time>]
+}
+
+/// Reads a problem (see the [module docs](self) for the format).
+///
+/// ```rust
+/// # use w_inter::{io, WeightedInterval};
+/// let input = r#"{ "intervals": [ { "start": 0, "end": 6, "weight": 3 } ], "source": "prep.py" }"#;
+///
+/// let problem: Vec<WeightedInterval<u32, i64>> = io::json::read_problem(input.as_bytes()).unwrap();
+/// assert_eq!(problem, vec![(0, 6, 3).into()]);
+/// ```
+pub fn read_problem<Weight, Time>(reader: impl Read) -> Result<Vec<WeightedInterval<Weight, Time>>, serde_json::Error>
+  where Weight: Ord + Add + Clone + DeserializeOwned,
+        Time: Ord + Clone + DeserializeOwned
+{
+  serde_json::from_reader(reader).map(|p: Problem<Weight, Time>| p.intervals)
+}
+
+/// Writes a solution, along with its total weight (see the [module docs](self) for the format).
+///
+/// ```rust
+/// # use w_inter::{io, unsorted, WeightedInterval};
+/// let problem: Vec<WeightedInterval<u32, i64>> = vec![(0, 6, 3).into(), (1, 4, 5).into()];
+///
+/// let mut output = vec![];
+/// io::json::write_solution(&mut output, &unsorted(&problem)).unwrap();
+/// assert_eq!(
+///   String::from_utf8(output).unwrap(),
+///   r#"{"total_weight":5,"intervals":[{"start":1,"end":4,"weight":5}]}"#
+/// );
+/// ```
+pub fn write_solution<Weight, Time>(
+  writer:   impl Write,
+  solution: &[WeightedInterval<Weight, Time>]
+) -> Result<(), serde_json::Error>
+  where Weight: Ord + Add<Output = Weight> + Clone + Serialize,
+        Time: Ord + Clone + Serialize
+{
+  let total_weight = solution.iter().map(|i| i.weight.clone()).reduce(|a, b| a + b);
+  serde_json::to_writer(writer, &Solution { total_weight, intervals: solution })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{io::json::{read_problem, write_solution}, WeightedInterval};
+
+  #[test]
+  fn empty_solution_has_null_total() {
+    let mut output = vec![];
+    write_solution::<u8, u8>(&mut output, &[]).unwrap();
+    assert_eq!(output, br#"{"total_weight":null,"intervals":[]}"#);
+  }
+
+  #[test]
+  fn missing_intervals_key() {
+    assert!(read_problem::<u8, u8>(r#"{ "jobs": [] }"#.as_bytes()).is_err());
+  }
+
+  #[test]
+  fn problem_round_trips_through_solution_format() {
+    // a solution is also a valid problem (the total is ignored)
+    let problem: Vec<WeightedInterval<u8, u8>> = vec![(0, 1, 2).into(), (1, 4, 5).into()];
+
+    let mut output = vec![];
+    write_solution(&mut output, &problem).unwrap();
+    assert_eq!(read_problem::<u8, u8>(&output[..]).unwrap(), problem);
+  }
+}
@@ -0,0 +1,6 @@
+//! Reading and writing problems and solutions in common interchange formats.
+
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "serde_json")]
+pub mod json;
@@ -0,0 +1,415 @@
+//! Scheduling on several identical machines: each machine runs one interval at a time.
+
+use std::{cmp::Reverse, collections::{btree_map::Entry, BTreeMap, BTreeSet, BinaryHeap}, ops::Add, rc::Rc};
+use crate::{flow::MinCostFlow, traits};
+
+/// Selects the intervals of maximum total weight that `k` machines can run, returning the intervals run by each machine
+/// (`k` lists, in chronological order). Exact, by minimum cost flow: `O(k n log n)` in interval number `n`.
+///
+/// As in [`unsorted`](crate::unsorted), intervals touching end to start fit on one machine.
+/// Weights must convert losslessly into `i128` (all primitive integers up to 64 bits do).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<WeightedInterval<u32, u8>> = vec![
+///   (0, 4, 3).into(),
+///   (1, 5, 4).into(),
+///   (2, 6, 5).into(),
+///   (4, 8, 2).into()
+/// ];
+///
+/// let machines = k_machines::max_weight(&jobs, 2);
+/// assert_eq!(machines, vec![vec![jobs[0], jobs[3]], vec![jobs[2]]]);
+/// ```
+#[must_use]
+pub fn max_weight<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  k:         usize
+) -> Vec<Vec<Interval>>
+  where Weight: Ord + Add<Output = Weight> + Clone + Into<i128>,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // two nodes per distinct time, machines arriving at the first and leaving from the second, and idle edges
+  // carrying them along from each node to the next
+  let mut times: Vec<Time> = intervals.iter().flat_map(|i| [i.start(), i.end()]).collect();
+  phase!("sort", times.len(), times.sort_unstable());
+  times.dedup();
+  let node = |t: &Time| times.binary_search(t).unwrap_or_else(|_| unreachable!());
+
+  let nodes = 2 * times.len();
+  let mut network = MinCostFlow::new(nodes);
+  for n in 1..nodes { network.add_edge(n - 1, n, k, 0); }
+
+  // running an interval moves a machine from its start to its end, earning its weight. Zero-length intervals
+  // at the same time run back to back, so one machine runs all of them, or none
+  let mut instants: Vec<Option<i128>> = vec![None; times.len()];
+  let edges: Vec<Option<usize>> = intervals.iter()
+    .map(|i| {
+      let (from, to) = (node(&i.start()), node(&i.end()));
+      if from == to { *instants[from].get_or_insert(0) -= i.weight().into(); }
+      (from < to).then(|| network.add_edge(2 * from + 1, 2 * to, 1, -i.weight().into()))
+    })
+    .collect();
+  let instants: Vec<Option<usize>> = instants.into_iter()
+    .enumerate()
+    .map(|(t, cost)| cost.map(|cost| network.add_edge(2 * t, 2 * t + 1, 1, cost)))
+    .collect();
+
+  let mut selected: Vec<usize> = vec![];
+  if nodes > 0 {
+    phase!("flow", intervals.len(), network.run(0, nodes - 1, k));
+    let edge = |i: usize| edges[i].or_else(|| instants[node(&intervals[i].start())]);
+    selected = (0..intervals.len()).filter(|&i| edge(i).is_some_and(|e| network.flow(e) == 1)).collect();
+  }
+
+  // at most `k` selected intervals run at any one time, so `k` machines are enough
+  let mut machines: Vec<Vec<Interval>> = vec![vec![]; k];
+  partition(intervals, selected, |i, machine| machines[machine].push(intervals[i].clone()));
+
+  machines
+}
+
+/// A choice reaching some state of [`max_weight_eligible`]: the heaviest, and the choice before it, should the interval
+/// `taken` have been run (on the machine given). Only the choices leading to states still reached are kept.
+struct Choice {
+  weight: i128,
+  taken:  Option<(usize, usize)>,
+  before: Option<Rc<Choice>>
+}
+
+impl Drop for Choice {
+  // one at a time, rather than recursing down a long chain of choices
+  fn drop(&mut self) {
+    let mut before = self.before.take();
+    while let Some(choice) = before {
+      before = Rc::try_unwrap(choice).ok().and_then(|mut choice| choice.before.take());
+    }
+  }
+}
+
+/// Like [`max_weight`], but interval `i` may only run on the machines `m` (numbered from `0`) for which
+/// `eligible(i, m)` holds, as when only some machines have the hardware a job needs. Exact.
+///
+/// Machines eligible for the same intervals are of a kind. When each interval may only run on machines of one kind,
+/// the kinds are separate problems, each solved by flow as in [`max_weight`]: `O(k n log n)` in interval number `n`,
+/// after asking `eligible` about every interval and machine.
+///
+/// An interval eligible for machines of several kinds must still run at most once, which no flow expresses: the
+/// problem is then NP-hard (Arkin and Silverberg, *Scheduling Jobs with Fixed Start and End Times*, 1987). In that
+/// case, by increasing start time, each interval goes to each eligible machine free by then, or to none, from every
+/// state reached (the time each machine becomes free), and of the choices reaching a state, only the heaviest is kept.
+/// `O(n mᵏ)` for at most `m` intervals overlapping at once, so this suits a few machines.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<WeightedInterval<u32, u8>> = vec![
+///   (0, 4, 3).into(),
+///   (1, 5, 4).into(),
+///   (2, 6, 5).into(),
+///   (4, 8, 2).into()
+/// ];
+/// // the first two need a GPU, which only machine 1 has
+/// let needs_gpu = [true, true, false, false];
+///
+/// let machines = k_machines::max_weight_eligible(&jobs, 2, |i, m| !needs_gpu[i] || m == 1);
+/// assert_eq!(machines, vec![vec![jobs[2]], vec![jobs[0], jobs[3]]]);
+///
+/// // with the GPU machine kept for the jobs needing it, each kind of machine is scheduled by flow
+/// let machines = k_machines::max_weight_eligible(&jobs, 2, |i, m| needs_gpu[i] == (m == 1));
+/// assert_eq!(machines, vec![vec![jobs[2]], vec![jobs[1]]]);
+/// ```
+#[must_use]
+pub fn max_weight_eligible<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  k:         usize,
+  eligible:  impl Fn(usize, usize) -> bool
+) -> Vec<Vec<Interval>>
+  where Weight: Ord + Add<Output = Weight> + Clone + Into<i128>,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // the intervals each kind of machine may run, and the kind of each machine
+  let mut numbering: BTreeMap<Vec<bool>, usize> = BTreeMap::new();
+  let kind: Vec<usize> = (0..k)
+    .map(|m| {
+      let kinds = numbering.len();
+      *numbering.entry((0..intervals.len()).map(|i| eligible(i, m)).collect()).or_insert(kinds)
+    })
+    .collect();
+  let mut kinds: Vec<Vec<bool>> = vec![vec![]; numbering.len()];
+  for (runs, number) in numbering { kinds[number] = runs; }
+  let eligible = |i: usize, m: usize| kinds[kind[m]][i];
+
+  let mut machines: Vec<Vec<Interval>> = vec![vec![]; k];
+  if (0..intervals.len()).all(|i| kinds.iter().filter(|runs| runs[i]).count() <= 1) {
+    for (of_kind, runs) in kinds.iter().enumerate() {
+      let alike: Vec<usize> = (0..k).filter(|&m| kind[m] == of_kind).collect();
+      let subset: Vec<Interval> = (0..intervals.len()).filter(|&i| runs[i]).map(|i| intervals[i].clone()).collect();
+      for (machine, run) in alike.iter().zip(max_weight(subset, alike.len())) { machines[*machine] = run; }
+    }
+    return machines;
+  }
+
+  // ties on start go to the earlier end, so that zero-length intervals can precede the others
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].start(), intervals[i].end())));
+
+  // keeps the heavier of the choices reaching a state
+  fn keep<Time: Ord>(states: &mut BTreeMap<Vec<Option<Time>>, Rc<Choice>>, state: Vec<Option<Time>>, choice: Rc<Choice>) {
+    match states.entry(state) {
+      Entry::Vacant(entry) => { entry.insert(choice); },
+      Entry::Occupied(mut entry) => if choice.weight > entry.get().weight { entry.insert(choice); }
+    }
+  }
+
+  // states are the time each machine becomes free, `None` for those free already
+  let mut states: BTreeMap<Vec<Option<Time>>, Rc<Choice>> = BTreeMap::from([(vec![None; k], Rc::new(Choice { weight: 0, taken: None, before: None }))]);
+  phase!("memoize", order.len(), for &i in &order {
+    let (start, weight) = (intervals[i].start(), intervals[i].weight().into());
+
+    for (mut state, choice) in std::mem::take(&mut states) {
+      for free_at in &mut state {
+        if free_at.as_ref().is_some_and(|end| *end <= start) { *free_at = None; }
+      }
+
+      for machine in (0..k).filter(|&m| state[m].is_none() && eligible(i, m)) {
+        let mut taken = state.clone();
+        taken[machine] = Some(intervals[i].end());
+        let run = Choice { weight: choice.weight + weight, taken: Some((i, machine)), before: Some(choice.clone()) };
+        keep(&mut states, taken, Rc::new(run));
+      }
+      keep(&mut states, state, choice);
+    }
+  });
+
+  // walking back, each machine's intervals come latest first
+  let mut choice = states.into_values().max_by_key(|choice| choice.weight);
+  phase!("reconstruct", order.len(), while let Some(c) = choice {
+    if let Some((i, machine)) = c.taken { machines[machine].push(intervals[i].clone()); }
+    choice = c.before.clone();
+  });
+  for machine in &mut machines { machine.reverse(); }
+
+  machines
+}
+
+/// Selects as many intervals as `k` machines can run, returning the intervals run by each machine (`k` lists,
+/// in chronological order). `O(n log n)` in interval number `n`.
+///
+/// Greedy, and optimal: by increasing end time, each interval goes to the machine that became free the latest
+/// before it starts, and is dropped if there is none.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<(u8, u8)> = vec![(0, 3), (1, 4), (3, 5), (2, 6), (5, 7)];
+///
+/// let machines = k_machines::max_count(&jobs, 2);
+/// assert_eq!(machines, vec![vec![(0, 3), (3, 5), (5, 7)], vec![(1, 4)]]);
+/// ```
+#[must_use]
+pub fn max_count<Time, Interval, InputContainer>(intervals: InputContainer, k: usize) -> Vec<Vec<Interval>>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // ties on end go to the later start, so that zero-length intervals can follow the others
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].end(), intervals[i].start())));
+
+  // machines by the time they become free (`None` for those yet to run anything), then lowest numbered first
+  let mut free_at: BTreeSet<(Option<Time>, Reverse<usize>)> = (0..k).map(|m| (None, Reverse(m))).collect();
+  let mut machines: Vec<Vec<Interval>> = vec![vec![]; k];
+
+  for i in order {
+    let interval = &intervals[i];
+    let Some(fit) = free_at.range(..=(Some(interval.start()), Reverse(0))).next_back().cloned() else { continue };
+    let Reverse(machine) = fit.1;
+
+    free_at.remove(&fit);
+    free_at.insert((Some(interval.end()), Reverse(machine)));
+    machines[machine].push(interval.clone());
+  }
+
+  machines
+}
+
+/// Schedules every interval on as few machines as possible, returning the machine count, and the machine (numbered
+/// from `0`) running each interval. `O(n log n)` in interval number `n`.
+///
+/// The machine count is the greatest number of intervals running at any one time, so no schedule needs fewer.
+/// Intervals touching end to start share machines, as do zero-length intervals and the intervals they don't sit
+/// strictly inside.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let lectures: Vec<(u8, u8)> = vec![(9, 11), (9, 10), (10, 12), (11, 12), (9, 12)];
+///
+/// let (rooms, room_of) = k_machines::min_machines(&lectures);
+/// assert_eq!(rooms, 3);
+/// assert_eq!(room_of, vec![1, 0, 0, 1, 2]);
+/// ```
+#[must_use]
+pub fn min_machines<Time, Interval, InputContainer>(intervals: InputContainer) -> (usize, Vec<usize>)
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let mut assignment = vec![0; intervals.len()];
+  let machines = partition(intervals, (0..intervals.len()).collect(), |i, machine| assignment[i] = machine);
+
+  (machines, assignment)
+}
+
+/// Hands the intervals at `indices` out to as few machines as possible: by increasing start time, each goes to the
+/// machine that became free the earliest, or to a new one if none is free yet. Calls `assign` with each index and
+/// its machine (in that order), and returns the machine count.
+fn partition<Time, Interval>(intervals: &[Interval], mut indices: Vec<usize>, mut assign: impl FnMut(usize, usize)) -> usize
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>
+{
+  // ties on start go to the earlier end, so that zero-length intervals can precede the others
+  phase!("sort", indices.len(), indices.sort_by_key(|&i| (intervals[i].start(), intervals[i].end())));
+
+  let mut free_at: BinaryHeap<Reverse<(Time, usize)>> = BinaryHeap::new();
+  let mut machines = 0;
+
+  for i in indices {
+    let machine = match free_at.peek() {
+      Some(&Reverse((ref end, machine))) if *end <= intervals[i].start() => { free_at.pop(); machine },
+      _                                                                    => { machines += 1; machines - 1 }
+    };
+
+    assign(i, machine);
+    free_at.push(Reverse((intervals[i].end(), machine)));
+  }
+
+  machines
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{k_machines, testing::Lcg, unsorted, Unweighted, WeightedInterval};
+
+  fn total(machines: &[Vec<WeightedInterval<u32, u32>>]) -> u32 { machines.iter().flatten().map(|i| i.weight).sum() }
+
+  #[test]
+  fn one_machine_matches_unsorted() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = vec![
+      (0, 6, 3).into(), (1, 4, 5).into(), (3, 5, 5).into(), (3, 8, 8).into(), (4, 7, 3).into(),
+      (5, 9, 7).into(), (6, 10, 3).into(), (8, 11, 4).into(), (0, 1, 2).into()
+    ];
+
+    assert_eq!(total(&k_machines::max_weight(&intervals, 1)), unsorted(&intervals).iter().map(|i| i.weight).sum::<u32>());
+    assert_eq!(total(&k_machines::max_weight(&intervals, 2)), 26);
+    assert_eq!(total(&k_machines::max_weight(&intervals, 3)), 34);
+    assert_eq!(total(&k_machines::max_weight(&intervals, 9)), 40);
+    assert_eq!(k_machines::max_weight(&intervals, 0), Vec::<Vec<_>>::new());
+  }
+
+  #[test]
+  fn machines_never_double_book() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..40).map(|i| (i % 7, i % 7 + 1 + i % 5, 1 + i % 3).into()).collect();
+
+    for k in 1..5 {
+      let machines = k_machines::max_weight(&intervals, k);
+      assert_eq!(machines.len(), k);
+      for machine in &machines {
+        assert!(machine.windows(2).all(|w| w[0].end <= w[1].start));
+      }
+    }
+  }
+
+  #[test]
+  fn eligibility_matches_brute_force() {
+    let mut rng = Lcg::new(97);
+
+    /// The most weight running intervals `i..` on eligible machines, after those on each machine so far.
+    fn best(intervals: &[WeightedInterval<u32, u32>], eligible: &[Vec<bool>], i: usize, machines: &mut [Vec<usize>]) -> u32 {
+      if i == intervals.len() { return 0; }
+      let mut most = best(intervals, eligible, i + 1, machines);
+      for m in 0..machines.len() {
+        let fits = machines[m].iter().all(|&j| intervals[j].end <= intervals[i].start || intervals[i].end <= intervals[j].start);
+        if eligible[i][m] && fits {
+          machines[m].push(i);
+          most = most.max(intervals[i].weight + best(intervals, eligible, i + 1, machines));
+          machines[m].pop();
+        }
+      }
+      most
+    }
+
+    for _ in 0..200 {
+      let (n, k) = (rng.next(8) as usize, rng.next(4) as usize);
+      let intervals: Vec<WeightedInterval<u32, u32>> = (0..n)
+        .map(|_| { let start = rng.next(8); (start, start + rng.next(5), rng.next(10)).into() })
+        .collect();
+      let eligible: Vec<Vec<bool>> = (0..n).map(|_| (0..k).map(|_| rng.next(3) != 0).collect()).collect();
+
+      let machines = k_machines::max_weight_eligible(&intervals, k, |i, m| eligible[i][m]);
+      assert_eq!(total(&machines), best(&intervals, &eligible, 0, &mut vec![vec![]; k]), "{intervals:?} {eligible:?}");
+      assert_eq!(machines.len(), k);
+      for (m, machine) in machines.iter().enumerate() {
+        assert!(machine.windows(2).all(|w| w[0].end <= w[1].start));
+        assert!(machine.iter().all(|interval| (0..n).any(|i| intervals[i] == *interval && eligible[i][m])));
+      }
+
+      // machines of two kinds, each interval running on one of them, as solved by flow
+      let (machine_kinds, interval_kinds): (Vec<u32>, Vec<u32>) = ((0..k).map(|_| rng.next(2)).collect(), (0..n).map(|_| rng.next(2)).collect());
+      let by_kind: Vec<Vec<bool>> = (0..n).map(|i| (0..k).map(|m| machine_kinds[m] == interval_kinds[i]).collect()).collect();
+      let machines = k_machines::max_weight_eligible(&intervals, k, |i, m| by_kind[i][m]);
+      assert_eq!(total(&machines), best(&intervals, &by_kind, 0, &mut vec![vec![]; k]), "{intervals:?} {by_kind:?}");
+      for (m, machine) in machines.iter().enumerate() {
+        assert!(machine.windows(2).all(|w| w[0].end <= w[1].start));
+        assert!(machine.iter().all(|interval| (0..n).any(|i| intervals[i] == *interval && by_kind[i][m])));
+      }
+
+      // every machine eligible for everything
+      assert_eq!(total(&k_machines::max_weight_eligible(&intervals, k, |_, _| true)), total(&k_machines::max_weight(&intervals, k)));
+    }
+  }
+
+  #[test]
+  fn greedy_count_matches_unit_weights() {
+    let intervals: Vec<(u32, u32)> = (0..60u32).map(|i| (i * 7 % 13, i * 7 % 13 + 1 + i % 6)).collect();
+    let weighted: Vec<WeightedInterval<u32, u32>> = intervals.iter().map(|&(start, end)| (start, end, 1).into()).collect();
+
+    for k in 0..6 {
+      let machines = k_machines::max_count(&intervals, k);
+      assert_eq!(machines.len(), k);
+      assert_eq!(machines.iter().map(Vec::len).sum::<usize>(), total(&k_machines::max_weight(&weighted, k)) as usize);
+      for machine in &machines {
+        assert!(machine.windows(2).all(|w| w[0].1 <= w[1].0));
+      }
+    }
+    let unweighted: Vec<_> = intervals.iter().copied().map(Unweighted).collect();
+    assert_eq!(k_machines::max_count(&intervals, 1)[0].len(), unsorted(&unweighted).len());
+  }
+
+  #[test]
+  fn fewest_machines() {
+    let intervals: Vec<(u32, u32)> = vec![(0, 4), (4, 4), (2, 2), (1, 3), (3, 6), (4, 6), (5, 5), (6, 7)];
+
+    let (machines, assignment) = k_machines::min_machines(&intervals);
+    assert_eq!(machines, 3);
+    assert_eq!(assignment.len(), intervals.len());
+    for a in 0..intervals.len() {
+      for b in 0..a {
+        let overlap = intervals[a].0.max(intervals[b].0) < intervals[a].1.min(intervals[b].1);
+        assert!(!overlap || assignment[a] != assignment[b]);
+      }
+    }
+
+    assert_eq!(k_machines::min_machines(Vec::<(u32, u32)>::new()), (0, vec![]));
+  }
+}
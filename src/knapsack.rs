@@ -0,0 +1,548 @@
+//! Knapsack problems: choosing the items of most total weight whose costs fit within a capacity.
+//!
+//! Items are anything [`Weighted`](crate::Weighted) and [`Cost`]ly ([`Item`] is provided). Costs are whole units (they
+//! must convert losslessly into `u64`), and the solvers are pseudo-polynomial: `O(n C)` in item number `n` and
+//! capacity `C`. As with [`sorted`](crate::sorted), the caller provides the buffers, so that solving many problems
+//! allocates once, and the solution is appended to.
+
+use std::ops::Add;
+use crate::traits;
+
+/// If a type is `Cost`ly, taking it uses up some of a capacity.
+pub trait Cost<Size> {
+  fn cost(&self) -> Size;
+}
+
+/// A batteries-included knapsack item: what it costs, and what it weighs.
+///
+/// ```rust
+/// # use w_inter::knapsack::Item;
+/// // from tuple (cost, weight)
+/// let item: Item<u32, u8> = (3, 10).into();
+/// assert_eq!(item, Item { cost: 3, weight: 10 });
+/// ```
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Item<Weight, Size> {
+  pub cost:   Size,
+  pub weight: Weight
+}
+
+impl<Weight, Size> From<(Size, Weight)> for Item<Weight, Size> {
+  fn from((cost, weight): (Size, Weight)) -> Self { Self { cost, weight } }
+}
+
+impl<Weight, Size: Clone> Cost<Size> for Item<Weight, Size> {
+  fn cost(&self) -> Size { self.cost.clone() }
+}
+
+impl<Weight: Ord + Add + Clone, Size> traits::Weighted<Weight> for Item<Weight, Size> {
+  fn weight(&self) -> Weight { self.weight.clone() }
+}
+
+/// A size as a number of units, for indexing.
+fn units<Size: Into<u64>>(size: Size) -> usize {
+  usize::try_from(size.into()).expect("sizes must fit in memory")
+}
+
+/// Chooses the items of most total weight whose costs add up to at most `capacity`, each taken at most once. `O(n C)`
+/// in item number `n` and capacity `C`.
+///
+/// - `memoization` is an existing buffer that will be written to, and must hold at least `(n + 1) (C + 1)` elements.
+///   Afterwards, `memoization[k (C + 1) + c]` is the most weight the first `k` items fit in a capacity of `c`.
+/// - `solution` is an existing buffer that the indices of the chosen items will be appended to, in descending order.
+///
+/// ```rust
+/// # use w_inter::{*, knapsack::Item};
+/// let items: Vec<Item<u32, u8>> = vec![(5, 10).into(), (4, 40).into(), (6, 30).into(), (3, 50).into()];
+///
+/// let mut memo = vec![0; (items.len() + 1) * (10 + 1)];
+/// let mut solution = vec![];
+/// knapsack::zero_one(&items, 10, &mut memo, &mut solution);
+///
+/// assert_eq!(solution, vec![3, 1]);
+/// assert_eq!(memo[memo.len() - 1], 90);
+/// ```
+///
+/// # Panics
+/// If the capacity doesn't fit in memory.
+pub fn zero_one<Weight, Size, Item>(
+  items:       &[Item],
+  capacity:    Size,
+  memoization: &mut [Weight],
+  solution:    &mut Vec<usize>
+) where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Size: Into<u64>,
+        Item: Cost<Size> + traits::Weighted<Weight>
+{
+  bounded(items, |_| 1, capacity, memoization, solution);
+}
+
+/// Like [`zero_one`], but item `i` can be taken up to `count(i)` times. `O(C Σ m)`, where each item counts for `m`, the
+/// most copies of it that fit. The buffers are as for `zero_one`, and the index of an item is appended once for each
+/// copy taken.
+///
+/// ```rust
+/// # use w_inter::{*, knapsack::Item};
+/// let items: Vec<Item<u32, u8>> = vec![(5, 10).into(), (4, 40).into(), (6, 30).into(), (3, 50).into()];
+/// let stock = [1, 1, 1, 2];
+///
+/// let mut memo = vec![0; (items.len() + 1) * (10 + 1)];
+/// let mut solution = vec![];
+/// knapsack::bounded(&items, |i| stock[i], 10, &mut memo, &mut solution);
+///
+/// assert_eq!(solution, vec![3, 3, 1]);
+/// assert_eq!(memo[memo.len() - 1], 140);
+/// ```
+///
+/// # Panics
+/// If the capacity doesn't fit in memory.
+pub fn bounded<Weight, Size, Item>(
+  items:       &[Item],
+  count:       impl Fn(usize) -> usize,
+  capacity:    Size,
+  memoization: &mut [Weight],
+  solution:    &mut Vec<usize>
+) where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Size: Into<u64>,
+        Item: Cost<Size> + traits::Weighted<Weight>
+{
+  let width = units(capacity) + 1;
+  let memo = &mut memoization[..(items.len() + 1) * width];
+  memo[..width].fill(Weight::default());
+
+  // row `k` is the most weight the first `k` items fit in each capacity, taking some copies of item `k - 1` after the
+  // best of the row before for what is left
+  phase!("memoize", items.len(), for (i, item) in items.iter().enumerate() {
+    let (cost, weight) = (units(item.cost()), item.weight());
+    let (before, row) = memo[i * width..(i + 2) * width].split_at_mut(width);
+    for c in 0..width {
+      row[c] = before[c].clone();
+      let mut taken = Weight::default();
+      for copies in 1..=count(i) {
+        let Some(left) = c.checked_sub(copies * cost) else { break };
+        taken = taken + weight.clone();
+        let with = before[left].clone() + taken.clone();
+        if with > row[c] { row[c] = with; }
+      }
+    }
+  });
+
+  // walking back up the rows, the fewest copies making up the difference were taken
+  phase!("reconstruct", items.len(), {
+    let mut c = width - 1;
+    for i in (0..items.len()).rev() {
+      let (cost, weight) = (units(items[i].cost()), items[i].weight());
+      let (before, row) = (&memo[i * width..(i + 1) * width], &memo[(i + 1) * width..(i + 2) * width]);
+      let (mut copies, mut taken) = (0, Weight::default());
+      while before[c - copies * cost].clone() + taken.clone() != row[c] {
+        copies += 1;
+        taken = taken + weight.clone();
+        solution.push(i);
+      }
+      c -= copies * cost;
+    }
+  });
+}
+
+/// Like [`zero_one`], but any number of copies of each item can be taken. `O(n C)` in item number `n` and capacity
+/// `C`, with `memoization` only needing to hold `C + 1` elements: afterwards, `memoization[c]` is the most weight fitting
+/// in a capacity of `c`. The index of an item is appended once for each copy taken.
+///
+/// ```rust
+/// # use w_inter::{*, knapsack::Item};
+/// let items: Vec<Item<u32, u8>> = vec![(5, 10).into(), (4, 40).into(), (6, 30).into(), (3, 50).into()];
+///
+/// let mut memo = vec![0; 10 + 1];
+/// let mut solution = vec![];
+/// knapsack::unbounded(&items, 10, &mut memo, &mut solution);
+///
+/// assert_eq!(solution, vec![3, 3, 3]);
+/// assert_eq!(memo[10], 150);
+/// ```
+///
+/// # Panics
+/// If an item costs nothing (any number of copies would fit), or the capacity doesn't fit in memory.
+pub fn unbounded<Weight, Size, Item>(
+  items:       &[Item],
+  capacity:    Size,
+  memoization: &mut [Weight],
+  solution:    &mut Vec<usize>
+) where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Size: Into<u64>,
+        Item: Cost<Size> + traits::Weighted<Weight>
+{
+  let cost = |i: usize| units(items[i].cost());
+  assert!((0..items.len()).all(|i| cost(i) > 0), "every item must cost something");
+
+  let width = units(capacity) + 1;
+  let memo = &mut memoization[..width];
+
+  // the most weight fitting in each capacity is that of a capacity one less, or of some item after the best for what
+  // is left
+  phase!("memoize", items.len(), for c in 0..width {
+    memo[c] = if c == 0 { Weight::default() } else { memo[c - 1].clone() };
+    for (i, item) in items.iter().enumerate() {
+      let Some(left) = c.checked_sub(cost(i)) else { continue };
+      let with = memo[left].clone() + item.weight();
+      if with > memo[c] { memo[c] = with; }
+    }
+  });
+
+  phase!("reconstruct", items.len(), {
+    let mut c = width - 1;
+    while c > 0 {
+      if memo[c] == memo[c - 1] { c -= 1; continue; }
+
+      let i = (0..items.len())
+        .find(|&i| cost(i) <= c && memo[c - cost(i)].clone() + items[i].weight() == memo[c])
+        .unwrap_or_else(|| unreachable!());
+      solution.push(i);
+      c -= cost(i);
+    }
+  });
+}
+
+/// Whether some of `values` add up to exactly `target`, each used at most once, by shifting bitsets of the sums within
+/// reach. `O(n T / 64)` in value number `n` and target `T`.
+///
+/// - `memoization` is an existing buffer that will be written to, and must hold at least `(n + 1) (T / 64 + 1)` words.
+///   Afterwards, bit `t` of its `k`th run of `T / 64 + 1` words is set when some of the first `k` values add up to `t`.
+/// - `solution` is an existing buffer that, should `target` be within reach, the indices of some values adding up to it
+///   will be appended to, in descending order.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let values = [3u32, 34, 4, 12, 5, 2];
+///
+/// let mut memo = vec![0; (values.len() + 1) * (9 / 64 + 1)];
+/// let mut solution = vec![];
+/// assert!(knapsack::subset_sum(&values, 9, &mut memo, &mut solution));
+/// assert_eq!(solution, vec![4, 2]);
+///
+/// let mut memo = vec![0; (values.len() + 1) * (30 / 64 + 1)];
+/// assert!(!knapsack::subset_sum(&values, 30, &mut memo, &mut solution));
+/// ```
+///
+/// # Panics
+/// If the target doesn't fit in memory.
+pub fn subset_sum<Size>(values: &[Size], target: Size, memoization: &mut [u64], solution: &mut Vec<usize>) -> bool
+  where Size: Clone + Into<u64>
+{
+  let target = units(target);
+  let words = target / 64 + 1;
+  let memo = &mut memoization[..(values.len() + 1) * words];
+  memo[..words].fill(0);
+  memo[0] = 1;
+
+  // the sums within reach of the first `k + 1` values are those of the first `k`, and those shifted by the next value
+  phase!("memoize", values.len(), for (k, value) in values.iter().enumerate() {
+    let value = units(value.clone());
+    let (shift, bits) = (value / 64, value % 64);
+    let (before, row) = memo[k * words..(k + 2) * words].split_at_mut(words);
+    for w in 0..words {
+      row[w] = before[w];
+      if w >= shift { row[w] |= before[w - shift] << bits; }
+      if bits > 0 && w > shift { row[w] |= before[w - shift - 1] >> (64 - bits); }
+    }
+  });
+
+  let reached = |k: usize, t: usize| memo[k * words + t / 64] >> (t % 64) & 1 == 1;
+  if !reached(values.len(), target) { return false; }
+
+  // walking back up the rows, a value was used wherever the sum left wasn't already within reach without it
+  phase!("reconstruct", values.len(), {
+    let mut t = target;
+    for k in (0..values.len()).rev() {
+      if !reached(k, t) {
+        solution.push(k);
+        t -= units(values[k].clone());
+      }
+    }
+  });
+  true
+}
+
+/// The fewest coins, of any number of each of `denominations`, adding up to exactly `amount` (`None` if none do).
+/// `O(n A)` in denomination number `n` and amount `A`.
+///
+/// - `memoization` is an existing buffer that will be written to, and must hold at least `A + 1` elements. Afterwards,
+///   `memoization[a]` is the fewest coins adding up to `a`.
+/// - `solution` is an existing buffer that, should `amount` be within reach, the index of the denomination of each coin
+///   will be appended to.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let denominations = [1u32, 3, 4];
+///
+/// let mut memo = vec![None; 6 + 1];
+/// let mut solution = vec![];
+/// assert_eq!(knapsack::min_coins(&denominations, 6, &mut memo, &mut solution), Some(2));
+/// assert_eq!(solution, vec![1, 1]);
+/// ```
+///
+/// # Panics
+/// If the amount doesn't fit in memory.
+pub fn min_coins<Size>(
+  denominations: &[Size],
+  amount:        Size,
+  memoization:   &mut [Option<usize>],
+  solution:      &mut Vec<usize>
+) -> Option<usize>
+  where Size: Clone + Into<u64>
+{
+  // coins worth nothing never help
+  let worth = |i: usize| units(denominations[i].clone());
+  let coins = || (0..denominations.len()).filter(|&i| worth(i) > 0);
+
+  let width = units(amount) + 1;
+  let memo = &mut memoization[..width];
+  memo[0] = Some(0);
+  phase!("memoize", denominations.len(), for a in 1..width {
+    memo[a] = coins().filter_map(|i| memo[a.checked_sub(worth(i))?]).min().map(|fewest| fewest + 1);
+  });
+
+  let fewest = memo[width - 1]?;
+  phase!("reconstruct", denominations.len(), {
+    let mut a = width - 1;
+    while a > 0 {
+      let i = coins()
+        .find(|&i| a >= worth(i) && memo[a - worth(i)].map(|count| count + 1) == memo[a])
+        .unwrap_or_else(|| unreachable!());
+      solution.push(i);
+      a -= worth(i);
+    }
+  });
+  Some(fewest)
+}
+
+/// The number of ways to make up exactly `amount` from any number of each of `denominations`, regardless of order.
+/// `O(n A)` in denomination number `n` and amount `A`.
+///
+/// Ways are counted in any type that can be added and made from `0` and `1`, e.g. `u128` for counts too large for `u64`,
+/// or `f64` for an approximation. Equal denominations count as different coins.
+///
+/// - `memoization` is an existing buffer that will be written to, and must hold at least `A + 1` elements. Afterwards,
+///   `memoization[a]` is the number of ways to make up `a`.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let denominations = [1u32, 2, 5];
+///
+/// // 5, 2 + 2 + 1, 2 + 1 + 1 + 1, 1 + 1 + 1 + 1 + 1
+/// let mut memo = vec![0u64; 5 + 1];
+/// assert_eq!(knapsack::count_ways(&denominations, 5, &mut memo), 4);
+/// ```
+///
+/// # Panics
+/// If a denomination is worth nothing (any number of such coins would add nothing), or the amount doesn't fit in memory.
+pub fn count_ways<Size, Ways>(denominations: &[Size], amount: Size, memoization: &mut [Ways]) -> Ways
+  where Size: Clone + Into<u64>,
+        Ways: Add<Output = Ways> + Clone + From<u8>
+{
+  let width = units(amount) + 1;
+  let memo = &mut memoization[..width];
+  memo.fill(Ways::from(0));
+  memo[0] = Ways::from(1);
+
+  // taking the denominations one at a time counts each combination once, with its coins in that order
+  phase!("memoize", denominations.len(), for denomination in denominations {
+    let worth = units(denomination.clone());
+    assert!(worth > 0, "every denomination must be worth something");
+    for a in worth..width { memo[a] = memo[a].clone() + memo[a - worth].clone(); }
+  });
+
+  memo[width - 1].clone()
+}
+
+/// Cuts a rod `length` long into the pieces selling for the most, a piece `k` long selling for `prices[k - 1]`. Pieces
+/// longer than `prices` lists sell for nothing. `O(L P)` in length `L` and price number `P`.
+///
+/// - `memoization` is an existing buffer that will be written to, and must hold at least `L + 1` elements. Afterwards,
+///   `memoization[l]` is the most a rod `l` long sells for.
+/// - `solution` is an existing buffer that the positions of the cuts will be appended to, ascending.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let prices = [1u32, 5, 8, 9, 10, 17, 17, 20];
+///
+/// let mut memo = vec![0; 7 + 1];
+/// let mut solution = vec![];
+/// knapsack::rod_cutting(&prices, 7u32, &mut memo, &mut solution);
+///
+/// // pieces 6 and 1 long
+/// assert_eq!(solution, vec![6]);
+/// assert_eq!(memo[7], 18);
+/// ```
+///
+/// # Panics
+/// If the length doesn't fit in memory.
+pub fn rod_cutting<Weight, Size>(prices: &[Weight], length: Size, memoization: &mut [Weight], solution: &mut Vec<usize>)
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Size: Into<u64>
+{
+  let width = units(length) + 1;
+  let memo = &mut memoization[..width];
+
+  // the most a rod sells for is that of its first piece, and the most the rest sells for, or nothing if it is sold whole
+  // for nothing
+  let pieces = |l: usize| 1..=l.min(prices.len());
+  phase!("memoize", width, for l in 0..width {
+    memo[l] = pieces(l)
+      .map(|k| prices[k - 1].clone() + memo[l - k].clone())
+      .fold(Weight::default(), Weight::max);
+  });
+
+  phase!("reconstruct", width, {
+    let (mut l, mut position) = (width - 1, 0);
+    // cutting no more than needed: the rest is sold whole once it sells for nothing anyway, and long pieces come first
+    while memo[l] != Weight::default() {
+      let k = pieces(l).rev().find(|&k| prices[k - 1].clone() + memo[l - k].clone() == memo[l]).unwrap_or_else(|| unreachable!());
+      l -= k;
+      position += k;
+      if l == 0 { break; }
+      solution.push(position);
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{knapsack::{self, Item}, testing::{subsets, Lcg}};
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(37);
+
+    let (mut memo, mut solution) = (vec![], vec![]);
+    for _ in 0..300 {
+      let n = rng.next(6) as usize;
+      let capacity = rng.next(16) as u8;
+      let items: Vec<Item<u32, u8>> = (0..n).map(|_| (rng.next(6) as u8, rng.next(20)).into()).collect();
+      let stock: Vec<usize> = (0..n).map(|_| rng.next(4) as usize).collect();
+      let width = usize::from(capacity) + 1;
+
+      // every way of taking up to 3 copies of each item
+      let (mut zero_one, mut bounded, mut unbounded) = (0, 0, 0);
+      for choice in 0..4usize.pow(n as u32) {
+        let copies: Vec<usize> = (0..n).map(|i| choice / 4usize.pow(i as u32) % 4).collect();
+        let cost = (0..n).map(|i| copies[i] * usize::from(items[i].cost)).sum::<usize>();
+        let weight = (0..n).map(|i| copies[i] as u32 * items[i].weight).sum::<u32>();
+        if cost > usize::from(capacity) { continue; }
+
+        if copies.iter().all(|&c| c <= 1) { zero_one = zero_one.max(weight); }
+        if (0..n).all(|i| copies[i] <= stock[i]) { bounded = bounded.max(weight); }
+        if items.iter().all(|item| item.cost > 0) { unbounded = unbounded.max(weight); }
+      }
+
+      let check = |solution: &[usize], limit: &dyn Fn(usize) -> usize, best: u32| {
+        assert_eq!(solution.iter().map(|&i| items[i].weight).sum::<u32>(), best, "{items:?} {capacity}");
+        assert!(solution.iter().map(|&i| usize::from(items[i].cost)).sum::<usize>() <= usize::from(capacity));
+        assert!((0..n).all(|i| solution.iter().filter(|&&j| j == i).count() <= limit(i)));
+      };
+
+      memo.resize((n + 1) * width, 0);
+      solution.clear();
+      knapsack::zero_one(&items, capacity, &mut memo, &mut solution);
+      assert_eq!(memo[(n + 1) * width - 1], zero_one);
+      check(&solution, &|_| 1, zero_one);
+
+      solution.clear();
+      knapsack::bounded(&items, |i| stock[i], capacity, &mut memo, &mut solution);
+      assert_eq!(memo[(n + 1) * width - 1], bounded);
+      check(&solution, &|i| stock[i], bounded);
+
+      // with costs of at least 1 and capacities below 16, an item worth taking 4 times would make a better brute force
+      if items.iter().all(|item| item.cost >= 4) {
+        solution.clear();
+        knapsack::unbounded(&items, capacity, &mut memo, &mut solution);
+        assert_eq!(memo[width - 1], unbounded);
+        check(&solution, &|_| usize::MAX, unbounded);
+      }
+    }
+  }
+
+  #[test]
+  fn subset_sum_matches_brute_force() {
+    let mut rng = Lcg::new(41);
+
+    let (mut memo, mut solution) = (vec![], vec![]);
+    for _ in 0..300 {
+      let n = rng.next(10) as usize;
+      let values: Vec<u32> = (0..n).map(|_| if rng.next(3) == 0 { rng.next(200) } else { rng.next(70) }).collect();
+      let target = rng.next(400);
+
+      let reachable = subsets(n).any(|set| set.iter().map(|&i| values[i]).sum::<u32>() == target);
+
+      memo.resize((n + 1) * (target as usize / 64 + 1), 0);
+      solution.clear();
+      assert_eq!(knapsack::subset_sum(&values, target, &mut memo, &mut solution), reachable, "{values:?} {target}");
+      if reachable {
+        assert_eq!(solution.iter().map(|&i| values[i]).sum::<u32>(), target);
+        assert!(solution.windows(2).all(|pair| pair[1] < pair[0]));
+      }
+      else { assert!(solution.is_empty()); }
+    }
+  }
+
+  #[test]
+  fn coin_change_matches_brute_force() {
+    let mut rng = Lcg::new(43);
+
+    /// The fewest coins and the number of ways making up `amount` from the denominations from `i` on.
+    fn brute_force(denominations: &[u32], i: usize, amount: u32) -> (Option<usize>, u64) {
+      if amount == 0 { return (Some(0), 1); }
+      if i == denominations.len() { return (None, 0); }
+      (0..=amount / denominations[i]).fold((None, 0), |(fewest, ways), copies| {
+        let (rest, rest_ways) = brute_force(denominations, i + 1, amount - copies * denominations[i]);
+        let count = rest.map(|rest| rest + copies as usize);
+        (fewest.into_iter().chain(count).min(), ways + rest_ways)
+      })
+    }
+
+    let (mut memo, mut ways, mut solution) = (vec![], vec![], vec![]);
+    for _ in 0..300 {
+      let denominations: Vec<u32> = (0..rng.next(5)).map(|_| 1 + rng.next(9)).collect();
+      let amount = rng.next(30);
+      let (fewest, expected) = brute_force(&denominations, 0, amount);
+
+      memo.resize(amount as usize + 1, None);
+      solution.clear();
+      assert_eq!(knapsack::min_coins(&denominations, amount, &mut memo, &mut solution), fewest, "{denominations:?} {amount}");
+      assert_eq!(solution.len(), fewest.unwrap_or(0));
+      if fewest.is_some() { assert_eq!(solution.iter().map(|&i| denominations[i]).sum::<u32>(), amount); }
+
+      ways.resize(amount as usize + 1, 0u64);
+      assert_eq!(knapsack::count_ways(&denominations, amount, &mut ways), expected);
+    }
+  }
+
+  #[test]
+  fn rod_cutting_matches_brute_force() {
+    let mut rng = Lcg::new(47);
+
+    let (mut memo, mut solution) = (vec![], vec![]);
+    for _ in 0..300 {
+      let prices: Vec<u32> = (0..rng.next(8)).map(|_| rng.next(20)).collect();
+      let length = rng.next(13) as usize;
+      let price = |k: usize| k.checked_sub(1).and_then(|k| prices.get(k)).copied().unwrap_or(0);
+
+      // every set of cuts
+      let best = subsets(length.saturating_sub(1))
+        .map(|cuts| {
+          let positions: Vec<usize> = cuts.iter().map(|c| c + 1).chain([length]).collect();
+          positions.iter().scan(0, |last, &p| { let k = p - *last; *last = p; Some(price(k)) }).sum::<u32>()
+        })
+        .max()
+        .unwrap_or(0);
+
+      memo.resize(length + 1, 0);
+      solution.clear();
+      knapsack::rod_cutting(&prices, length as u64, &mut memo, &mut solution);
+      assert_eq!(memo[length], best, "{prices:?} {length}");
+
+      assert!(solution.windows(2).all(|pair| pair[0] < pair[1]) && solution.iter().all(|&p| 0 < p && p < length));
+      let ends: Vec<usize> = solution.iter().copied().chain([length]).collect();
+      assert_eq!(ends.iter().scan(0, |last, &p| { let k = p - *last; *last = p; Some(price(k)) }).sum::<u32>(), best);
+    }
+  }
+}
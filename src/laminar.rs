@@ -0,0 +1,115 @@
+use std::{cmp::Reverse, ops::Add};
+use crate::{solvers::solve_by, traits};
+
+/// Like [`unsorted`](crate::unsorted), but selected intervals may also nest: any two are either disjoint, or one lies
+/// within the other, never partially overlapping (a laminar family), as when booking a whole floor doesn't rule out
+/// booking rooms on it. `O(n² log n)` in interval number.
+///
+/// The heaviest family within each interval (itself included) is that interval and the heaviest disjoint selection of
+/// the families within it, found from the innermost out. Intervals with the same bounds nest in one another, so any
+/// number of them may be selected. An empty interval is within another only strictly inside it, merely touching those
+/// it sits at the start or end of.
+///
+/// The selection is in descending order of end, each interval before those within it. Weights are assumed
+/// non-negative.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let bookings: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 10, 4).into(), // the whole floor
+///   (1, 4, 3).into(),  // a room on it
+///   (5, 9, 3).into(),  // another
+///   (3, 7, 5).into()   // partially overlapping both
+/// ];
+///
+/// assert_eq!(unsorted_laminar(&bookings), vec![bookings[0], bookings[2], bookings[1]]);
+/// ```
+#[must_use]
+pub fn unsorted_laminar<Weight, Time, Interval, InputContainer>(intervals: InputContainer) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // by end, the shorter first, so that each interval comes after those within it (and after those with the same
+  // bounds and a lower index). Empty intervals come last, after those they touch the end of
+  let empty = |i: usize| intervals[i].start() == intervals[i].end();
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].end(), empty(i), Reverse(intervals[i].start()))));
+
+  // the positions before `k` (in order) of the intervals within `order[k]`
+  let within = |k: usize| -> Vec<usize> {
+    let (start, end) = (intervals[order[k]].start(), intervals[order[k]].end());
+    (0..k)
+      .filter(|&m| {
+        let (inner_start, inner_end) = (intervals[order[m]].start(), intervals[order[m]].end());
+        if empty(order[m]) { start < inner_start && inner_end < end } else { start <= inner_start && inner_end <= end }
+      })
+      .collect()
+  };
+
+  // the positions of the heaviest disjoint selection among `members` (by position), weighing `nested`
+  let disjoint = |members: &[usize], nested: &[Weight]| -> Vec<usize> {
+    let ends: Vec<Time> = members.iter().map(|&m| intervals[order[m]].end()).collect();
+
+    let mut selected = vec![];
+    solve_by(
+      members.len(),
+      |x| nested[members[x]].clone(),
+      |x| ends[..x].partition_point(|e| *e <= intervals[order[members[x]]].start()).checked_sub(1),
+      &mut selected
+    );
+    selected.into_iter().map(|x| members[x]).collect()
+  };
+
+  // `nested[k]` is the weight of the heaviest family within `order[k]`, including it
+  let mut nested: Vec<Weight> = Vec::with_capacity(order.len());
+  phase!("memoize", order.len(), for k in 0..order.len() {
+    let inner = disjoint(&within(k), &nested);
+    nested.push(inner.into_iter().fold(intervals[order[k]].weight(), |total, m| total + nested[m].clone()));
+  });
+
+  // each interval selected goes before the selection within it, and then its later siblings
+  let mut selected = vec![];
+  let mut stack = disjoint(&(0..order.len()).collect::<Vec<usize>>(), &nested);
+  stack.reverse();
+  phase!("reconstruct", order.len(), while let Some(k) = stack.pop() {
+    selected.push(intervals[order[k]].clone());
+    stack.extend(disjoint(&within(k), &nested).into_iter().rev());
+  });
+
+  selected
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{testing::{brute_force_best, pairwise, Lcg}, unsorted, unsorted_laminar, WeightedInterval};
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(79);
+
+    let laminar = |a: &WeightedInterval<u32, u8>, b: &WeightedInterval<u32, u8>| {
+      a.end <= b.start || b.end <= a.start || (a.start <= b.start && b.end <= a.end) || (b.start <= a.start && a.end <= b.end)
+    };
+
+    for _ in 0..300 {
+      let n = rng.next(11) as usize;
+      let intervals: Vec<WeightedInterval<u32, u8>> = (0..n)
+        .map(|_| { let start = rng.next(10) as u8; (start, start + rng.next(6) as u8, rng.next(10)).into() })
+        .collect();
+
+      let best: u32 = brute_force_best(&intervals, |set| pairwise(set, |a, b| laminar(&intervals[a], &intervals[b])));
+
+      let selected = unsorted_laminar(&intervals);
+      assert_eq!(selected.iter().map(|i| i.weight).sum::<u32>(), best, "{intervals:?}");
+      assert!(selected.iter().enumerate().all(|(x, a)| selected[..x].iter().all(|b| laminar(a, b))));
+      assert!(selected.windows(2).all(|pair| pair[1].end <= pair[0].end));
+
+      // a disjoint selection is laminar
+      assert!(unsorted(&intervals).iter().map(|i| i.weight).sum::<u32>() <= best);
+    }
+  }
+}
@@ -0,0 +1,89 @@
+use std::ops::{Add, Sub};
+use crate::{solvers::solve_by, traits};
+
+/// Solves the problem when intervals are jobs with due times, each worth its weight less a penalty for finishing late.
+/// `O(n log n)` in interval number.
+///
+/// Job `i` is due by `due(i)`: ending by then, it is worth its weight, and ending `late = end - due(i)` after it, its
+/// weight less `penalty(i, late)` (e.g. `rate[i] * late` for weighted tardiness, or a flat fee for a missed deadline).
+/// Jobs left worth nothing or less are never selected.
+///
+/// `i` is an index into `intervals`, which can be in any order. Returns the indices of the optimal set, in descending
+/// end order.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<WeightedInterval<u32, u32>> = vec![(0, 5, 10).into(), (4, 9, 12).into(), (5, 8, 6).into()];
+/// let due = [5, 6, 8];
+///
+/// // every unit of time late costs 2
+/// let selected = unsorted_with_lateness(&jobs, |i| due[i], |_, late: u32| 2 * late);
+/// assert_eq!(selected, vec![2, 0]);
+/// ```
+#[must_use]
+pub fn unsorted_with_lateness<Weight, Time, Lateness, Interval, InputContainer>(
+  intervals: InputContainer,
+  due:       impl Fn(usize) -> Time,
+  penalty:   impl Fn(usize, Lateness) -> Weight
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Sub<Output = Weight> + Clone + Default,
+        Time: Ord + Sub<Output = Lateness>,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // what each job is worth, where it is
+  let worth: Vec<Weight> = (0..intervals.len())
+    .map(|i| {
+      let (end, due) = (intervals[i].end(), due(i));
+      if end > due {
+        let penalty = penalty(i, end - due);
+        let weight = intervals[i].weight();
+        if penalty >= weight { Weight::default() } else { weight - penalty }
+      }
+      else { intervals[i].weight() }
+    })
+    .collect();
+
+  // visit the jobs worth selecting in order of end time
+  let mut order: Vec<usize> = (0..intervals.len()).filter(|&i| worth[i] > Weight::default()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  let mut selected = vec![];
+  solve_by(
+    order.len(),
+    |k| worth[order[k]].clone(),
+    |k| ends[..k].partition_point(|e| *e <= intervals[order[k]].start()).checked_sub(1),
+    &mut selected
+  );
+  selected.into_iter().map(|k| order[k]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{unsorted, unsorted_with_lateness, WeightedInterval};
+
+  #[test]
+  fn matches_solving_with_net_weights() {
+    let jobs: Vec<WeightedInterval<u32, u32>> = (0..60u32).map(|i| (i * 17 % 53, i * 17 % 53 + 1 + i % 6, 5 + i % 11).into()).collect();
+    let due: Vec<u32> = (0..60u32).map(|i| i * 17 % 53 + i % 9).collect();
+    let penalty = |i: usize, late: u32| late * (1 + i as u32 % 3);
+
+    let net = |i: usize| {
+      let late = jobs[i].end.saturating_sub(due[i]);
+      jobs[i].weight.saturating_sub(if late > 0 { penalty(i, late) } else { 0 })
+    };
+    let reweighted: Vec<WeightedInterval<u32, u32>> = (0..jobs.len())
+      .filter(|&i| net(i) > 0)
+      .map(|i| WeightedInterval::new(jobs[i].start, jobs[i].end, net(i)))
+      .collect();
+    let expected = unsorted(&reweighted).iter().map(|i| i.weight).sum::<u32>();
+
+    let selected = unsorted_with_lateness(&jobs, |i| due[i], penalty);
+    assert_eq!(selected.iter().map(|&i| net(i)).sum::<u32>(), expected);
+    assert!(selected.iter().all(|&i| net(i) > 0));
+    assert!(selected.windows(2).all(|pair| jobs[pair[1]].end <= jobs[pair[0]].start));
+  }
+}
@@ -0,0 +1,94 @@
+use std::{cmp::Ordering, ops::Add};
+
+/// A weight compared by `Primary` first, and by `Secondary` among equal primaries. Adds component-wise.
+/// 
+/// Tuples are ordered this way too, but can't be added, so they can't be weights. This can, and converts from
+/// and into `(primary, secondary)` tuples. Nest it for more criteria, and wrap components in [`Minimize`]
+/// for criteria where less is better.
+/// 
+/// ```rust
+/// # use w_inter::*;
+/// // revenue first, then as few crew changes as possible
+/// type Score = Lexicographic<u32, Minimize<u8>>;
+/// 
+/// let shifts: Vec<((u8, u8), Score)> = vec![
+///   ((0, 4), (10, Minimize(1)).into()),
+///   ((4, 8), (10, Minimize(1)).into()),
+///   ((0, 8), (20, Minimize(1)).into())
+/// ];
+/// 
+/// assert_eq!(unsorted(&shifts), vec![shifts[2]]);
+/// 
+/// let (revenue, Minimize(changes)) = shifts.iter().map(|s| s.1).reduce(|a, b| a + b).unwrap().into();
+/// assert_eq!((revenue, changes), (40, 3));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lexicographic<Primary, Secondary>(pub Primary, pub Secondary);
+
+impl<Primary: Add<Output = Primary>, Secondary: Add<Output = Secondary>> Add for Lexicographic<Primary, Secondary> {
+  type Output = Self;
+  fn add(self, other: Self) -> Self { Lexicographic(self.0 + other.0, self.1 + other.1) }
+}
+
+impl<Primary, Secondary> From<(Primary, Secondary)> for Lexicographic<Primary, Secondary> {
+  fn from(tuple: (Primary, Secondary)) -> Self { Lexicographic(tuple.0, tuple.1) }
+}
+
+impl<Primary, Secondary> From<Lexicographic<Primary, Secondary>> for (Primary, Secondary) {
+  fn from(weight: Lexicographic<Primary, Secondary>) -> Self { (weight.0, weight.1) }
+}
+
+/// A weight where less is better: ordered in reverse, so that maximizing it minimizes the inner value.
+/// 
+/// Unlike `std::cmp::Reverse`, it can be added up, so it can be used as (part of) a weight.
+/// 
+/// ```rust
+/// # use w_inter::Minimize;
+/// assert!(Minimize(1) > Minimize(2));
+/// assert_eq!(Minimize(1) + Minimize(2), Minimize(3));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Minimize<T>(pub T);
+
+impl<T: Ord> Ord for Minimize<T> {
+  fn cmp(&self, other: &Self) -> Ordering { other.0.cmp(&self.0) }
+}
+
+impl<T: Ord> PartialOrd for Minimize<T> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<T: Add<Output = T>> Add for Minimize<T> {
+  type Output = Self;
+  fn add(self, other: Self) -> Self { Minimize(self.0 + other.0) }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{unsorted, Lexicographic, Minimize, WeightedInterval};
+
+  #[test]
+  fn secondary_breaks_ties_only() {
+    type Weight = Lexicographic<u8, Minimize<u8>>;
+    let weight = |primary, secondary| Weight::from((primary, Minimize(secondary)));
+
+    let intervals: Vec<WeightedInterval<Weight, u8>> = vec![
+      (0, 2, weight(3, 1)).into(),
+      (2, 4, weight(3, 1)).into(),
+      (0, 4, weight(6, 5)).into(),
+      (0, 4, weight(5, 0)).into()
+    ];
+    assert_eq!(unsorted(&intervals), vec![intervals[1], intervals[0]]);
+  }
+
+  #[test]
+  fn nested_criteria() {
+    type Weight = Lexicographic<u8, Lexicographic<Minimize<u8>, u8>>;
+    let weight = |a, b, c| Weight::from((a, (Minimize(b), c).into()));
+
+    assert!(weight(1, 9, 0) > weight(0, 0, 9));
+    assert!(weight(1, 0, 0) > weight(1, 1, 9));
+    assert!(weight(1, 1, 1) > weight(1, 1, 0));
+    assert_eq!(weight(1, 2, 3) + weight(1, 2, 3), weight(2, 4, 6));
+  }
+}
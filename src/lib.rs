@@ -1,10 +1,16 @@
 //! A pair of solvers for the [Weighted Interval Scheduling Problem](https://en.wikipedia.org/wiki/Interval_scheduling).
 //! 
 //! #### Features
-//! - Zero external dependencies, although requires an allocator (not optional yet).
-//! - Flexible: anything implementing `Ord + Add + Clone` may be thought of as an interval bound or a weight type.
+//! - Zero external dependencies by default (integrations are opt-in features), although requires an allocator (not optional yet).
+//! - Flexible: anything implementing `Ord + Clone` may be thought of as an interval bound (`chrono`/`time` date-times included), and anything implementing `Ord + Add + Clone` as a weight type (`Lexicographic` weights rank several criteria).
 //! - Efficient: running in `O(n log n)`.
 //! - Fast: cache-aware, zero-reallocation APIs are available.
+//! - Observable: the optional `tracing` feature emits spans for the sort, memoization, and reconstruction phases (with interval counts and durations).
+//! - Visual: `render::ascii` draws problems (and their optimal sets) on a text timeline, like the one below. `render::svg` (behind the `svg` feature) draws them as Gantt charts, and `render::dot` exports their conflict graph for Graphviz.
+//! - Portable: the `serde` feature lets problems and solutions be persisted or shipped over APIs as-is.
+//! - Scriptable: `cargo install w_inter --features cli` installs a `w_inter` binary solving CSV/JSON problem files.
+//! - Parallel: the `rayon` feature adds `par_unsorted`, sorting, searching and memoizing independent stretches of the timeline on all cores.
+//! - Diagnostic: the `lp` feature solves the linear programming relaxation, pricing each stretch of time by its dual.
 //! 
 //! #### Simple Example
 //! ```text
@@ -21,7 +27,8 @@
 //! ◀──0───1───2───3───4───5───6───7───8───9───10──11──▶
 //! ```
 //! ```rust
-//! let intervals = vec![
+//! # use w_inter::*;
+//! let intervals: Vec<WeightedInterval<u8, u8>> = vec![
 //!   (0u8, 1u8,  2u8).into(), // (start, end, weight)
 //!   (0u8, 6u8,  3u8).into(),
 //!   (1u8, 4u8,  5u8).into(),
@@ -35,11 +42,13 @@
 //! 
 //! let optimal = unsorted(&intervals);
 //! 
+//! // the optimal set is reported in descending order of end time
 //! assert_eq!(
 //!   optimal, 
 //!   vec![
+//!     (5u8, 9u8, 7u8).into(),
 //!     (1u8, 4u8, 5u8).into(),
-//!     (5u8, 9u8, 7u8).into()
+//!     (0u8, 1u8, 2u8).into()
 //!   ]
 //! );
 //! ```
@@ -47,9 +56,12 @@
 //! #### Fast (Amortized Allocation) Example
 //!   
 //! ```rust
+//! # use w_inter::*;
+//! # let problems = vec![vec![WeightedInterval::new(0u8, 1u8, 2u8)]];
+//! # fn sort(i: &mut [WeightedInterval<u8, u8>]) { i.sort_by_key(|i| i.end) }
 //! // our goal is to allocate once and reuse the same buffers
 //! // measure (or apply a guess) to avoid having to resize the vector.
-//! let max_interval_count = problems.iter().map(|i| i.len()).max();
+//! let max_interval_count = problems.iter().map(|i| i.len()).max().unwrap_or(0);
 //! 
 //! // we can say with certainty that the memo buffer 
 //! // will never need to be larger than the largest input size.
@@ -59,7 +71,7 @@
 //! // but it can't be larger than the largest input size.
 //! let mut soln = Vec::with_capacity(max_interval_count);
 //! 
-//! for intervals in problems {
+//! for mut intervals in problems {
 //!   // perhaps we know our intervals to be *almost* sorted, 
 //!   // so we choose to use an algorithm tuned for this case.
 //!   sort(&mut intervals);
@@ -79,11 +91,110 @@
 //! ```
 //! 
 
+#[macro_use]
+mod trace;
+
 mod traits;
+mod impls;
 mod util;
 mod weighted_interval;
+mod tagged_interval;
 mod solvers;
+mod compatibility;
+mod setup;
+mod rejection;
+mod lateness;
+mod lexicographic;
+mod tie_break;
+mod cardinality;
+mod constraints;
+mod greedy;
+mod cover;
+mod merge;
+mod interval_tree;
+mod window_queries;
+mod streaming;
+mod dynamic;
+mod prepared;
+mod snapshot;
+mod eytzinger;
+mod search;
+mod cyclic;
+mod laminar;
+mod flow;
+mod adapters;
+#[cfg(test)]
+mod testing;
+#[cfg(feature = "rkyv")]
+mod archived;
+#[cfg(feature = "rayon")]
+mod parallel;
+
+pub mod render;
+pub mod interval_graph;
+pub mod k_machines;
+pub mod assignment;
+pub mod bandwidth;
+pub mod dag;
+pub mod knapsack;
+pub mod preemptive;
+pub mod rectangles;
+pub mod sequencing;
+pub mod subsequence;
+pub mod sweep;
+pub mod recurrence;
+pub mod enumerate;
+pub mod analysis;
+pub mod validate;
+#[cfg(feature = "lp")]
+pub mod lp;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(any(feature = "csv", feature = "serde_json"))]
+pub mod io;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use solvers::{sorted, sorted_soa, unsorted, unsorted_soa};        // expose solver functions
+pub use solvers::sorted_in_window;                                    // expose solver restricted to a window
+pub use solvers::sorted_soa_vectorized;                               // expose solver vectorizing the searches over primitive times
+pub use eytzinger::{sorted_with_index, EytzingerIndex};               // expose solver searching a cache-friendly copy of the ends
+pub use search::{last_compatible, last_compatible_with};              // expose the predecessor search, for custom recurrences
+pub use search::{predecessors, predecessors_with};                    // expose the predecessors of all intervals at once
+pub use solvers::{sorted_with, unsorted_with};                        // expose solvers taking a compatibility rule
+pub use solvers::{sorted_weighted_by, unsorted_weighted_by};          // expose solvers computing weights as they go
+pub use greedy::{max_count, piercing_points};                         // expose greedy solvers for unweighted problems
+pub use cover::{gaps, min_count_cover, min_weight_cover, Uncovered};  // expose solvers covering a window, and the gaps left
+pub use setup::unsorted_with_setup;                                   // expose solver for sequence-dependent setup times
+pub use rejection::{unsorted_with_rejection, Rejection};              // expose solver penalizing the intervals left out
+pub use lateness::unsorted_with_lateness;                             // expose solver penalizing jobs finishing after their due time
+pub use tie_break::{unsorted_with_tie_break, TieBreak};               // expose solver choosing among equally heavy optimal sets
+pub use tie_break::{unsorted_with_hint, unsorted_with_secondary};     // expose solvers with a secondary objective, or warm-started
+pub use cyclic::unsorted_cyclic;                                      // expose solver for circular timelines
+pub use laminar::unsorted_laminar;                                    // expose solver selecting nested or disjoint intervals
+pub use cardinality::{sorted_at_most_k, unsorted_at_most_k};          // expose solvers limiting the number of selected intervals
+pub use cardinality::{sorted_exactly_k, unsorted_exactly_k};          // expose solvers fixing the number of selected intervals
+pub use cardinality::{sorted_at_least_k, unsorted_at_least_k};        // expose solvers requiring a number of selected intervals
+pub use constraints::{unsorted_with_mandatory, MandatoryConflict};    // expose solver forcing intervals into the solution
+pub use constraints::{unsorted_with_conflicts, unsorted_with_groups}; // expose solvers with conflicts beyond overlap
+pub use constraints::{unsorted_with_tree, GroupTree};                 // expose solver keeping to a tree of alternatives
+pub use lexicographic::{Lexicographic, Minimize};                     // expose weight types for multiple criteria
+pub use compatibility::{Closed, Compatibility, HalfOpen, MinGap};     // expose rules deciding which intervals may follow one another
+pub use merge::{merge_overlapping, MergeWeights};                     // expose merging of overlapping intervals
+pub use interval_tree::IntervalTree;                                  // expose index answering overlap queries
+pub use window_queries::{TableTooLarge, WindowQueries};               // expose precomputed optimal weights for many windows
+pub use streaming::{OutOfOrder, SlidingWindow, StreamingSolver};      // expose solvers for intervals arriving one at a time
+pub use streaming::ApproximateStreamingSolver;                        // expose solver approximating streams too large to keep
+pub use dynamic::DynamicSolver;                                       // expose solver repairing the optimum after each edit
+pub use prepared::Prepared;                                           // expose problem structure for solving as only weights change
+pub use snapshot::Snapshot;                                           // expose shared solver state for branching what-ifs
+pub use weighted_interval::{EndsAtMaximum, WeightedInterval};         // expose default weighted interval struct
+pub use tagged_interval::TaggedInterval;                              // expose weighted interval struct carrying a payload
+pub use traits::{Interval, Weighted};                                 // expose traits so users can implement them on their own types
+pub use adapters::{Unweighted, WeightByLength};                       // expose adapters changing the objective
+pub use validate::validate_intervals;                                 // expose input sanity checks
 
-pub use solvers::{sorted, unsorted};         // expose solver functions
-pub use weighted_interval::WeightedInterval; // expose default weighted interval struct
-pub use traits::{Interval, Weighted};        // expose traits so users can implement them on their own types
\ No newline at end of file
+#[cfg(feature = "rayon")]
+pub use parallel::par_unsorted;                                       // expose solver spreading the work over threads
+#[cfg(feature = "rkyv")]
+pub use archived::{sorted_archived, ArchivedWeightedInterval};        // zero-copy solving over archives
\ No newline at end of file
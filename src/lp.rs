@@ -0,0 +1,157 @@
+//! The linear program behind the problem: its relaxation, and the dual prices it puts on time.
+//!
+//! Selecting intervals is the integer program maximizing `Σ wᵢ xᵢ` under `Σ xᵢ ≤ 1` over the intervals covering each
+//! stretch of time, and the relaxation lets each `xᵢ` be any fraction `≥ 0`. Each interval covers a run of consecutive
+//! stretches, so the constraint matrix has consecutive ones and is totally unimodular: the relaxation is tight (its value
+//! is the optimal weight), and its dual is a longest path along the timeline. No simplex is needed, as the potentials
+//! of that path are the memoization itself.
+
+use std::ops::{Add, Range, Sub};
+use crate::traits;
+
+/// The dual price of one stretch of time: how much the optimum would gain per unit of it freed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShadowPrice<Weight, Time> {
+  /// The stretch: between consecutive starts or ends, or the single moment of a zero-length interval (`t..t`).
+  pub during: Range<Time>,
+  pub price:  Weight
+}
+
+/// The relaxation of a problem, solved along with its dual.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Relaxation<Weight, Time> {
+  /// The optimal value of the relaxation (that of the integer problem, `Weight::default()` if there are no intervals).
+  pub value:  Weight,
+  /// The optimal dual prices, in chronological order: all `≥ 0`, summing to `value`, and summing to at least an
+  /// interval's weight over the stretches it covers (exactly, for the intervals of an optimal selection).
+  pub prices: Vec<ShadowPrice<Weight, Time>>
+}
+
+/// Solves the relaxation of the problem and its dual, pricing every stretch of time. `O(n log n)` in interval number.
+///
+/// The stretches are those between consecutive distinct starts and ends, plus one for each interval of zero length (in
+/// input order among those at the same time), which is the moment it occupies: as in [`unsorted`](crate::unsorted), it
+/// overlaps intervals running across that moment, but neither its neighbours nor other zero-length intervals.
+///
+/// The price of a stretch is how much more the intervals fitting before its end are worth than those fitting before its
+/// start, so stretches no interval in the optimum needs are free, and contended ones carry what contending for them
+/// costs. Weights are assumed non-negative.
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::lp::ShadowPrice;
+/// let intervals: Vec<WeightedInterval<u32, u8>> = vec![(0, 4, 5).into(), (3, 6, 4).into(), (4, 8, 3).into(), (7, 9, 4).into()];
+///
+/// let relaxation = lp::relaxation(&intervals);
+/// assert_eq!(relaxation.value, 9);
+///
+/// let priced: Vec<ShadowPrice<u32, u8>> = relaxation.prices.into_iter().filter(|p| p.price > 0).collect();
+/// assert_eq!(priced, vec![
+///   ShadowPrice { during: 3..4, price: 5 },
+///   ShadowPrice { during: 7..8, price: 3 },
+///   ShadowPrice { during: 8..9, price: 1 }
+/// ]);
+/// ```
+#[must_use]
+pub fn relaxation<Weight, Time, Interval, InputContainer>(intervals: InputContainer) -> Relaxation<Weight, Time>
+  where Weight: Ord + Add<Output = Weight> + Sub<Output = Weight> + Clone + Default,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let mut times: Vec<Time> = intervals.iter().flat_map(|i| [i.start(), i.end()]).collect();
+  phase!("sort", times.len(), times.sort());
+  times.dedup();
+  let rank = |time: &Time| times.partition_point(|t| t < time);
+
+  // the stretches, as ranks of their bounds, and the first and last stretch each interval covers
+  let mut stretches: Vec<(usize, usize)> = vec![];
+  let mut covers: Vec<(usize, usize)> = vec![(0, 0); intervals.len()];
+  let mut zero_length: Vec<usize> = (0..intervals.len()).filter(|&i| intervals[i].start() == intervals[i].end()).collect();
+  zero_length.sort_by_key(|&i| rank(&intervals[i].start()));
+
+  let mut after: Vec<usize> = Vec::with_capacity(times.len());
+  let mut moments = zero_length.into_iter().peekable();
+  for t in 0..times.len() {
+    while let Some(i) = moments.next_if(|&i| rank(&intervals[i].start()) == t) {
+      covers[i] = (stretches.len(), stretches.len());
+      stretches.push((t, t));
+    }
+    after.push(stretches.len());
+    stretches.push((t, t + 1));
+  }
+  stretches.pop();
+
+  let mut ending: Vec<Vec<usize>> = vec![vec![]; stretches.len()];
+  for (i, interval) in intervals.iter().enumerate() {
+    let (start, end) = (rank(&interval.start()), rank(&interval.end()));
+    if start < end { covers[i] = (after[start], after[end - 1]); }
+    ending[covers[i].1].push(i);
+  }
+
+  // the potentials: the optimum of the intervals covering only stretches before each one
+  let mut potentials: Vec<Weight> = Vec::with_capacity(stretches.len() + 1);
+  potentials.push(Weight::default());
+  phase!("memoize", stretches.len(), for ending in &ending {
+    let best = ending.iter()
+      .map(|&i| intervals[i].weight() + potentials[covers[i].0].clone())
+      .fold(potentials[potentials.len() - 1].clone(), Ord::max);
+    potentials.push(best);
+  });
+
+  let prices = stretches.into_iter()
+    .enumerate()
+    .map(|(s, (from, to))| ShadowPrice {
+      during: times[from].clone()..times[to].clone(),
+      price:  potentials[s + 1].clone() - potentials[s].clone()
+    })
+    .collect();
+
+  Relaxation { value: potentials[potentials.len() - 1].clone(), prices }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{lp, testing::{brute_force_best, pairwise, Lcg}, WeightedInterval};
+
+  #[test]
+  fn duals_are_feasible_and_tight() {
+    let mut rng = Lcg::new(7);
+
+    for _ in 0..300 {
+      let n = 1 + rng.next(8) as usize;
+      let intervals: Vec<WeightedInterval<u32, u8>> = (0..n)
+        .map(|_| { let start = rng.next(7) as u8; (start, start + rng.next(4) as u8, rng.next(9)).into() })
+        .collect();
+      let relaxation = lp::relaxation(&intervals);
+
+      // the best compatible subset, by brute force
+      let optimum: u32 = brute_force_best(&intervals, |set| pairwise(set, |a, b| {
+        let (x, y) = (&intervals[a], &intervals[b]);
+        x.end <= y.start || y.end <= x.start
+      }));
+      assert_eq!(relaxation.value, optimum);
+      assert_eq!(relaxation.prices.iter().map(|p| p.price).sum::<u32>(), optimum);
+
+      for (k, interval) in intervals.iter().enumerate() {
+        let cost = if interval.start == interval.end {
+          // the moments at its time go to its zero-length intervals in input order
+          let nth = intervals[..k].iter().filter(|i| i.start == interval.start && i.end == interval.start).count();
+          relaxation.prices.iter().filter(|p| p.during == (interval.start..interval.start)).nth(nth).unwrap().price
+        }
+        else {
+          relaxation.prices.iter()
+            .filter(|p| interval.start <= p.during.start && p.during.end <= interval.end)
+            .filter(|p| p.during.start < p.during.end || (interval.start < p.during.start && p.during.start < interval.end))
+            .map(|p| p.price)
+            .sum::<u32>()
+        };
+        assert!(cost >= interval.weight, "{intervals:?}");
+      }
+    }
+
+    assert_eq!(lp::relaxation(Vec::<WeightedInterval<u32, u8>>::new()), lp::Relaxation { value: 0, prices: vec![] });
+  }
+}
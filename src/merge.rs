@@ -0,0 +1,84 @@
+use std::ops::Add;
+use crate::{traits, WeightedInterval};
+
+/// How the weights of merged intervals combine into the weight of the merged piece.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MergeWeights {
+  /// The piece weighs as much as all of its intervals together.
+  #[default]
+  Sum,
+  /// The piece weighs as much as its heaviest interval.
+  Max
+}
+
+/// Merges overlapping intervals into single pieces, returned in chronological order. `O(n log n)` in interval number.
+///
+/// Pieces span their intervals, and weigh as `weights` says. As everywhere else in the crate, intervals merely touching
+/// don't overlap, so they stay apart (which also keeps zero-length intervals sitting on another's bound apart).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 4, 2).into(),
+///   (1, 2, 5).into(),
+///   (3, 6, 1).into(),
+///   (6, 8, 3).into()
+/// ];
+///
+/// assert_eq!(merge_overlapping(&intervals, MergeWeights::Sum), vec![(0, 6, 8).into(), (6, 8, 3).into()]);
+/// assert_eq!(merge_overlapping(&intervals, MergeWeights::Max), vec![(0, 6, 5).into(), (6, 8, 3).into()]);
+/// ```
+#[must_use]
+pub fn merge_overlapping<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  weights:   MergeWeights
+) -> Vec<WeightedInterval<Weight, Time>>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| (intervals[i].start(), intervals[i].end())));
+
+  let mut pieces: Vec<WeightedInterval<Weight, Time>> = vec![];
+  for i in order {
+    let (start, end, weight) = (intervals[i].start(), intervals[i].end(), intervals[i].weight());
+
+    match pieces.last_mut() {
+      Some(piece) if start < piece.end => {
+        piece.end = piece.end.clone().max(end);
+        piece.weight = match weights {
+          MergeWeights::Sum => piece.weight.clone() + weight,
+          MergeWeights::Max => piece.weight.clone().max(weight)
+        };
+      },
+      _ => pieces.push(WeightedInterval { start, end, weight })
+    }
+  }
+
+  pieces
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{merge_overlapping, MergeWeights, WeightedInterval};
+
+  #[test]
+  fn duplicates_and_nesting() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (5, 9, 1).into(), (0, 2, 1).into(), (0, 2, 1).into(), (2, 3, 4).into(),
+      (5, 6, 2).into(), (7, 7, 3).into(), (9, 9, 1).into(), (8, 12, 1).into()
+    ];
+
+    assert_eq!(merge_overlapping(&intervals, MergeWeights::Sum), vec![
+      (0, 2, 2).into(), (2, 3, 4).into(), (5, 12, 8).into()
+    ]);
+    assert_eq!(merge_overlapping(&intervals, MergeWeights::Max), vec![
+      (0, 2, 1).into(), (2, 3, 4).into(), (5, 12, 3).into()
+    ]);
+    assert!(merge_overlapping(Vec::<WeightedInterval<u8, u8>>::new(), MergeWeights::Sum).is_empty());
+  }
+}
@@ -0,0 +1,90 @@
+use std::ops::Add;
+use rayon::prelude::*;
+use crate::{solvers::{memoize, reconstruct}, traits};
+
+/// Like [`unsorted`](crate::unsorted), but spreading the work over the threads of the rayon pool. `O(n log n / p)` for
+/// `p` threads, on timelines that fall apart into many independent stretches.
+///
+/// Sorting and the predecessor searches (the `O(n log n)` of the work) are done in parallel. The memoization is a chain,
+/// each entry depending on the one before it, so it is split instead at the times no interval runs across: the
+/// stretches between them share no conflicts, and are memoized and reconstructed in parallel, each on its own. A
+/// timeline without such times (every moment covered, e.g. by one interval spanning it all) is memoized in one `O(n)`
+/// pass, which is still far less than the sort.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u32, u32>> = (0..10_000).map(|i| (i, i + 1 + i % 3, 1 + i % 5).into()).collect();
+///
+/// let optimal = par_unsorted(&intervals);
+/// let weight = |solution: &[WeightedInterval<u32, u32>]| solution.iter().map(|i| i.weight).sum::<u32>();
+/// assert_eq!(weight(&optimal), weight(&unsorted(&intervals)));
+/// ```
+#[must_use]
+pub fn par_unsorted<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone + Send,
+        Time: Ord + Send + Sync,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone + Send + Sync,
+        InputContainer: AsRef<[Interval]>
+{
+  let mut intervals: Vec<Interval> = intervals.as_ref().par_iter().cloned().collect();
+  if intervals.is_empty() { return vec![]; }
+  phase!("sort", intervals.len(), intervals.par_sort_unstable_by_key(|i| i.end()));
+
+  let ends: Vec<Time> = intervals.par_iter().map(|i| i.end()).collect();
+  let predecessors: Vec<Option<usize>> = intervals.par_iter()
+    .enumerate()
+    .map(|(k, i)| ends[..k].partition_point(|e| *e <= i.start()).checked_sub(1))
+    .collect();
+
+  // a stretch starts at `k` when nothing from there on starts before the interval at `k - 1` ends, that is, when none
+  // of their predecessors is earlier than it (`None` sorting first)
+  let mut cuts = vec![intervals.len()];
+  let mut earliest = Some(usize::MAX);
+  for k in (1..intervals.len()).rev() {
+    earliest = earliest.min(predecessors[k]);
+    if earliest >= Some(k - 1) { cuts.push(k); }
+  }
+  cuts.push(0);
+  cuts.reverse();
+
+  let stretches: Vec<Vec<usize>> = phase!("memoize", intervals.len(), cuts.par_windows(2)
+    .map(|cut| {
+      let (first, count) = (cut[0], cut[1] - cut[0]);
+      let weight      = |k: usize| intervals[first + k].weight();
+      let predecessor = |k: usize| predecessors[first + k].and_then(|p| p.checked_sub(first));
+
+      let mut memoization = vec![weight(0); count];
+      memoize(count, &mut memoization, weight, predecessor);
+
+      let mut selected = vec![];
+      reconstruct(count, &memoization, weight, predecessor, |k| selected.push(first + k));
+      selected
+    })
+    .collect());
+
+  stretches.into_iter().rev().flatten().map(|k| intervals[k].clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{par_unsorted, unsorted, WeightedInterval};
+
+  #[test]
+  fn matches_unsorted() {
+    let weight = |solution: &[WeightedInterval<u64, u64>]| solution.iter().map(|i| i.weight).sum::<u64>();
+
+    for spread in [1u64, 3, 10, 40] {
+      let intervals: Vec<WeightedInterval<u64, u64>> = (0..5_000u64)
+        .map(|i| { let start = i * 7_919 % 5_000 * spread / 10; (start, start + 1 + i % 13, 1 + i * 31 % 17).into() })
+        .collect();
+
+      let optimal = par_unsorted(&intervals);
+      assert_eq!(weight(&optimal), weight(&unsorted(&intervals)), "spread {spread}");
+      assert!(optimal.windows(2).all(|pair| pair[1].end <= pair[0].start));
+    }
+
+    assert!(par_unsorted(Vec::<WeightedInterval<u64, u64>>::new()).is_empty());
+  }
+}
@@ -0,0 +1,286 @@
+//! Preemptable intervals: jobs needing some processing time within their interval, which may be split into pieces to
+//! get it, with other jobs running in between. A job earns its weight only if all of its processing fits in its
+//! interval, and each split (each piece of a job after its first) costs a penalty, so a penalty of zero splits jobs
+//! freely.
+//!
+//! Jobs run earliest deadline first: whenever a job starts or completes, the machine runs the job whose interval ends
+//! first. That fits every set of jobs which fits at all (Horn, *Some Simple Scheduling Algorithms*, 1974), and only
+//! splits a job when another one, ending earlier, starts, so pieces begin and end where intervals start or jobs
+//! complete.
+
+use std::{cmp::Reverse, ops::{Add, Range, Sub}};
+use crate::traits;
+
+/// The outcome of [`max_weight`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Preemption<Weight, Time> {
+  /// The jobs completed, by index, ascending.
+  pub jobs:   Vec<usize>,
+  /// The pieces run, in chronological order: the index of the job, and when that piece of it runs.
+  pub pieces: Vec<(usize, Range<Time>)>,
+  /// The weight of the jobs completed, less the penalties of their splits.
+  pub net:    Weight
+}
+
+/// Selects the jobs of greatest total weight, less penalties, that can all complete on one machine: job `i` needs
+/// `processing(i)` time within its interval, in as many pieces as it takes, and each piece of it after the first costs
+/// `penalty(i)`.
+///
+/// The problem is NP-hard (a knapsack problem already when all jobs start together, Karp, 1972), so this is a branch
+/// and bound: exponential in the worst case, but quick for a few dozen jobs. Jobs are tried from the heaviest down,
+/// taking each or leaving it out. A job that can't complete alongside those taken never will once more are, so each
+/// branch only tries those that can. It's abandoned once the heaviest of those left wouldn't beat the best selection so
+/// far, taking as many as could fit: no more than the shortest of them fit in the time their intervals cover. Each set
+/// of jobs tried is scheduled in `O(n²)`.
+///
+/// Without penalties, the selection is optimal. With them, it's the best of those scheduled earliest deadline first:
+/// running some jobs in another order might split fewer of them. Penalties should not be negative. A job with no
+/// processing to do completes at its start, in no pieces.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // a long batch job, and an urgent one
+/// let jobs: Vec<WeightedInterval<u32, u32>> = vec![(0, 10, 5).into(), (4, 6, 4).into()];
+/// let processing = [6, 2];
+///
+/// // the batch job is split around the urgent one
+/// let split = preemptive::max_weight(&jobs, |i| processing[i], |_| 1);
+/// assert_eq!(split.jobs, vec![0, 1]);
+/// assert_eq!(split.pieces, vec![(0, 0..4), (1, 4..6), (0, 6..8)]);
+/// assert_eq!(split.net, 5 + 4 - 1);
+///
+/// // unless splitting it costs more than the urgent job earns
+/// let whole = preemptive::max_weight(&jobs, |i| processing[i], |_| 5);
+/// assert_eq!(whole.jobs, vec![0]);
+/// assert_eq!(whole.pieces, vec![(0, 0..6)]);
+/// assert_eq!(whole.net, 5);
+/// ```
+#[must_use]
+pub fn max_weight<Weight, Time, Interval>(
+  intervals:  &[Interval],
+  processing: impl Fn(usize) -> Time,
+  penalty:    impl Fn(usize) -> Weight
+) -> Preemption<Weight, Time>
+  where Weight: Ord + Add<Output = Weight> + Sub<Output = Weight> + Clone + Default,
+        Time: Ord + Add<Output = Time> + Sub<Output = Time> + Clone + Default,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  let processing: Vec<Time> = (0..intervals.len()).map(processing).collect();
+  let penalties: Vec<Weight> = (0..intervals.len()).map(penalty).collect();
+
+  let mut order: Vec<usize> = (0..intervals.len()).filter(|&i| intervals[i].start() <= intervals[i].end()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| Reverse(intervals[i].weight())));
+
+  let mut search = Search {
+    intervals,
+    processing: &processing,
+    penalties:  &penalties,
+    taken:      vec![],
+    best:       Preemption { jobs: vec![], pieces: vec![], net: Weight::default() }
+  };
+  phase!("search", intervals.len(), search.branch(&order, Weight::default()));
+  search.best
+}
+
+/// Branch and bound state of [`max_weight`].
+struct Search<'a, Weight, Time, Interval> {
+  intervals:  &'a [Interval],
+  processing: &'a [Time],
+  penalties:  &'a [Weight],
+  taken:      Vec<usize>,
+  best:       Preemption<Weight, Time>
+}
+
+impl<Weight, Time, Interval> Search<'_, Weight, Time, Interval>
+  where Weight: Ord + Add<Output = Weight> + Sub<Output = Weight> + Clone + Default,
+        Time: Ord + Add<Output = Time> + Sub<Output = Time> + Clone + Default,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  /// Decides on the `candidates` (heaviest first), having taken `weight` before.
+  fn branch(&mut self, candidates: &[usize], weight: Weight) {
+    // any more jobs only make it harder for those taken to complete, so those that can't now never will
+    let mut open = vec![];
+    for &i in candidates {
+      self.taken.push(i);
+      if let Some(pieces) = schedule(self.intervals, self.processing, &self.taken) { open.push((i, pieces)); }
+      self.taken.pop();
+    }
+
+    // taking each in turn, having left out those before it
+    let jobs: Vec<usize> = open.iter().map(|(i, _)| *i).collect();
+    for (x, (i, pieces)) in open.into_iter().enumerate() {
+      let fitting = self.most_fitting(&jobs[x..]);
+      let bound = jobs[x..x + fitting].iter().fold(weight.clone(), |total, &j| total + self.intervals[j].weight());
+      if bound <= self.best.net { return; }
+
+      let weight = weight.clone() + self.intervals[i].weight();
+      self.taken.push(i);
+      self.consider(weight.clone(), pieces);
+      self.branch(&jobs[x + 1..], weight);
+      self.taken.pop();
+    }
+  }
+
+  /// The most of `jobs` that could complete alongside those taken: their processing, and that of the jobs taken, has
+  /// to fit in the time their intervals cover, so no more of them than the shortest do.
+  fn most_fitting(&self, jobs: &[usize]) -> usize {
+    let mut windows: Vec<(Time, Time)> = self.taken.iter().chain(jobs)
+      .map(|&i| (self.intervals[i].start(), self.intervals[i].end()))
+      .collect();
+    windows.sort_unstable();
+
+    // the time covered, stretch by stretch
+    let mut covered = Time::default();
+    let mut stretch: Option<(Time, Time)> = None;
+    for (start, end) in windows {
+      match &mut stretch {
+        Some((_, to)) if start <= *to => { if end > *to { *to = end; } }
+        _                             => {
+          if let Some((from, to)) = stretch.replace((start, end)) { covered = covered + (to - from); }
+        }
+      }
+    }
+    if let Some((from, to)) = stretch { covered = covered + (to - from); }
+
+    let busy = self.taken.iter().fold(Time::default(), |busy, &i| busy + self.processing[i].clone());
+    let mut shortest: Vec<Time> = jobs.iter().map(|&i| self.processing[i].clone()).collect();
+    shortest.sort_unstable();
+    let mut used = busy;
+    shortest.into_iter().take_while(|time| { used = used.clone() + time.clone(); used <= covered }).count()
+  }
+
+  /// Keeps the jobs taken, weighing `weight` and run in `pieces`, if they net more than the best so far.
+  fn consider(&mut self, weight: Weight, pieces: Vec<(usize, Range<Time>)>) {
+    let mut started = vec![false; self.intervals.len()];
+    let mut charged = Weight::default();
+    for (job, _) in &pieces {
+      if started[*job] { charged = charged + self.penalties[*job].clone(); }
+      started[*job] = true;
+    }
+
+    if weight < charged || weight.clone() - charged.clone() <= self.best.net { return; }
+    let mut jobs = self.taken.clone();
+    jobs.sort_unstable();
+    self.best = Preemption { jobs, pieces, net: weight - charged };
+  }
+}
+
+/// Runs `jobs` earliest deadline first, returning the pieces run in chronological order, or `None` if one of them can't
+/// complete within its interval. Of the jobs ending first, the one running carries on, or else the lowest index runs.
+fn schedule<Time, Interval>(intervals: &[Interval], processing: &[Time], jobs: &[usize]) -> Option<Vec<(usize, Range<Time>)>>
+  where Time: Ord + Add<Output = Time> + Sub<Output = Time> + Clone + Default,
+        Interval: traits::Interval<Time>
+{
+  let mut arrivals = jobs.to_vec();
+  arrivals.sort_by_key(|&i| (intervals[i].start(), i));
+
+  let mut pieces: Vec<(usize, Range<Time>)> = vec![];
+  let Some(&first) = arrivals.first() else { return Some(pieces) };
+  let mut now = intervals[first].start();
+
+  // the jobs started, with the processing they have left
+  let mut left: Vec<(usize, Time)> = vec![];
+  let mut next = 0;
+  loop {
+    while let Some(&i) = arrivals.get(next) {
+      if intervals[i].start() > now { break; }
+      left.push((i, processing[i].clone()));
+      next += 1;
+    }
+    left.retain(|(_, time)| *time > Time::default());
+
+    let running = pieces.last().filter(|(_, piece)| piece.end == now).map(|(job, _)| *job);
+    let Some(position) = (0..left.len()).min_by_key(|&p| (intervals[left[p].0].end(), Some(left[p].0) != running, left[p].0)) else {
+      // nothing to run until the next job starts, if one does
+      let Some(&i) = arrivals.get(next) else { return Some(pieces) };
+      now = intervals[i].start();
+      continue;
+    };
+
+    // until the job completes, or another starts
+    let job = left[position].0;
+    let finish = now.clone() + left[position].1.clone();
+    let until = match arrivals.get(next) {
+      Some(&i) if intervals[i].start() < finish => intervals[i].start(),
+      _                                         => finish
+    };
+    if until > intervals[job].end() { return None; }
+
+    left[position].1 = left[position].1.clone() - (until.clone() - now.clone());
+    match pieces.last_mut() {
+      Some((last, piece)) if *last == job && piece.end == now => piece.end = until.clone(),
+      _                                                       => pieces.push((job, now.clone()..until.clone()))
+    }
+    now = until;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{preemptive::max_weight, testing::{brute_force_best, subsets, Lcg}, WeightedInterval};
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(73);
+
+    /// The pieces of each of `jobs` when run earliest deadline first, a unit of time at a time, the running job carrying
+    /// on among those ending first. `None` if one of them doesn't complete within its interval.
+    fn pieces(intervals: &[WeightedInterval<u32, u8>], processing: &[u8], jobs: &[usize]) -> Option<Vec<u32>> {
+      let (mut left, mut pieces) = (processing.to_vec(), vec![0; intervals.len()]);
+      let mut running = None;
+      for t in 0..16 {
+        if jobs.iter().any(|&i| left[i] > 0 && intervals[i].end <= t) { return None; }
+        running = jobs.iter()
+          .copied()
+          .filter(|&i| left[i] > 0 && intervals[i].start <= t)
+          .min_by_key(|&i| (intervals[i].end, Some(i) != running, i))
+          .inspect(|&i| { if Some(i) != running { pieces[i] += 1; } left[i] -= 1; });
+      }
+      Some(pieces)
+    }
+
+    for _ in 0..300 {
+      let n = rng.next(8) as usize;
+      let intervals: Vec<WeightedInterval<u32, u8>> = (0..n)
+        .map(|_| { let start = rng.next(10) as u8; (start, start + rng.next(6) as u8, 1 + rng.next(9)).into() })
+        .collect();
+      let processing: Vec<u8> = (0..n).map(|_| rng.next(4) as u8).collect();
+      let penalties: Vec<u32> = (0..n).map(|_| rng.next(4)).collect();
+
+      let weight = |set: &[usize]| set.iter().map(|&i| intervals[i].weight).sum::<u32>();
+
+      // with penalties, the best set scheduled earliest deadline first
+      let best = subsets(n)
+        .filter_map(|set| {
+          let pieces = pieces(&intervals, &processing, &set)?;
+          let charged = set.iter().map(|&i| penalties[i] * pieces[i].saturating_sub(1)).sum::<u32>();
+          Some(i64::from(weight(&set)) - i64::from(charged))
+        })
+        .max()
+        .unwrap_or(0);
+
+      let outcome = max_weight(&intervals, |i| processing[i], |i| penalties[i]);
+      assert_eq!(i64::from(outcome.net), best, "{intervals:?} {processing:?} {penalties:?}");
+      assert!(outcome.jobs.windows(2).all(|pair| pair[0] < pair[1]));
+
+      // the pieces fit their intervals and each other, complete the jobs selected, and net what was reported
+      let mut ran = vec![0; n];
+      for (i, piece) in &outcome.pieces {
+        assert!(outcome.jobs.contains(i) && intervals[*i].start <= piece.start && piece.end <= intervals[*i].end && piece.start < piece.end);
+        ran[*i] += piece.end - piece.start;
+      }
+      assert!(outcome.jobs.iter().all(|&i| ran[i] == processing[i]));
+      assert!(outcome.pieces.windows(2).all(|pair| pair[0].1.end <= pair[1].1.start));
+      let splits = |i: usize| outcome.pieces.iter().filter(|(job, _)| *job == i).count().saturating_sub(1) as u32;
+      assert_eq!(weight(&outcome.jobs) - outcome.jobs.iter().map(|&i| penalties[i] * splits(i)).sum::<u32>(), outcome.net);
+
+      // without penalties, the heaviest set asking no more of any stretch of time than it lasts, which is what fits
+      let fits = |set: &[usize]| set.iter().all(|&a| set.iter().all(|&b| {
+        let (from, to) = (intervals[a].start, intervals[b].end);
+        set.iter().filter(|&&i| from <= intervals[i].start && intervals[i].end <= to).map(|&i| processing[i]).sum::<u8>() <= to.saturating_sub(from)
+      }));
+      let heaviest: u32 = brute_force_best(&intervals, fits);
+      assert_eq!(max_weight(&intervals, |i| processing[i], |_| 0).net, heaviest, "{intervals:?} {processing:?}");
+    }
+  }
+}
@@ -0,0 +1,109 @@
+use std::ops::Add;
+use crate::{solvers::{memoize, reconstruct}, traits};
+
+/// The structure of a problem (which intervals may follow one another), for solving it again and again as only the
+/// weights change. Building it is `O(n log n)` in interval number, each solve `O(n)`.
+///
+/// The sort by end and the predecessor of each interval only depend on the times, so they are found once, and every
+/// solve just fills in the memoization and reconstructs. Any weights attached to the intervals are ignored.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<(u32, u32)> = vec![(4, 9), (0, 4), (1, 5)];
+/// let prepared = Prepared::new(&intervals);
+///
+/// let mut memo = vec![0; prepared.len()];
+/// let mut solution = vec![];
+/// prepared.solve(&[3u16, 2, 3], &mut memo, &mut solution);
+/// assert_eq!(solution, vec![0, 1]);
+///
+/// // re-priced
+/// solution.clear();
+/// prepared.solve(&[3, 2, 9], &mut memo, &mut solution);
+/// assert_eq!(solution, vec![2]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Prepared {
+  /// The indices of the intervals, by increasing end.
+  order:        Vec<usize>,
+  /// For each position in `order`, the last position before it whose interval ends by the time its interval starts.
+  predecessors: Vec<Option<usize>>
+}
+
+impl Prepared {
+  /// Finds the order and predecessors of `intervals`, in any order. `O(n log n)`.
+  pub fn new<Time, Interval, InputContainer>(intervals: InputContainer) -> Self
+    where Time: Ord,
+          Interval: traits::Interval<Time>,
+          InputContainer: AsRef<[Interval]>
+  {
+    let intervals = intervals.as_ref();
+
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+
+    let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+    let predecessors = order.iter()
+      .enumerate()
+      .map(|(k, &i)| ends[..k].partition_point(|e| *e <= intervals[i].start()).checked_sub(1))
+      .collect();
+
+    Self { order, predecessors }
+  }
+
+  /// Solves the problem with interval `i` weighing `weights[i]`, appending the indices of the optimal set to `solution`
+  /// (in descending order of end, as from [`unsorted_soa`](crate::unsorted_soa)). `O(n)`.
+  ///
+  /// As for [`sorted`](crate::sorted), `memoization` is a buffer at least as long as the problem, and there is no need to
+  /// clear it between solves. Once solved, its entry at `len() - 1` holds the optimal weight.
+  ///
+  /// # Panics
+  /// If there isn't one weight per interval.
+  pub fn solve<Weight>(&self, weights: &[Weight], memoization: &mut [Weight], solution: &mut Vec<usize>)
+    where Weight: Ord + Add<Output = Weight> + Clone
+  {
+    assert_eq!(weights.len(), self.order.len(), "one weight per interval is needed");
+
+    if let Some(&i) = self.order.first() { memoization[0] = weights[i].clone(); }
+    else { return; }
+
+    let weight      = |k: usize| weights[self.order[k]].clone();
+    let predecessor = |k: usize| self.predecessors[k];
+
+    phase!("memoize", self.order.len(), memoize(self.order.len(), memoization, weight, predecessor));
+    phase!("reconstruct", self.order.len(), reconstruct(self.order.len(), memoization, weight, predecessor, |k| solution.push(self.order[k])));
+  }
+
+  /// How many intervals there are.
+  pub fn len(&self) -> usize { self.order.len() }
+
+  /// Whether there are no intervals.
+  pub fn is_empty(&self) -> bool { self.order.is_empty() }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{unsorted_soa, Prepared};
+
+  #[test]
+  fn resolves_match_solving_anew() {
+    let starts: Vec<u32> = (0..50).map(|i| i * 19 % 41).collect();
+    let ends: Vec<u32> = starts.iter().enumerate().map(|(i, s)| s + 1 + i as u32 % 7).collect();
+    let intervals: Vec<(u32, u32)> = starts.iter().copied().zip(ends.iter().copied()).collect();
+
+    let prepared = Prepared::new(&intervals);
+    let mut memo = vec![0; prepared.len()];
+    for round in 0..10u64 {
+      let weights: Vec<u64> = (0..50).map(|i| (i * 7 + round * 13) % 11 + 1).collect();
+
+      let mut solution = vec![];
+      prepared.solve(&weights, &mut memo, &mut solution);
+      let total = |solution: &[usize]| solution.iter().map(|&i| weights[i]).sum::<u64>();
+      assert_eq!(total(&solution), total(&unsorted_soa(&starts, &ends, &weights)));
+      assert_eq!(memo[prepared.len() - 1], total(&solution));
+      assert!(solution.windows(2).all(|pair| ends[pair[1]] <= starts[pair[0]]));
+    }
+
+    Prepared::new(Vec::<(u32, u32)>::new()).solve::<u8>(&[], &mut [], &mut vec![]);
+  }
+}
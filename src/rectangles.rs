@@ -0,0 +1,228 @@
+//! Rectangles: intervals of time that also occupy a range on a second axis (seats in a venue, lanes of a pool, frequency
+//! bands), where two may both be selected unless they overlap on both axes.
+//!
+//! The problem is NP-hard, so [`exact`] takes exponential time in the worst case, and [`approximate`] is polynomial, with
+//! a guarantee. The range on the second axis is given for each interval by a closure over its index, and is half-open,
+//! like the intervals. The solvers return the indices of the selected intervals, ascending. Weights are assumed
+//! non-negative.
+
+use std::ops::{Add, Range};
+use crate::{interval_graph, traits};
+
+/// Whether half-open ranges `a` and `b` overlap (an empty one sitting on the other's start or end doesn't).
+fn overlap<Place: Ord>(a: &Range<Place>, b: &Range<Place>) -> bool {
+  !(a.end <= b.start || b.end <= a.start)
+}
+
+/// Selects the rectangles of maximum total weight no two of which overlap, interval `i` occupying `span(i)` on the
+/// second axis. Exact, by branch and bound: exponential in the worst case, but quick for a few dozen rectangles, or
+/// more when few of them overlap.
+///
+/// Rectangles are tried from the heaviest down, taking each or leaving it out, and a branch is abandoned once all it
+/// could still take wouldn't beat the best selection so far (which starts as that of [`approximate`]).
+///
+/// ```rust
+/// # use w_inter::*;
+/// // bookings of a venue's seats, for some hours
+/// let hours: Vec<WeightedInterval<u32, u8>> = vec![(18, 22, 5).into(), (19, 21, 4).into(), (20, 23, 4).into()];
+/// let seats = [0..50, 40..80, 50..100];
+///
+/// // the first two want the same seats, at the same time
+/// assert_eq!(rectangles::exact(&hours, |i| seats[i].clone()), vec![0, 2]);
+/// ```
+#[must_use]
+pub fn exact<Weight, Time, Place, Interval, InputContainer>(
+  intervals: InputContainer,
+  span:      impl Fn(usize) -> Range<Place>
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Time: Ord + Clone,
+        Place: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  let spans: Vec<Range<Place>> = (0..intervals.len()).map(&span).collect();
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| std::cmp::Reverse(intervals[i].weight())));
+
+  let neighbors: Vec<Vec<usize>> = (0..intervals.len())
+    .map(|a| (0..intervals.len()).filter(|&b| b != a && overlaps(intervals, &spans, a, b)).collect())
+    .collect();
+
+  let best = approximate(intervals, |i| spans[i].clone());
+  let mut search = Search {
+    intervals,
+    order,
+    neighbors,
+    blocked:  vec![0; intervals.len()],
+    taken:    vec![],
+    weight:   best.iter().fold(Weight::default(), |total, &i| total + intervals[i].weight()),
+    best
+  };
+  phase!("search", intervals.len(), search.branch(0, Weight::default()));
+
+  let mut selected = search.best;
+  selected.sort_unstable();
+  selected
+}
+
+/// Whether rectangles `a` and `b` overlap on both axes.
+fn overlaps<Time: Ord, Place: Ord>(
+  intervals: &[impl traits::Interval<Time>],
+  spans:     &[Range<Place>],
+  a:         usize,
+  b:         usize
+) -> bool {
+  let time = |i: usize| intervals[i].start()..intervals[i].end();
+  overlap(&time(a), &time(b)) && overlap(&spans[a], &spans[b])
+}
+
+/// Branch and bound state of [`exact`].
+struct Search<'a, Weight, Interval> {
+  intervals: &'a [Interval],
+  order:     Vec<usize>,
+  neighbors: Vec<Vec<usize>>,
+  /// How many taken rectangles each overlaps.
+  blocked:   Vec<usize>,
+  taken:     Vec<usize>,
+  best:      Vec<usize>,
+  weight:    Weight
+}
+
+impl<Weight, Interval> Search<'_, Weight, Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default
+{
+  /// Decides on the rectangles from position `k` (in order) on, having taken `weight` before.
+  fn branch<Time: Ord>(&mut self, k: usize, weight: Weight) where Interval: traits::Weighted<Weight> + traits::Interval<Time> {
+    let intervals = self.intervals;
+    let Some(&i) = self.order.get(k) else {
+      if weight > self.weight { (self.best, self.weight) = (self.taken.clone(), weight); }
+      return;
+    };
+
+    // all that could still be taken, were nothing left to overlap
+    let bound = self.order[k..].iter()
+      .filter(|&&j| self.blocked[j] == 0)
+      .fold(weight.clone(), |total, &j| total + intervals[j].weight());
+    if bound <= self.weight { return; }
+
+    if self.blocked[i] == 0 {
+      for &j in &self.neighbors[i] { self.blocked[j] += 1; }
+      self.taken.push(i);
+      self.branch(k + 1, weight.clone() + intervals[i].weight());
+      self.taken.pop();
+      for &j in &self.neighbors[i] { self.blocked[j] -= 1; }
+    }
+    self.branch(k + 1, weight);
+  }
+}
+
+/// Like [`exact`], but in polynomial time, selecting rectangles of at least `1 / (⌊log₂ n⌋ + 2)` of the maximum total
+/// weight, for `n` rectangles. `O(n log² n)` in rectangle number.
+///
+/// The timeline is split at the median start: the rectangles running at that time overlap exactly when their spans
+/// do, so the heaviest of them that fit together are found as in [`unsorted`](crate::unsorted), along the second axis.
+/// Those wholly before or after the split are solved in the same way, and the heavier of the two selections is
+/// returned (Agarwal, van Kreveld and Suri, *Label Placement by Maximum Independent Set in Rectangles*, 1998).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let hours: Vec<WeightedInterval<u32, u8>> = vec![(18, 22, 5).into(), (19, 21, 4).into(), (20, 23, 4).into()];
+/// let seats = [0..50, 40..80, 50..100];
+///
+/// let selected = rectangles::approximate(&hours, |i| seats[i].clone());
+/// let weight: u32 = selected.iter().map(|&i| hours[i].weight).sum();
+/// assert!(3 * weight >= 9);
+/// ```
+#[must_use]
+pub fn approximate<Weight, Time, Place, Interval, InputContainer>(
+  intervals: InputContainer,
+  span:      impl Fn(usize) -> Range<Place>
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Time: Ord + Clone,
+        Place: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  let spans: Vec<Range<Place>> = (0..intervals.len()).map(span).collect();
+  let (_, mut selected) = phase!("split", intervals.len(), split(intervals, &spans, (0..intervals.len()).collect()));
+  selected.sort_unstable();
+  selected
+}
+
+/// The selection of [`approximate`] among `members`, and its weight.
+fn split<Weight, Time, Place, Interval>(intervals: &[Interval], spans: &[Range<Place>], members: Vec<usize>) -> (Weight, Vec<usize>)
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Time: Ord + Clone,
+        Place: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  let total = |set: &[usize]| set.iter().fold(Weight::default(), |total, &i| total + intervals[i].weight());
+
+  // rectangles lasting no time overlap nothing they could be left with, so once only they are left, all are selected
+  let mut starts: Vec<Time> = members.iter()
+    .filter(|&&i| intervals[i].start() < intervals[i].end())
+    .map(|&i| intervals[i].start())
+    .collect();
+  if starts.is_empty() { return (total(&members), members); }
+  let middle = starts.len() / 2;
+  let median = starts.select_nth_unstable(middle).1.clone();
+
+  let mut crossing = vec![];
+  let (mut before, mut after) = (vec![], vec![]);
+  for i in members {
+    if intervals[i].end() <= median { before.push(i); }
+    else if intervals[i].start() > median { after.push(i); }
+    else { crossing.push(i); }
+  }
+
+  let crossing_spans: Vec<Range<Place>> = crossing.iter().map(|&i| spans[i].clone()).collect();
+  let weights: Vec<Weight> = crossing.iter().map(|&i| intervals[i].weight()).collect();
+  let crossing: Vec<usize> = interval_graph::max_weight_independent_set(&crossing_spans, &weights)
+    .into_iter()
+    .map(|k| crossing[k])
+    .collect();
+
+  let (before_weight, mut before) = split(intervals, spans, before);
+  let (after_weight, after) = split(intervals, spans, after);
+  let (crossing_weight, sides_weight) = (total(&crossing), before_weight + after_weight);
+  if crossing_weight >= sides_weight { return (crossing_weight, crossing); }
+  before.extend(after);
+  (sides_weight, before)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::ops::Range;
+  use crate::{rectangles::{approximate, exact, overlaps}, testing::{brute_force_best, pairwise, Lcg}, WeightedInterval};
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(89);
+
+    for _ in 0..300 {
+      let n = rng.next(11) as usize;
+      let intervals: Vec<WeightedInterval<u32, u8>> = (0..n)
+        .map(|_| { let start = rng.next(8) as u8; (start, start + rng.next(5) as u8, rng.next(10)).into() })
+        .collect();
+      let spans: Vec<Range<u8>> = (0..n).map(|_| { let low = rng.next(8) as u8; low..low + rng.next(5) as u8 }).collect();
+
+      let fits = |set: &[usize]| pairwise(set, |a, b| !overlaps(&intervals, &spans, a, b));
+      let weight = |set: &[usize]| set.iter().map(|&i| intervals[i].weight).sum::<u32>();
+      let best = brute_force_best(&intervals, fits);
+
+      let selected = exact(&intervals, |i| spans[i].clone());
+      assert!(fits(&selected), "{intervals:?} {spans:?}");
+      assert_eq!(weight(&selected), best, "{intervals:?} {spans:?}");
+      assert!(selected.windows(2).all(|pair| pair[0] < pair[1]));
+
+      let selected = approximate(&intervals, |i| spans[i].clone());
+      assert!(fits(&selected), "{intervals:?} {spans:?}");
+      assert!((n.max(1).ilog2() + 2) * weight(&selected) >= best, "{intervals:?} {spans:?}");
+      assert!(selected.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+  }
+}
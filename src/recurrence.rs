@@ -0,0 +1,174 @@
+//! Recurring intervals ("every weekday from 9 to 10"), expanded into the concrete intervals the solvers take.
+
+use std::ops::{Add, Range};
+use crate::WeightedInterval;
+
+/// A rule repeating an interval every `Step`, from a first occurrence on, until stopped by a count, a date, or the
+/// horizon it is expanded over. Occurrences can be filtered, cancelled, and replaced one by one.
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::recurrence::Recurrence;
+/// use chrono::{Datelike, NaiveDate, NaiveDateTime, TimeDelta, Weekday};
+///
+/// let at = |day, hour| NaiveDate::from_ymd_opt(2024, 1, day).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+///
+/// // every weekday from 9 to 10, weight 5 (1 January 2024 is a Monday)
+/// let standup = Recurrence::new((at(1, 9), at(1, 10), 5).into(), TimeDelta::days(1))
+///   .only(|start: &NaiveDateTime| !matches!(start.weekday(), Weekday::Sat | Weekday::Sun))
+///   .except(at(3, 9))
+///   .replace(at(4, 9), (at(4, 11), at(4, 12), 5).into());
+///
+/// let week = standup.expand(at(1, 0)..at(8, 0));
+/// assert_eq!(week.len(), 4);
+/// assert_eq!(week[2], (at(4, 11), at(4, 12), 5).into());
+/// ```
+pub struct Recurrence<'a, Weight: Ord + Add + Clone, Time: Ord + Clone, Step> {
+  first:     WeightedInterval<Weight, Time>,
+  every:     Step,
+  count:     Option<usize>,
+  until:     Option<Time>,
+  only:      Option<Keep<'a, Time>>,
+  except:    Vec<Time>,
+  overrides: Vec<(Time, WeightedInterval<Weight, Time>)>
+}
+
+type Keep<'a, Time> = Box<dyn Fn(&Time) -> bool + 'a>;
+
+impl<'a, Weight, Time, Step> Recurrence<'a, Weight, Time, Step>
+  where Weight: Ord + Add + Clone,
+        Time: Ord + Clone + Add<Step, Output = Time>,
+        Step: Clone
+{
+  /// Repeats `first` every `every`. A step that doesn't move the interval forward yields the first occurrence only.
+  pub fn new(first: WeightedInterval<Weight, Time>, every: Step) -> Self {
+    Self { first, every, count: None, until: None, only: None, except: vec![], overrides: vec![] }
+  }
+
+  /// Stops after `count` occurrences (counting those cancelled or replaced, but not those filtered out by `only`).
+  pub fn count(mut self, count: usize) -> Self {
+    self.count = Some(count);
+    self
+  }
+
+  /// Stops before the first occurrence starting at or after `until`.
+  pub fn until(mut self, until: Time) -> Self {
+    self.until = Some(until);
+    self
+  }
+
+  /// Keeps only the occurrences whose start passes `keep` (e.g. weekdays).
+  pub fn only(mut self, keep: impl Fn(&Time) -> bool + 'a) -> Self {
+    self.only = Some(Box::new(keep));
+    self
+  }
+
+  /// Cancels the occurrence starting at `start`.
+  pub fn except(mut self, start: Time) -> Self {
+    self.except.push(start);
+    self
+  }
+
+  /// Replaces the occurrence starting at `start` with `with` (moving it, resizing it, or reweighting it).
+  pub fn replace(mut self, start: Time, with: WeightedInterval<Weight, Time>) -> Self {
+    self.overrides.push((start, with));
+    self
+  }
+
+  /// The occurrences starting within `horizon`, in chronological order (replacements taking the place of the
+  /// occurrence they replace). `O(m (c + r))` in the number `m` of occurrences up to the end of the horizon,
+  /// cancellations `c`, and replacements `r`.
+  #[must_use]
+  pub fn expand(&self, horizon: Range<Time>) -> Vec<WeightedInterval<Weight, Time>> {
+    let mut expanded = vec![];
+    let (mut start, mut end) = (self.first.start.clone(), self.first.end.clone());
+    let mut occurrences = 0;
+
+    loop {
+      let stopped = start >= horizon.end
+        || self.until.as_ref().is_some_and(|until| start >= *until)
+        || self.count.is_some_and(|count| occurrences >= count);
+      if stopped { break; }
+
+      if self.only.as_ref().is_none_or(|keep| keep(&start)) {
+        occurrences += 1;
+
+        if start >= horizon.start && !self.except.contains(&start) {
+          expanded.push(match self.overrides.iter().find(|(replaced, _)| *replaced == start) {
+            Some((_, with)) => with.clone(),
+            None            => WeightedInterval { start: start.clone(), end: end.clone(), weight: self.first.weight.clone() }
+          });
+        }
+      }
+
+      let next = start.clone() + self.every.clone();
+      if next <= start { break; }
+      (start, end) = (next, end + self.every.clone());
+    }
+
+    expanded
+  }
+}
+
+/// Expands every rule over `horizon`, sorting the occurrences by end, ready for [`sorted`](crate::sorted).
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::recurrence::{expand_all, Recurrence};
+/// let rules = [
+///   Recurrence::new((0, 2, 3).into(), 10),
+///   Recurrence::new((1, 4, 4).into(), 5).count(3)
+/// ];
+///
+/// let occurrences: Vec<WeightedInterval<u32, u32>> = expand_all(&rules, 0..30);
+/// assert_eq!(occurrences.len(), 6);
+///
+/// let mut memo = vec![0; occurrences.len()];
+/// let mut best = vec![];
+/// sorted(&occurrences, &mut memo, &mut best);
+/// assert_eq!(best.iter().map(|i| i.weight).sum::<u32>(), 15);
+/// ```
+#[must_use]
+pub fn expand_all<Weight, Time, Step>(
+  rules:   &[Recurrence<'_, Weight, Time, Step>],
+  horizon: Range<Time>
+) -> Vec<WeightedInterval<Weight, Time>>
+  where Weight: Ord + Add + Clone,
+        Time: Ord + Clone + Add<Step, Output = Time>,
+        Step: Clone
+{
+  let mut expanded: Vec<WeightedInterval<Weight, Time>> = rules.iter().flat_map(|rule| rule.expand(horizon.clone())).collect();
+  phase!("sort", expanded.len(), expanded.sort_by(|a, b| a.end.cmp(&b.end)));
+  expanded
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{recurrence::Recurrence, WeightedInterval};
+
+  fn starts(expanded: Vec<WeightedInterval<u8, u32>>) -> Vec<u32> { expanded.into_iter().map(|i| i.start).collect() }
+
+  #[test]
+  fn stopping() {
+    let hourly = || Recurrence::new((2, 3, 1u8).into(), 4u32);
+
+    assert_eq!(starts(hourly().expand(0..20)), vec![2, 6, 10, 14, 18]);
+    assert_eq!(starts(hourly().expand(7..20)), vec![10, 14, 18]);
+    assert_eq!(starts(hourly().count(2).expand(0..20)), vec![2, 6]);
+    assert_eq!(starts(hourly().count(4).expand(7..20)), vec![10, 14]);
+    assert_eq!(starts(hourly().until(14).expand(0..20)), vec![2, 6, 10]);
+    assert_eq!(starts(Recurrence::new((2, 3, 1u8).into(), 0u32).expand(0..20)), vec![2]);
+  }
+
+  #[test]
+  fn filters_and_overrides() {
+    let rule = Recurrence::new((0, 1, 1u8).into(), 1u32)
+      .only(|start| start % 3 != 0)
+      .count(4)
+      .except(2)
+      .replace(4, (4, 6, 9).into());
+
+    let expanded = rule.expand(0..100);
+    assert_eq!(expanded, vec![(1, 2, 1).into(), (4, 6, 9).into(), (5, 6, 1).into()]);
+  }
+}
@@ -0,0 +1,102 @@
+use std::ops::{Add, Sub};
+use crate::{solvers::solve_by, traits};
+
+/// The outcome of [`unsorted_with_rejection`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rejection<Weight> {
+  /// The indices of the selected intervals, in descending end order.
+  pub selected: Vec<usize>,
+  /// The indices of the rejected intervals, ascending.
+  pub rejected: Vec<usize>,
+  /// The weight of the selected intervals, less the penalties of the rejected ones.
+  pub net:      Weight
+}
+
+/// Solves the problem when every interval left out costs a penalty: maximizes the weight of the selected intervals,
+/// less `penalty(i)` for each interval `i` rejected. `O(n log n)` in interval number.
+///
+/// Rejecting costs the penalty, so selecting an interval is worth its weight *and* its penalty, and the net objective is
+/// that of the intervals weighted so, less all the penalties. Intervals whose weight and penalty add up to zero or less
+/// are never worth selecting, and are rejected outright.
+///
+/// `i` is an index into `intervals`, which can be in any order. The net objective can be negative (when the penalties
+/// outweigh what can be selected), so signed weights are needed unless that is known not to happen.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<WeightedInterval<i32, u8>> = vec![(0, 4, 5).into(), (2, 6, 4).into(), (5, 8, 2).into()];
+/// // turning away the second job breaks a contract
+/// let penalty = [0, 6, 0];
+///
+/// let outcome = unsorted_with_rejection(&jobs, |i| penalty[i]);
+/// assert_eq!(outcome.selected, vec![1]);
+/// assert_eq!(outcome.rejected, vec![0, 2]);
+/// assert_eq!(outcome.net, 4);
+/// ```
+#[must_use]
+pub fn unsorted_with_rejection<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  penalty:   impl Fn(usize) -> Weight
+) -> Rejection<Weight>
+  where Weight: Ord + Add<Output = Weight> + Sub<Output = Weight> + Clone + Default,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  let worth = |i: usize| intervals[i].weight() + penalty(i);
+
+  // visit the intervals worth selecting in order of end time
+  let mut order: Vec<usize> = (0..intervals.len()).filter(|&i| worth(i) > Weight::default()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  let mut positions = vec![];
+  solve_by(
+    order.len(),
+    |k| worth(order[k]),
+    |k| ends[..k].partition_point(|e| *e <= intervals[order[k]].start()).checked_sub(1),
+    &mut positions
+  );
+  let selected: Vec<usize> = positions.into_iter().map(|k| order[k]).collect();
+
+  let mut is_selected = vec![false; intervals.len()];
+  for &i in &selected { is_selected[i] = true; }
+  let rejected: Vec<usize> = (0..intervals.len()).filter(|&i| !is_selected[i]).collect();
+
+  let gained = selected.iter().fold(Weight::default(), |total, &i| total + intervals[i].weight());
+  let lost = rejected.iter().fold(Weight::default(), |total, &i| total + penalty(i));
+  Rejection { selected, rejected, net: gained - lost }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{testing::{pairwise, subsets, Lcg}, unsorted_with_rejection, WeightedInterval};
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(5);
+
+    for _ in 0..300 {
+      let n = rng.next(9) as usize;
+      let intervals: Vec<WeightedInterval<i64, u8>> = (0..n)
+        .map(|_| { let start = rng.next(10) as u8; (start, start + 1 + rng.next(4) as u8, i64::from(rng.next(21)) - 8).into() })
+        .collect();
+      let penalties: Vec<i64> = (0..n).map(|_| i64::from(rng.next(9)) - 2).collect();
+
+      let outcome = unsorted_with_rejection(&intervals, |i| penalties[i]);
+
+      let best = subsets(n)
+        .filter(|set| pairwise(set, |a, b| intervals[a].end <= intervals[b].start || intervals[b].end <= intervals[a].start))
+        .map(|set| (0..n).map(|i| if set.contains(&i) { intervals[i].weight } else { -penalties[i] }).sum::<i64>())
+        .max()
+        .unwrap();
+      assert_eq!(outcome.net, best, "{intervals:?} {penalties:?}");
+
+      let net = (0..n).map(|i| if outcome.selected.contains(&i) { intervals[i].weight } else { -penalties[i] }).sum::<i64>();
+      assert_eq!(net, best);
+      assert_eq!(outcome.selected.len() + outcome.rejected.len(), n);
+      assert!(outcome.selected.windows(2).all(|pair| intervals[pair[1]].end <= intervals[pair[0]].start));
+    }
+  }
+}
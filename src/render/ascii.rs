@@ -0,0 +1,138 @@
+use std::{fmt::Display, ops::Add};
+use crate::traits;
+use super::{Axis, selected};
+
+/// Horizontal distance (in characters) between two neighbouring time labels, unless a label needs more room.
+const MIN_CELL: usize = 4;
+
+/// Characters drawn before the first / after the last axis label (`◀──` and `──▶`).
+const MARGIN: usize = 3;
+
+/// Draws `intervals` on a text timeline, in the style of the diagram in the crate docs.
+///
+/// - Each interval is drawn as a box labelled with its weight, boxes are packed into as few rows as possible.
+/// - Members of `solution` (matched by start, end, and weight) are drawn with double lines (`╔═══╗`).
+/// - The axis is ordinal, see the [module docs](crate::render).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 2, 3).into(),
+///   (1, 3, 5).into(),
+///   (3, 4, 4).into()
+/// ];
+/// let optimal = unsorted(&intervals);
+///
+/// let picture = render::ascii(&intervals, Some(&optimal));
+/// assert_eq!(
+///   picture,
+///   concat!(
+///     "       5═══════╗\n",
+///     "       ╚═══════╝\n",
+///     "   3───────┐   4═══╗\n",
+///     "   └───────┘   ╚═══╝\n",
+///     "◀──0───1───2───3───4──▶\n"
+///   )
+/// );
+/// ```
+pub fn ascii<Weight, Time, Interval>(
+  intervals: &[Interval],
+  solution:  Option<&[Interval]>
+) -> String
+  where Weight: Ord + Add<Output = Weight> + Display,
+        Time: Ord + Display,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  // every distinct endpoint becomes one column of the axis
+  let axis = Axis::new(intervals);
+  let cell = (axis.widest_label() + 2).max(MIN_CELL);
+  let column = |t: &Time| MARGIN + cell * axis.position(t);
+
+  // boxes as (first column, last column, label, selected), in order of start time
+  let mut boxes: Vec<(usize, usize, String, bool)> = intervals.iter()
+    .map(|i| {
+      let (a, b) = (column(&i.start()), column(&i.end()));
+      (a, b.max(a + 1), i.weight().to_string(), selected(i, solution))
+    })
+    .collect();
+  boxes.sort_by_key(|b| b.0);
+
+  // pack boxes into rows, placing each into the lowest row where it fits
+  let mut rows: Vec<(usize, Vec<usize>)> = vec![]; // (last occupied column, box indices)
+  for (index, b) in boxes.iter().enumerate() {
+    match rows.iter_mut().find(|r| r.0 < b.0) {
+      Some(row) => { row.0 = b.1; row.1.push(index); }
+      None      => rows.push((b.1, vec![index]))
+    }
+  }
+
+  let axis_end = axis.times.last().zip(axis.labels.last()).map_or(0, |(t, l)| column(t) + l.chars().count());
+  let width = rows.iter().map(|r| r.0 + 1).max().unwrap_or(0).max(axis_end + MARGIN);
+
+  let mut out = String::new();
+  for (_, members) in rows.iter().rev() {
+    let mut top    = vec![' '; width];
+    let mut bottom = vec![' '; width];
+
+    for &index in members {
+      let (a, b, label, chosen) = &boxes[index];
+      let (h, tr, bl, br) = if *chosen { ('═', '╗', '╚', '╝') } else { ('─', '┐', '└', '┘') };
+
+      for x in *a..*b { top[x] = h; bottom[x] = h; }
+      for (x, c) in (*a..*b).zip(label.chars()) { top[x] = c; }
+      top[*b] = tr;
+      bottom[*a] = bl;
+      bottom[*b] = br;
+    }
+
+    push_line(&mut out, &top);
+    push_line(&mut out, &bottom);
+  }
+
+  // the axis itself
+  let mut line = vec!['─'; width];
+  line[0] = '◀';
+  for (t, label) in axis.times.iter().zip(&axis.labels) {
+    for (x, c) in (column(t)..).zip(label.chars()) { line[x] = c; }
+  }
+  line[width - 1] = '▶';
+  push_line(&mut out, &line);
+
+  out
+}
+
+fn push_line(out: &mut String, line: &[char]) {
+  out.extend(line.iter());
+  out.truncate(out.trim_end_matches(' ').len());
+  out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{WeightedInterval, render::ascii};
+
+  #[test]
+  fn empty() {
+    let intervals: [WeightedInterval<u8, u8>; 0] = [];
+    assert_eq!(ascii(&intervals, None), "◀─▶\n");
+  }
+
+  #[test]
+  fn zero_length_and_wide_labels() {
+    let intervals = [
+      WeightedInterval { start: 100u16, end: 100u16, weight: 7u8 },
+      WeightedInterval { start: 100u16, end: 2000u16, weight: 12u8 }
+    ];
+
+    assert_eq!(
+      ascii(&intervals, None),
+      concat!(
+        "   12────┐\n",
+        "   └─────┘\n",
+        "   7┐\n",
+        "   └┘\n",
+        "◀──100───2000──▶\n"
+      )
+    );
+  }
+}
@@ -0,0 +1,51 @@
+use std::{fmt::{Display, Write}, ops::Add};
+use crate::{interval_graph, traits};
+use super::selected;
+
+/// Serializes the conflict graph of `intervals` (see [`interval_graph::conflicts`]) in Graphviz DOT format.
+///
+/// - Vertices are named by their index into `intervals`, and labelled `start–end (weight)`.
+/// - Members of `solution` (matched by start, end, and weight) are filled in.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 2, 3).into(),
+///   (1, 3, 5).into()
+/// ];
+/// let optimal = unsorted(&intervals);
+///
+/// let graph = render::dot(&intervals, Some(&optimal));
+/// assert!(graph.contains(r#"0 [label="0–2 (3)"];"#));
+/// assert!(graph.contains("0 -- 1;"));
+/// ```
+pub fn dot<Weight, Time, Interval>(
+  intervals: &[Interval],
+  solution:  Option<&[Interval]>
+) -> String
+  where Weight: Ord + Add<Output = Weight> + Display,
+        Time: Ord + Display,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  let mut edges = interval_graph::conflicts(intervals);
+  edges.sort_unstable();
+
+  // writing into a `String` can't fail
+  let mut out = String::from("graph conflicts {\n  node [shape=box];\n");
+
+  for (index, interval) in intervals.iter().enumerate() {
+    let label = escape(&format!("{}–{} ({})", interval.start(), interval.end(), interval.weight()));
+    let style = if selected(interval, solution) { r##", style=filled, fillcolor="#4e79a7", fontcolor=white"## } else { "" };
+    let _ = writeln!(out, r#"  {index} [label="{label}"{style}];"#);
+  }
+
+  for (a, b) in edges {
+    let _ = writeln!(out, "  {a} -- {b};");
+  }
+
+  out.push_str("}\n");
+  out
+}
+
+/// Escapes the characters with special meaning inside a quoted DOT string.
+fn escape(text: &str) -> String { text.replace('\\', "\\\\").replace('"', "\\\"") }
@@ -0,0 +1,54 @@
+//! Human-friendly pictures of problems and their solutions.
+//!
+//! The timeline renderers use an ordinal time axis: every distinct start/end value gets its own evenly spaced label.
+//! This is what lets any `Ord + Display` time type be drawn, at the cost of gaps not being to scale.
+
+use std::{fmt::Display, ops::Add};
+use crate::traits;
+
+mod ascii;
+mod dot;
+#[cfg(feature = "svg")]
+mod svg;
+
+pub use ascii::ascii;
+pub use dot::dot;
+#[cfg(feature = "svg")]
+pub use svg::svg;
+
+/// The distinct endpoints of a problem, in ascending order, along with their labels.
+struct Axis<Time> {
+  times:  Vec<Time>,
+  labels: Vec<String>
+}
+
+impl<Time: Ord + Display> Axis<Time> {
+  fn new<Weight, Interval>(intervals: &[Interval]) -> Self
+    where Weight: Ord + Add,
+          Interval: traits::Interval<Time> + traits::Weighted<Weight>
+  {
+    let mut times: Vec<Time> = intervals.iter().flat_map(|i| [i.start(), i.end()]).collect();
+    times.sort_unstable();
+    times.dedup();
+
+    let labels = times.iter().map(|t| t.to_string()).collect();
+    Self { times, labels }
+  }
+
+  /// Ordinal position of `t` on the axis (`t` must be one of the endpoints the axis was built from).
+  fn position(&self, t: &Time) -> usize { self.times.binary_search(t).unwrap_or(0) }
+
+  /// Character count of the widest label.
+  fn widest_label(&self) -> usize { self.labels.iter().map(|l| l.chars().count()).max().unwrap_or(0) }
+}
+
+/// Whether `interval` is a member of `solution` (matched by start, end, and weight).
+fn selected<Weight, Time, Interval>(interval: &Interval, solution: Option<&[Interval]>) -> bool
+  where Weight: Ord + Add,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  solution.is_some_and(|s| s.iter().any(|o| {
+    o.start() == interval.start() && o.end() == interval.end() && o.weight() == interval.weight()
+  }))
+}
@@ -0,0 +1,120 @@
+use std::{fmt::{Display, Write}, ops::Add};
+use crate::traits;
+use super::{Axis, selected};
+
+const MARGIN:     usize = 24; // blank space around the chart (px)
+const ROW_HEIGHT: usize = 26; // vertical distance between bars (px)
+const BAR_HEIGHT: usize = 18; // (px)
+const AXIS_SPACE: usize = 28; // room below the bars for the time labels (px)
+const CHAR_WIDTH: usize = 8;  // rough width of a label character (px), used to keep labels from colliding
+const MIN_CELL:   usize = 48; // minimum horizontal distance between two neighbouring time labels (px)
+
+const FILL:          &str = "#bab0ac";
+const FILL_SELECTED: &str = "#4e79a7";
+
+/// Draws `intervals` as a Gantt-style SVG document (one bar per interval, ordered by start time).
+///
+/// - Bars are labelled with their weight, and carry a `<title>` tooltip of `start–end (weight)`.
+/// - Members of `solution` (matched by start, end, and weight) are highlighted, and marked with `class="selected"`.
+/// - The axis is ordinal, see the [module docs](crate::render).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 2, 3).into(),
+///   (1, 3, 5).into(),
+///   (3, 4, 4).into()
+/// ];
+/// let optimal = unsorted(&intervals);
+///
+/// let document = render::svg(&intervals, Some(&optimal));
+/// assert!(document.starts_with("<svg"));
+/// assert_eq!(document.matches(r#"class="selected""#).count(), 2);
+/// ```
+pub fn svg<Weight, Time, Interval>(
+  intervals: &[Interval],
+  solution:  Option<&[Interval]>
+) -> String
+  where Weight: Ord + Add<Output = Weight> + Display,
+        Time: Ord + Display,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  let axis = Axis::new(intervals);
+  let cell = (axis.widest_label() * CHAR_WIDTH + CHAR_WIDTH * 2).max(MIN_CELL);
+  let x = |t: &Time| MARGIN + cell * axis.position(t);
+
+  let mut order: Vec<&Interval> = intervals.iter().collect();
+  order.sort_by_key(|i| i.start());
+
+  let chart_bottom = MARGIN + ROW_HEIGHT * order.len();
+  let width  = 2 * MARGIN + cell * axis.times.len().saturating_sub(1);
+  let height = chart_bottom + AXIS_SPACE;
+
+  // writing into a `String` can't fail
+  let mut out = String::new();
+  let _ = writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12">"#);
+  let _ = writeln!(out, r#"<rect width="{width}" height="{height}" fill="white"/>"#);
+
+  // grid lines and labels for every distinct endpoint
+  for (t, label) in axis.times.iter().zip(&axis.labels) {
+    let x = x(t);
+    let _ = writeln!(out, r##"<line x1="{x}" y1="{MARGIN}" x2="{x}" y2="{chart_bottom}" stroke="#e0e0e0"/>"##);
+    let _ = writeln!(out, r#"<text x="{x}" y="{}" text-anchor="middle">{}</text>"#, chart_bottom + AXIS_SPACE / 2, escape(label));
+  }
+
+  // one bar per interval
+  for (row, interval) in order.iter().enumerate() {
+    let (start, end, weight) = (interval.start(), interval.end(), interval.weight());
+    let (x0, x1) = (x(&start), x(&end));
+    let y = MARGIN + ROW_HEIGHT * row + (ROW_HEIGHT - BAR_HEIGHT) / 2;
+    let w = x1.saturating_sub(x0).max(2); // an inverted interval is drawn as a sliver at its start, like an empty one
+
+    let (class, fill) = if selected(*interval, solution) { ("selected", FILL_SELECTED) } else { ("interval", FILL) };
+    let weight = escape(&weight.to_string());
+
+    let _ = writeln!(out, r#"<g class="{class}">"#);
+    let _ = writeln!(out, r#"<title>{}–{} ({weight})</title>"#, escape(&start.to_string()), escape(&end.to_string()));
+    let _ = writeln!(out, r#"<rect x="{x0}" y="{y}" width="{w}" height="{BAR_HEIGHT}" rx="3" fill="{fill}"/>"#);
+    let _ = writeln!(out, r#"<text x="{}" y="{}" fill="white">{weight}</text>"#, x0 + 4, y + BAR_HEIGHT - 5);
+    let _ = writeln!(out, "</g>");
+  }
+
+  out.push_str("</svg>\n");
+  out
+}
+
+/// Escapes the characters with special meaning in XML text and attribute values.
+fn escape(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&'  => out.push_str("&amp;"),
+      '<'  => out.push_str("&lt;"),
+      '>'  => out.push_str("&gt;"),
+      '"'  => out.push_str("&quot;"),
+      '\'' => out.push_str("&apos;"),
+      c    => out.push(c)
+    }
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::WeightedInterval;
+  use super::{escape, svg};
+
+  #[test]
+  fn escapes_markup() {
+    assert_eq!(escape(r#"<a & 'b' "c">"#), "&lt;a &amp; &apos;b&apos; &quot;c&quot;&gt;");
+  }
+
+  #[test]
+  fn draws_inverted_intervals() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![(5, 2, 1).into(), (0, 3, 2).into()];
+
+    let document = svg(&intervals, None);
+    assert_eq!(document.matches("<rect x=").count(), 2);
+    assert_eq!(document.matches(r#"width="2""#).count(), 1);
+  }
+}
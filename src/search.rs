@@ -0,0 +1,102 @@
+use crate::{compatibility::Compatibility, traits, util::final_compatible, HalfOpen};
+
+/// The position of the last of `intervals` (sorted by end) that ends by `start`, i.e. the last one an interval starting
+/// at `start` may follow (`None` if there is none). `O(log n)` in interval number.
+///
+/// This is the predecessor search behind the solvers, for building other recurrences over the same structure. Should
+/// `intervals` not be sorted by end, no claims are made about the result.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 1).into(), (1, 4, 1).into(), (3, 6, 1).into()];
+///
+/// assert_eq!(last_compatible(&intervals, &1), None);
+/// assert_eq!(last_compatible(&intervals, &4), Some(1));
+/// assert_eq!(last_compatible(&intervals[..1], &4), Some(0));
+/// ```
+#[must_use]
+pub fn last_compatible<Time, Interval>(intervals: &[Interval], start: &Time) -> Option<usize>
+  where Time: Ord,
+        Interval: traits::Interval<Time>
+{
+  last_compatible_with(intervals, start, HalfOpen)
+}
+
+/// Like [`last_compatible`], but `compatibility` decides which intervals one starting at `start` may follow (e.g.
+/// [`Closed`](crate::Closed) excludes those ending right at `start`).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 1).into(), (1, 4, 1).into(), (3, 6, 1).into()];
+///
+/// assert_eq!(last_compatible_with(&intervals, &4, Closed), Some(0));
+/// assert_eq!(last_compatible_with(&intervals, &7, MinGap(2)), Some(1));
+/// ```
+#[must_use]
+pub fn last_compatible_with<Time, Interval>(
+  intervals:     &[Interval],
+  start:         &Time,
+  compatibility: impl Compatibility<Time>
+) -> Option<usize>
+  where Time: Ord,
+        Interval: traits::Interval<Time>
+{
+  intervals.partition_point(|i| compatibility.compatible(&i.end(), start)).checked_sub(1)
+}
+
+/// The predecessor of every one of `intervals` (sorted by end): the position of the last interval before it that it
+/// may follow, as the solvers find it (`None` if there is none). `O(n log d)` for `n` intervals whose predecessors are
+/// at most `d` positions back, so about linear when intervals are short compared to their spacing.
+///
+/// Should `intervals` not be sorted by end, no claims are made about the result.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 1).into(), (1, 4, 1).into(), (3, 6, 1).into(), (4, 7, 1).into()];
+///
+/// assert_eq!(predecessors(&intervals), vec![None, None, Some(0), Some(1)]);
+/// ```
+#[must_use]
+pub fn predecessors<Time, Interval>(intervals: &[Interval]) -> Vec<Option<usize>>
+  where Time: Ord,
+        Interval: traits::Interval<Time>
+{
+  predecessors_with(intervals, HalfOpen)
+}
+
+/// Like [`predecessors`], but `compatibility` decides which intervals may follow one another.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 1).into(), (1, 4, 1).into(), (3, 6, 1).into(), (4, 7, 1).into()];
+///
+/// assert_eq!(predecessors_with(&intervals, Closed), vec![None, None, Some(0), Some(0)]);
+/// ```
+#[must_use]
+pub fn predecessors_with<Time, Interval>(intervals: &[Interval], compatibility: impl Compatibility<Time>) -> Vec<Option<usize>>
+  where Time: Ord,
+        Interval: traits::Interval<Time>
+{
+  (0..intervals.len()).map(|i| final_compatible(intervals, i, &compatibility)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{last_compatible, last_compatible_with, predecessors, predecessors_with, util::final_compatible, Closed, HalfOpen, WeightedInterval};
+
+  #[test]
+  fn matches_the_solvers_search() {
+    let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..200u32).map(|i| (i * 11 % 97, i * 11 % 97 + i % 6, 1).into()).collect();
+    intervals.sort_by_key(|i| i.end);
+
+    let (half_open, closed) = (predecessors(&intervals), predecessors_with(&intervals, Closed));
+    for index in 0..intervals.len() {
+      let start = intervals[index].start;
+      assert_eq!(half_open[index], final_compatible(&intervals, index, &HalfOpen));
+      assert_eq!(closed[index], final_compatible(&intervals, index, &Closed));
+      assert_eq!(last_compatible(&intervals[..index], &start), final_compatible(&intervals, index, &HalfOpen));
+      assert_eq!(last_compatible_with(&intervals[..index], &start, Closed), final_compatible(&intervals, index, &Closed));
+    }
+    assert_eq!(last_compatible(&intervals[..0], &5), None);
+  }
+}
@@ -0,0 +1,640 @@
+//! Sequencing jobs on a single machine: each job runs for its processing time, one after another, from time zero.
+//!
+//! Unlike intervals, jobs aren't pinned to the timeline: the solvers here decide the order they run in (and which of
+//! them are worth running on time), and their times follow from it.
+
+use std::{cmp::Reverse, collections::BinaryHeap, ops::{Add, Mul, Range, Sub}};
+use crate::traits;
+
+/// If a type is `Processing`, it is a job taking some time to run.
+pub trait Processing<Time> {
+  fn processing(&self) -> Time;
+}
+
+/// If a type is `Due`, it is a job that should be done by some time.
+pub trait Due<Time> {
+  fn due(&self) -> Time;
+}
+
+/// If a type is `Released`, it is a job that can't start before some time.
+pub trait Released<Time> {
+  fn release(&self) -> Time;
+}
+
+/// If a type is `TwoStage`, it is a job running on one machine, then on another.
+pub trait TwoStage<Time> {
+  /// How long it runs on the first machine.
+  fn first(&self) -> Time;
+  /// How long it runs on the second machine.
+  fn second(&self) -> Time;
+}
+
+/// `(processing, due)`, for when a struct would be overkill.
+impl<Time: Clone> Processing<Time> for (Time, Time) {
+  fn processing(&self) -> Time { self.0.clone() }
+}
+
+impl<Time: Clone> Due<Time> for (Time, Time) {
+  fn due(&self) -> Time { self.1.clone() }
+}
+
+/// `((processing, due), weight)`, a job paired with a weight.
+impl<Time: Clone, Weight> Processing<Time> for ((Time, Time), Weight) {
+  fn processing(&self) -> Time { self.0.0.clone() }
+}
+
+impl<Time: Clone, Weight> Due<Time> for ((Time, Time), Weight) {
+  fn due(&self) -> Time { self.0.1.clone() }
+}
+
+/// `((release, processing, due), weight)`, a job to run within a window, paired with a weight.
+impl<Time: Clone, Weight> Released<Time> for ((Time, Time, Time), Weight) {
+  fn release(&self) -> Time { self.0.0.clone() }
+}
+
+impl<Time: Clone, Weight> Processing<Time> for ((Time, Time, Time), Weight) {
+  fn processing(&self) -> Time { self.0.1.clone() }
+}
+
+impl<Time: Clone, Weight> Due<Time> for ((Time, Time, Time), Weight) {
+  fn due(&self) -> Time { self.0.2.clone() }
+}
+
+impl<Time, Weight: Ord + Add + Clone> traits::Weighted<Weight> for ((Time, Time, Time), Weight) {
+  fn weight(&self) -> Weight { self.1.clone() }
+}
+
+/// `(first, second)`, the processing times of a job on each machine.
+impl<Time: Clone> TwoStage<Time> for (Time, Time) {
+  fn first(&self) -> Time { self.0.clone() }
+  fn second(&self) -> Time { self.1.clone() }
+}
+
+/// The outcome of [`johnson_schedule`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FlowShop<Time> {
+  /// The indices of the jobs, in the order both machines run them.
+  pub order:    Vec<usize>,
+  /// When the first machine runs each job, in that order.
+  pub first:    Vec<Range<Time>>,
+  /// When the second machine runs each job, in that order.
+  pub second:   Vec<Range<Time>>,
+  /// When the second machine is done with the last job.
+  pub makespan: Time
+}
+
+/// Chooses the most jobs that can be done by their due times (Moore–Hodgson). `O(n log n)` in job number.
+///
+/// Returns the indices of the jobs done on time, in the order to run them (earliest due first). The other jobs are
+/// late whatever is done, and can run after them in any order.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // (processing, due)
+/// let jobs = [(2u32, 3u32), (3, 4), (2, 5), (1, 6)];
+///
+/// assert_eq!(sequencing::min_late(&jobs), vec![0, 2, 3]);
+/// ```
+#[must_use]
+pub fn min_late<Time, Job>(jobs: &[Job]) -> Vec<usize>
+  where Time: Ord + Add<Output = Time> + Sub<Output = Time> + Clone + Default,
+        Job: Processing<Time> + Due<Time>
+{
+  let mut order: Vec<usize> = (0..jobs.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| jobs[i].due()));
+
+  // run the jobs earliest due first, and whenever one would be late, drop the longest so far: the rest are then back
+  // on time, with as much room as can be left for the jobs still to come
+  let mut on_time = vec![false; jobs.len()];
+  let mut running = BinaryHeap::new();
+  let mut completion = Time::default();
+  for &i in &order {
+    completion = completion + jobs[i].processing();
+    running.push((jobs[i].processing(), i));
+    on_time[i] = true;
+
+    if completion > jobs[i].due() {
+      let Some((longest, dropped)) = running.pop() else { unreachable!() };
+      completion = completion - longest;
+      on_time[dropped] = false;
+    }
+  }
+
+  order.into_iter().filter(|&i| on_time[i]).collect()
+}
+
+/// Chooses the jobs of most total weight that can be done by their due times, so that the weight of the late jobs is
+/// least (Lawler–Moore). `O(n H)` in time and space, for `n` jobs and a horizon `H`, the lesser of the total processing
+/// time and the latest due time.
+///
+/// This is pseudo-polynomial: the problem is NP-hard once weights differ, so the running time grows with the magnitude
+/// of the times (use [`min_late`] when all jobs weigh the same). Times must convert losslessly into `u64`.
+///
+/// Returns the indices of the jobs done on time, in the order to run them (earliest due first). The other jobs are
+/// late whatever is done, and can run after them in any order.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // ((processing, due), weight)
+/// let jobs = [((2u32, 3u32), 1u32), ((3, 4), 5), ((2, 5), 1), ((1, 6), 1)];
+///
+/// assert_eq!(sequencing::min_weighted_late(&jobs), vec![1, 2, 3]);
+/// ```
+///
+/// # Panics
+/// If the horizon doesn't fit in memory.
+#[must_use]
+pub fn min_weighted_late<Weight, Time, Job>(jobs: &[Job]) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Time: Ord + Clone + Into<u64>,
+        Job: Processing<Time> + Due<Time> + traits::Weighted<Weight>
+{
+  let mut order: Vec<usize> = (0..jobs.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| jobs[i].due()));
+
+  let duration = |time: Time| usize::try_from(time.into()).expect("the horizon must fit in memory");
+  let processing: Vec<usize> = order.iter().map(|&i| duration(jobs[i].processing())).collect();
+  let horizon = processing.iter()
+    .sum::<usize>()
+    .min(order.iter().map(|&i| duration(jobs[i].due())).max().unwrap_or(0));
+
+  // `best[t]` is the most weight done on time by jobs taking `t` in all, so that taking job `k` last finishes it at `t`
+  let width = horizon + 1;
+  let mut best: Vec<Option<Weight>> = vec![None; width];
+  best[0] = Some(Weight::default());
+  let mut taken = vec![false; order.len() * width];
+
+  phase!("memoize", order.len(), for (k, &i) in order.iter().enumerate() {
+    let (length, due) = (processing[k], duration(jobs[i].due()).min(horizon));
+    if length > due { continue; }
+
+    for t in (length..=due).rev() {
+      let Some(before) = best[t - length].clone() else { continue };
+      let with = before + jobs[i].weight();
+      if best[t].as_ref().is_none_or(|current| with > *current) {
+        best[t] = Some(with);
+        taken[k * width + t] = true;
+      }
+    }
+  });
+
+  // walk back from the best total, through the jobs that last improved it
+  let mut t = (0..width).max_by_key(|&t| best[t].clone()).unwrap_or(0);
+  let mut on_time = vec![];
+  phase!("reconstruct", order.len(), for k in (0..order.len()).rev() {
+    if taken[k * width + t] {
+      on_time.push(order[k]);
+      t -= processing[k];
+    }
+  });
+
+  on_time.reverse();
+  on_time
+}
+
+/// Chooses the jobs of most total weight that can be done by their due times, when every job takes one unit of time
+/// (processing times are ignored). `O(n log n)` in job number.
+///
+/// Greedily, each job, heaviest first, takes the latest free unit before it is due, if any is left, found through a
+/// disjoint-set forest of the taken units. Due times are counted in units from time zero, and must convert losslessly
+/// into `u64`.
+///
+/// Returns the indices of the jobs done on time, in the order to run them (earliest due first). The other jobs are
+/// late whatever is done, and can run after them in any order.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // ((processing, due), weight)
+/// let jobs = [((1u32, 2u32), 100u32), ((1, 1), 19), ((1, 2), 27), ((1, 1), 25), ((1, 3), 15)];
+///
+/// assert_eq!(sequencing::max_weight_unit_jobs(&jobs), vec![2, 0, 4]);
+/// ```
+#[must_use]
+pub fn max_weight_unit_jobs<Weight, Time, Job>(jobs: &[Job]) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Time: Into<u64>,
+        Job: Due<Time> + traits::Weighted<Weight>
+{
+  let mut order: Vec<usize> = (0..jobs.len()).filter(|&i| jobs[i].weight() > Weight::default()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| Reverse(jobs[i].weight())));
+
+  // no more units than jobs are ever taken. `free[u]` leads towards the latest free unit up to `u` (unit 0 standing for
+  // none), and `runs[u]` is the job run in unit `u`
+  let units = order.len();
+  let mut free: Vec<usize> = (0..=units).collect();
+  let mut runs: Vec<Option<usize>> = vec![None; units + 1];
+
+  phase!("assign", order.len(), for &i in &order {
+    let due = usize::try_from(jobs[i].due().into()).map_or(units, |due| due.min(units));
+
+    let mut unit = due;
+    while free[unit] != unit {
+      free[unit] = free[free[unit]];
+      unit = free[unit];
+    }
+    if unit == 0 { continue; }
+
+    runs[unit] = Some(i);
+    free[unit] = unit - 1;
+  });
+
+  // a job may have taken a unit ahead of one due sooner, so run the jobs taken earliest due first
+  let mut on_time: Vec<usize> = runs.into_iter().flatten().collect();
+  on_time.sort_by_key(|&i| jobs[i].due().into());
+  on_time
+}
+
+/// Orders the jobs earliest due first (Jackson's rule), which minimizes the maximum lateness. `O(n log n)` in job number.
+///
+/// A job's lateness is how long after its due time it completes, `completion - due`, negative when it completes early,
+/// so `Time` needs to be signed unless the jobs are known to be late. Returns the indices of the jobs in the order to run
+/// them, and the maximum lateness (`None` if there are no jobs).
+///
+/// ```rust
+/// # use w_inter::*;
+/// // (processing, due)
+/// let jobs = [(3i32, 9i32), (2, 3), (4, 7)];
+///
+/// let (order, lateness) = sequencing::edd_schedule(&jobs);
+/// assert_eq!(order, vec![1, 2, 0]);
+/// assert_eq!(lateness, Some(0));
+/// ```
+#[must_use]
+pub fn edd_schedule<Time, Lateness, Job>(jobs: &[Job]) -> (Vec<usize>, Option<Lateness>)
+  where Time: Ord + Add<Output = Time> + Sub<Output = Lateness> + Clone + Default,
+        Lateness: Ord,
+        Job: Processing<Time> + Due<Time>
+{
+  let mut order: Vec<usize> = (0..jobs.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| jobs[i].due()));
+
+  let mut completion = Time::default();
+  let lateness = order.iter()
+    .map(|&i| {
+      completion = completion.clone() + jobs[i].processing();
+      completion.clone() - jobs[i].due()
+    })
+    .max();
+
+  (order, lateness)
+}
+
+/// Orders the jobs by their processing time per unit of weight, shortest first (Smith's rule), which minimizes the total
+/// weighted completion time. `O(n log n)` in job number.
+///
+/// Weights must not be negative. Jobs weighing nothing add nothing to the objective, and run last. Times multiply by
+/// weights into the objective (for primitives, both must be of the same type). Returns the indices of the jobs in the
+/// order to run them, and the total of their weights times their completion times.
+///
+/// ```rust
+/// # use w_inter::{*, sequencing::Processing};
+/// struct Task { minutes: u32, customers: u32 }
+///
+/// impl Processing<u32> for Task { fn processing(&self) -> u32 { self.minutes } }
+/// impl Weighted<u32> for Task { fn weight(&self) -> u32 { self.customers } }
+///
+/// let tasks = [Task { minutes: 3, customers: 1 }, Task { minutes: 1, customers: 2 }, Task { minutes: 2, customers: 2 }];
+///
+/// // customers wait 2 × 1 + 2 × 3 + 1 × 6 minutes in all
+/// let (order, waited) = sequencing::wspt_schedule(&tasks);
+/// assert_eq!(order, vec![1, 2, 0]);
+/// assert_eq!(waited, 14);
+/// ```
+#[must_use]
+pub fn wspt_schedule<Weight, Time, Cost, Job>(jobs: &[Job]) -> (Vec<usize>, Cost)
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Time: Add<Output = Time> + Mul<Weight, Output = Cost> + Clone + Default,
+        Cost: Ord + Add<Output = Cost> + Default,
+        Job: Processing<Time> + traits::Weighted<Weight>
+{
+  // comparing `p / w` by cross-multiplying only orders jobs that weigh something: those that don't compare equal to all
+  let (mut order, weightless): (Vec<usize>, Vec<usize>) = (0..jobs.len()).partition(|&i| jobs[i].weight() > Weight::default());
+  phase!("sort", order.len(), order.sort_by(|&a, &b| {
+    (jobs[a].processing() * jobs[b].weight()).cmp(&(jobs[b].processing() * jobs[a].weight()))
+  }));
+  order.extend(weightless);
+
+  let mut completion = Time::default();
+  let cost = order.iter().fold(Cost::default(), |cost, &i| {
+    completion = completion.clone() + jobs[i].processing();
+    cost + completion.clone() * jobs[i].weight()
+  });
+
+  (order, cost)
+}
+
+/// Orders jobs running on a first machine, then on a second (a two-machine flow shop), so that the second machine is
+/// done soonest (Johnson's rule). `O(n log n)` in job number.
+///
+/// The jobs quicker on the first machine run first, soonest done there first, so the second machine gets going early,
+/// and the others run last, soonest done on the second machine last, so it isn't left waiting at the end. Each machine
+/// runs the jobs in the same order, as early as it can.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // (first, second)
+/// let jobs = [(3u32, 2u32), (1, 4), (2, 2)];
+///
+/// let shop = sequencing::johnson_schedule(&jobs);
+/// assert_eq!(shop.order, vec![1, 2, 0]);
+/// assert_eq!(shop.first, vec![0..1, 1..3, 3..6]);
+/// assert_eq!(shop.second, vec![1..5, 5..7, 7..9]);
+/// assert_eq!(shop.makespan, 9);
+/// ```
+#[must_use]
+pub fn johnson_schedule<Time, Job>(jobs: &[Job]) -> FlowShop<Time>
+  where Time: Ord + Add<Output = Time> + Clone + Default,
+        Job: TwoStage<Time>
+{
+  let (mut early, mut late): (Vec<usize>, Vec<usize>) = (0..jobs.len()).partition(|&i| jobs[i].first() <= jobs[i].second());
+  phase!("sort", jobs.len(), {
+    early.sort_by_key(|&i| jobs[i].first());
+    late.sort_by_key(|&i| Reverse(jobs[i].second()));
+  });
+  let mut order = early;
+  order.extend(late);
+
+  let (mut first, mut second) = (Vec::with_capacity(order.len()), Vec::with_capacity(order.len()));
+  let (mut free, mut makespan) = (Time::default(), Time::default());
+  for &i in &order {
+    let start = free.clone();
+    free = start.clone() + jobs[i].first();
+    first.push(start..free.clone());
+
+    // the second machine takes the job once the first is done with it, and it is done with the one before
+    let start = free.clone().max(makespan);
+    makespan = start.clone() + jobs[i].second();
+    second.push(start..makespan.clone());
+  }
+
+  FlowShop { order, first, second, makespan }
+}
+
+/// Chooses and places the jobs of most total weight that can each run within its window, from its release time to its
+/// due time, one at a time. Exact, by branch and bound.
+///
+/// Unlike intervals, these jobs can move within their windows. This makes the problem NP-hard even when the times are
+/// small, so no pseudo-polynomial algorithm is known: the search is exponential in the worst case, and is meant for
+/// problems of tens of jobs, or whose windows overlap little. Should all jobs be released at once,
+/// [`min_weighted_late`] is pseudo-polynomial instead.
+///
+/// Jobs weighing nothing are never run. Returns the indices of the jobs run, in the order to run them, each with its
+/// start time.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // ((release, processing, due), weight)
+/// let jobs = [((0u32, 3u32, 4u32), 2u32), ((1, 2, 3), 3), ((3, 2, 6), 2)];
+///
+/// // the first job can't start before the second is done, nor end in time
+/// assert_eq!(sequencing::max_weight_in_windows(&jobs), vec![(1, 1), (2, 3)]);
+/// ```
+#[must_use]
+pub fn max_weight_in_windows<Weight, Time, Job>(jobs: &[Job]) -> Vec<(usize, Time)>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Time: Ord + Add<Output = Time> + Clone,
+        Job: Released<Time> + Processing<Time> + Due<Time> + traits::Weighted<Weight>
+{
+  // the jobs worth running that fit their windows at all, earliest due first, so good plans are found early
+  let mut order: Vec<usize> = (0..jobs.len())
+    .filter(|&i| jobs[i].weight() > Weight::default() && jobs[i].release() + jobs[i].processing() <= jobs[i].due())
+    .collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| jobs[i].due()));
+
+  let mut search = Search { jobs, order: &order, used: vec![false; order.len()], plan: vec![], best: Weight::default(), best_plan: vec![] };
+  phase!("search", order.len(), search.branch(None, Weight::default()));
+  search.best_plan.into_iter().map(|(k, start)| (order[k], start)).collect()
+}
+
+/// The state of the search behind [`max_weight_in_windows`]: plans run jobs one after another, each as early as it can.
+struct Search<'a, Weight, Time, Job> {
+  jobs:      &'a [Job],
+  order:     &'a [usize],
+  /// Whether each job (by position in `order`) is in the plan.
+  used:      Vec<bool>,
+  /// The jobs in the plan (by position in `order`), with their start times.
+  plan:      Vec<(usize, Time)>,
+  best:      Weight,
+  best_plan: Vec<(usize, Time)>
+}
+
+impl<Weight, Time, Job> Search<'_, Weight, Time, Job>
+  where Weight: Ord + Add<Output = Weight> + Clone + Default,
+        Time: Ord + Add<Output = Time> + Clone,
+        Job: Released<Time> + Processing<Time> + Due<Time> + traits::Weighted<Weight>
+{
+  /// When job `k` would start and end, run next once the machine is `free`, if it can still end in time.
+  fn place(&self, k: usize, free: &Option<Time>) -> Option<(Time, Time)> {
+    let job = &self.jobs[self.order[k]];
+    let start = match free { Some(free) => free.clone().max(job.release()), None => job.release() };
+    let end = start.clone() + job.processing();
+    (!self.used[k] && end <= job.due()).then_some((start, end))
+  }
+
+  fn branch(&mut self, free: Option<Time>, weight: Weight) {
+    if weight > self.best {
+      self.best = weight.clone();
+      self.best_plan = self.plan.clone();
+    }
+
+    let places: Vec<Option<(Time, Time)>> = (0..self.order.len()).map(|k| self.place(k, &free)).collect();
+    let bound = places.iter()
+      .enumerate()
+      .filter(|(_, place)| place.is_some())
+      .fold(weight.clone(), |bound, (k, _)| bound + self.jobs[self.order[k]].weight());
+    if bound <= self.best { return; }
+
+    for (k, place) in places.iter().enumerate() {
+      let Some((start, end)) = place else { continue };
+
+      // a job that fits, whole, before this one starts is better run first: doing so delays nothing
+      let dominated = places.iter().enumerate().any(|(j, other)| other.as_ref().is_some_and(|(other_start, other_end)| {
+        j != k && other_end <= start && (other_start < start || j < k)
+      }));
+      if dominated { continue; }
+
+      self.used[k] = true;
+      self.plan.push((k, start.clone()));
+      self.branch(Some(end.clone()), weight.clone() + self.jobs[self.order[k]].weight());
+      self.plan.pop();
+      self.used[k] = false;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{sequencing::{edd_schedule, johnson_schedule, max_weight_in_windows, max_weight_unit_jobs, min_late, min_weighted_late, wspt_schedule}, testing::{brute_force_best, subsets, Lcg}};
+
+  /// Whether the jobs in `set` are all on time, run earliest due first.
+  fn on_time(jobs: &[((u32, u32), u32)], set: &[usize]) -> bool {
+    let mut set = set.to_vec();
+    set.sort_by_key(|&i| jobs[i].0.1);
+    set.iter().scan(0, |completion, &i| { *completion += jobs[i].0.0; Some(*completion <= jobs[i].0.1) }).all(|ok| ok)
+  }
+
+  #[test]
+  fn matches_brute_force() {
+    let mut rng = Lcg::new(17);
+
+    for _ in 0..300 {
+      let n = rng.next(10) as usize;
+      let jobs: Vec<((u32, u32), u32)> = (0..n).map(|_| ((rng.next(5), rng.next(16)), rng.next(10))).collect();
+      let unweighted: Vec<(u32, u32)> = jobs.iter().map(|job| job.0).collect();
+
+      let most = subsets(n).filter(|set| on_time(&jobs, set)).map(|set| set.len()).max().unwrap();
+      let heaviest: u32 = brute_force_best(&jobs, |set| on_time(&jobs, set));
+
+      let count = min_late(&unweighted);
+      assert_eq!(count.len(), most, "{jobs:?}");
+      assert!(on_time(&jobs, &count));
+      assert!(count.windows(2).all(|pair| jobs[pair[0]].0.1 <= jobs[pair[1]].0.1));
+
+      let weighted = min_weighted_late(&jobs);
+      assert_eq!(weighted.iter().map(|&i| jobs[i].1).sum::<u32>(), heaviest, "{jobs:?}");
+      assert!(on_time(&jobs, &weighted));
+      assert!(weighted.windows(2).all(|pair| jobs[pair[0]].0.1 <= jobs[pair[1]].0.1));
+    }
+  }
+
+  #[test]
+  fn edd_minimizes_maximum_lateness() {
+    let mut rng = Lcg::new(3);
+
+    /// The least maximum lateness of any order of the jobs not yet `run`, after `completion`.
+    fn best(jobs: &[(i32, i32)], run: u32, completion: i32) -> i32 {
+      (0..jobs.len())
+        .filter(|i| run & (1 << i) == 0)
+        .map(|i| (completion + jobs[i].0 - jobs[i].1).max(best(jobs, run | (1 << i), completion + jobs[i].0)))
+        .min()
+        .unwrap_or(i32::MIN)
+    }
+
+    for _ in 0..200 {
+      let n = 1 + rng.next(6) as usize;
+      let jobs: Vec<(i32, i32)> = (0..n).map(|_| (rng.next(6) as i32, rng.next(20) as i32 - 4)).collect();
+
+      let (order, lateness) = edd_schedule(&jobs);
+      assert_eq!(lateness, Some(best(&jobs, 0, 0)), "{jobs:?}");
+      let mut sorted = order.clone();
+      sorted.sort_unstable();
+      assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+    }
+    assert_eq!(edd_schedule::<i32, i32, (i32, i32)>(&[]), (vec![], None));
+  }
+
+  #[test]
+  fn wspt_minimizes_weighted_completion() {
+    let mut rng = Lcg::new(11);
+
+    /// The least weighted completion time of any order of the jobs not yet `run`, after `completion`.
+    fn best(jobs: &[((u32, u32), u32)], run: u32, completion: u32) -> u32 {
+      (0..jobs.len())
+        .filter(|i| run & (1 << i) == 0)
+        .map(|i| (completion + jobs[i].0.0) * jobs[i].1 + best(jobs, run | (1 << i), completion + jobs[i].0.0))
+        .min()
+        .unwrap_or(0)
+    }
+
+    for _ in 0..200 {
+      let n = rng.next(7) as usize;
+      let jobs: Vec<((u32, u32), u32)> = (0..n).map(|_| ((rng.next(6), 0), rng.next(5))).collect();
+
+      let (order, cost) = wspt_schedule(&jobs);
+      assert_eq!(cost, best(&jobs, 0, 0), "{jobs:?}");
+      let mut completion = 0;
+      assert_eq!(order.iter().map(|&i| { completion += jobs[i].0.0; completion * jobs[i].1 }).sum::<u32>(), cost);
+      let mut sorted = order.clone();
+      sorted.sort_unstable();
+      assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+    }
+  }
+
+  #[test]
+  fn johnson_minimizes_makespan() {
+    let mut rng = Lcg::new(23);
+
+    /// The least makespan of any order of the jobs not yet `run`, once the machines are free at `first` and `second`.
+    fn best(jobs: &[(u32, u32)], run: u32, first: u32, second: u32) -> u32 {
+      (0..jobs.len())
+        .filter(|i| run & (1 << i) == 0)
+        .map(|i| {
+          let done = first + jobs[i].0;
+          best(jobs, run | (1 << i), done, done.max(second) + jobs[i].1)
+        })
+        .min()
+        .unwrap_or(second)
+    }
+
+    for _ in 0..200 {
+      let n = rng.next(7) as usize;
+      let jobs: Vec<(u32, u32)> = (0..n).map(|_| (rng.next(8), rng.next(8))).collect();
+
+      let shop = johnson_schedule(&jobs);
+      assert_eq!(shop.makespan, best(&jobs, 0, 0, 0), "{jobs:?}");
+      assert_eq!(shop.second.last().map_or(0, |run| run.end), shop.makespan);
+      for (k, &i) in shop.order.iter().enumerate() {
+        assert_eq!(shop.first[k].end - shop.first[k].start, jobs[i].0);
+        assert_eq!(shop.second[k].end - shop.second[k].start, jobs[i].1);
+        assert!(shop.first[k].end <= shop.second[k].start);
+        assert!(k == 0 || shop.first[k - 1].end <= shop.first[k].start && shop.second[k - 1].end <= shop.second[k].start);
+      }
+      let mut sorted = shop.order.clone();
+      sorted.sort_unstable();
+      assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+    }
+  }
+
+  #[test]
+  fn windows_match_exhaustive_search() {
+    let mut rng = Lcg::new(29);
+
+    type Job = ((u32, u32, u32), u32);
+
+    /// The most weight of any sequence of the jobs not yet `run`, each run as early as it can once the machine is `free`.
+    fn best(jobs: &[Job], run: u32, free: u32) -> u32 {
+      (0..jobs.len())
+        .filter(|i| run & (1 << i) == 0)
+        .filter_map(|i| {
+          let ((release, processing, due), weight) = jobs[i];
+          let end = free.max(release) + processing;
+          (end <= due).then(|| weight + best(jobs, run | (1 << i), end))
+        })
+        .max()
+        .unwrap_or(0)
+    }
+
+    for _ in 0..300 {
+      let n = rng.next(8) as usize;
+      let jobs: Vec<Job> = (0..n)
+        .map(|_| { let release = rng.next(12); ((release, rng.next(5), release + rng.next(9)), rng.next(6)) })
+        .collect();
+
+      let plan = max_weight_in_windows(&jobs);
+      assert_eq!(plan.iter().map(|&(i, _)| jobs[i].1).sum::<u32>(), best(&jobs, 0, 0), "{jobs:?}");
+
+      let mut free = 0;
+      for &(i, start) in &plan {
+        let ((release, processing, due), _) = jobs[i];
+        assert!(start >= free && start >= release && start + processing <= due, "{jobs:?} {plan:?}");
+        free = start + processing;
+      }
+    }
+  }
+
+  #[test]
+  fn unit_jobs_match_the_general_solver() {
+    let mut rng = Lcg::new(53);
+
+    for _ in 0..300 {
+      let n = rng.next(30) as usize;
+      let jobs: Vec<((u32, u32), u32)> = (0..n).map(|_| ((1, rng.next(12)), rng.next(40))).collect();
+
+      let chosen = max_weight_unit_jobs(&jobs);
+      let expected = min_weighted_late(&jobs).iter().map(|&i| jobs[i].1).sum::<u32>();
+      assert_eq!(chosen.iter().map(|&i| jobs[i].1).sum::<u32>(), expected, "{jobs:?}");
+      assert!(on_time(&jobs, &chosen));
+      assert!(chosen.windows(2).all(|pair| jobs[pair[0]].0.1 <= jobs[pair[1]].0.1));
+    }
+  }
+}
@@ -0,0 +1,107 @@
+use std::ops::Add;
+use crate::traits;
+
+/// Solves the problem when the setup time needed between two consecutive intervals depends on the pair.
+/// `O(n²)` in interval number.
+///
+/// Interval `j` may directly follow interval `i` if `i` ends by the time `j` starts, leaving room for
+/// `setup(i, j)`: `end(i) + setup(i, j) <= start(j)`. Only *consecutive* selected intervals are checked against each other,
+/// as with travel times between sites (a setup time matrix, indexed by interval, works as well as any callback).
+///
+/// `i` and `j` are indices into `intervals`, which can be in any order. Returns the indices of the optimal set,
+/// in descending end order.
+///
+/// When setup times only depend on the earlier interval (or are constant), [`MinGap`](crate::MinGap) and the
+/// `O(n log n)` solvers are the better fit.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // three jobs, at two sites
+/// let jobs: Vec<WeightedInterval<u8, u32>> = vec![
+///   (0, 10, 3).into(),  // at site 0
+///   (11, 20, 3).into(), // at site 1
+///   (12, 20, 2).into()  // at site 0
+/// ];
+/// let site = [0, 1, 0];
+///
+/// // moving between sites takes 5
+/// let travel = |i: usize, j: usize| if site[i] == site[j] { 0 } else { 5 };
+/// assert_eq!(unsorted_with_setup(&jobs, travel), vec![2, 0]);
+/// ```
+#[must_use]
+pub fn unsorted_with_setup<Weight, Time, Setup, Interval>(
+  intervals: &[Interval],
+  setup:     impl Fn(usize, usize) -> Setup
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone + Add<Setup, Output = Time>,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  // visit the intervals in order of end time
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  // `best[j]` is the weight of the heaviest chain of compatible intervals finishing with the `j`th (in end order),
+  // and `previous[j]` the interval before it in that chain
+  let mut best: Vec<Weight> = Vec::with_capacity(order.len());
+  let mut previous: Vec<Option<usize>> = Vec::with_capacity(order.len());
+
+  phase!("memoize", order.len(), for (j, &oj) in order.iter().enumerate() {
+    let start = intervals[oj].start();
+
+    // only intervals ending by `start` can come before, the rest depends on the setup time
+    let mut chosen: Option<usize> = None;
+    for i in 0..ends[..j].partition_point(|e| *e <= start) {
+      if ends[i].clone() + setup(order[i], oj) > start { continue; }
+      if chosen.is_none_or(|c| best[i] > best[c]) { chosen = Some(i); }
+    }
+
+    best.push(match chosen {
+      Some(i) => intervals[oj].weight() + best[i].clone(),
+      None    => intervals[oj].weight()
+    });
+    previous.push(chosen);
+  });
+
+  let mut optimal_solution = vec![];
+  phase!("reconstruct", order.len(), {
+    let mut j = (0..best.len()).reduce(|a, b| if best[b] > best[a] { b } else { a });
+    while let Some(k) = j {
+      optimal_solution.push(order[k]);
+      j = previous[k];
+    }
+  });
+
+  optimal_solution
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{unsorted, unsorted_with_setup, WeightedInterval};
+
+  #[test]
+  fn without_setup_matches_unsorted() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (0, 6, 3).into(), (1, 4, 5).into(), (3, 5, 5).into(), (3, 8, 8).into(), (4, 7, 3).into(),
+      (5, 9, 7).into(), (6, 10, 3).into(), (8, 11, 4).into(), (0, 1, 2).into()
+    ];
+
+    let total = |indices: Vec<usize>| indices.into_iter().map(|i| intervals[i].weight).sum::<u8>();
+    assert_eq!(
+      total(unsorted_with_setup(&intervals, |_, _| 0)),
+      unsorted(&intervals).iter().map(|i| i.weight).sum::<u8>()
+    );
+  }
+
+  #[test]
+  fn setup_depends_on_both_intervals() {
+    // a matrix of changeover times, asymmetric: switching from 0 to 1 is quick, back is slow
+    let setup = [[0, 1, 9], [9, 0, 1], [1, 9, 0]];
+    let intervals: Vec<WeightedInterval<u8, u32>> = vec![(0, 2, 1).into(), (3, 5, 1).into(), (6, 8, 1).into()];
+
+    assert_eq!(unsorted_with_setup(&intervals, |i, j| setup[i][j]), vec![2, 1, 0]);
+    assert_eq!(unsorted_with_setup(&intervals, |i, j| setup[j][i]).len(), 2);
+    assert!(unsorted_with_setup::<u8, u32, u32, WeightedInterval<u8, u32>>(&[], |_, _| 0).is_empty());
+  }
+}
@@ -0,0 +1,282 @@
+use std::{ops::Add, sync::Arc};
+use crate::traits;
+
+/// A solved problem to branch "what-if" explorations from, forcing intervals into the solution one at a time.
+///
+/// The solver state (the intervals sorted by end, their predecessors, and the memoizations) is shared behind an `Arc`, so
+/// snapshots clone in `O(f)` for the `f` intervals they force, whatever the size of the problem. Building the first
+/// snapshot is `O(n log n)` in interval number.
+///
+/// The memoizations hold both the optimum of the intervals ending by each time and the optimum of those starting from
+/// each time, so the optimum with a single interval forced into it is found in `O(log n)`. With more, the stretches
+/// between forced intervals are solved when asked for, in time linear in the number of intervals ending within them.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let base = Snapshot::new(vec![
+///   WeightedInterval::new(0u8, 4u8, 5u32),
+///   WeightedInterval::new(3, 6, 4),
+///   WeightedInterval::new(4, 8, 3),
+///   WeightedInterval::new(7, 9, 4)
+/// ]);
+/// assert_eq!(base.optimal_weight(), Some(9));
+///
+/// // what if the interval from 3 to 6 had to be in?
+/// let what_if = base.including(1).unwrap();
+/// assert_eq!(what_if.optimal_weight(), Some(8));
+/// assert_eq!(what_if.solution(), vec![(7, 9, 4).into(), (3, 6, 4).into()]);
+///
+/// // the interval from 0 to 4 can't join it
+/// assert!(what_if.including(0).is_none());
+/// assert_eq!(base.optimal_weight(), Some(9));
+/// ```
+#[derive(Debug)]
+pub struct Snapshot<Weight, Time, Interval> {
+  base:   Arc<Base<Weight, Time, Interval>>,
+  /// The positions of the intervals forced into the solution, in chronological order.
+  forced: Vec<usize>
+}
+
+#[derive(Debug)]
+struct Base<Weight, Time, Interval> {
+  /// The intervals, sorted by end (their positions are those the snapshot takes).
+  intervals:    Vec<Interval>,
+  ends:         Vec<Time>,
+  predecessors: Vec<Option<usize>>,
+  /// The optimal weight of the intervals up to each position.
+  prefix:       Vec<Weight>,
+  /// The starts, ascending.
+  starts:       Vec<Time>,
+  /// The optimal weight of the intervals from each rank (in start order) on.
+  suffix:       Vec<Weight>
+}
+
+impl<Weight, Time, Interval> Clone for Snapshot<Weight, Time, Interval> {
+  fn clone(&self) -> Self {
+    Self { base: Arc::clone(&self.base), forced: self.forced.clone() }
+  }
+}
+
+impl<Weight, Time, Interval> Snapshot<Weight, Time, Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  /// Solves `intervals`, in any order, forcing nothing. `O(n log n)`.
+  pub fn new(mut intervals: Vec<Interval>) -> Self {
+    // by start among equal ends, so that zero-length intervals follow those ending where they are
+    phase!("sort", intervals.len(), intervals.sort_by_key(|i| (i.end(), i.start())));
+
+    let ends: Vec<Time> = intervals.iter().map(|i| i.end()).collect();
+    let predecessors: Vec<Option<usize>> = intervals.iter()
+      .enumerate()
+      .map(|(k, i)| ends[..k].partition_point(|e| *e <= i.start()).checked_sub(1))
+      .collect();
+
+    let mut prefix: Vec<Weight> = Vec::with_capacity(intervals.len());
+    phase!("memoize", intervals.len(), for (k, interval) in intervals.iter().enumerate() {
+      let included = match predecessors[k] {
+        Some(p) => interval.weight() + prefix[p].clone(),
+        None    => interval.weight()
+      };
+      let best = match prefix.last() {
+        Some(excluded) => included.max(excluded.clone()),
+        None           => included
+      };
+      prefix.push(best);
+    });
+
+    // the mirror image: in start order, from the last interval back
+    let mut by_start: Vec<usize> = (0..intervals.len()).collect();
+    phase!("sort", by_start.len(), by_start.sort_by_key(|&k| (intervals[k].start(), intervals[k].end())));
+    let starts: Vec<Time> = by_start.iter().map(|&k| intervals[k].start()).collect();
+
+    let mut suffix: Vec<Weight> = Vec::with_capacity(intervals.len());
+    phase!("memoize", intervals.len(), for (rank, &k) in by_start.iter().enumerate().rev() {
+      let after = rank + 1 + starts[rank + 1..].partition_point(|s| *s < intervals[k].end());
+      let included = match (after < intervals.len()).then(|| suffix[intervals.len() - 1 - after].clone()) {
+        Some(weight) => intervals[k].weight() + weight,
+        None         => intervals[k].weight()
+      };
+      let best = match suffix.last() {
+        Some(excluded) => included.max(excluded.clone()),
+        None           => included
+      };
+      suffix.push(best);
+    });
+    suffix.reverse();
+
+    Self { base: Arc::new(Base { intervals, ends, predecessors, prefix, starts, suffix }), forced: vec![] }
+  }
+
+  /// A snapshot also forcing the interval at `position` (among [`intervals`](Self::intervals)) into the solution, or
+  /// `None` if it overlaps an interval already forced. `O(f)`.
+  ///
+  /// # Panics
+  /// If `position` is out of bounds.
+  #[must_use]
+  pub fn including(&self, position: usize) -> Option<Self> {
+    let intervals = &self.base.intervals;
+    let interval = &intervals[position];
+    if self.forced.contains(&position) { return Some(self.clone()); }
+
+    let overlapping = self.forced.iter().any(|&f| intervals[f].end() > interval.start() && interval.end() > intervals[f].start());
+    if overlapping { return None; }
+
+    let mut forced = self.forced.clone();
+    // a zero-length interval comes after one ending where it is
+    let at = forced.partition_point(|&f| (intervals[f].end(), intervals[f].start(), f) < (interval.end(), interval.start(), position));
+    forced.insert(at, position);
+    Some(Self { base: Arc::clone(&self.base), forced })
+  }
+
+  /// The weight of the optimal selection containing the forced intervals (`None` if there are no intervals).
+  /// `O(f log n)`, plus the solves of the stretches between forced intervals.
+  #[must_use]
+  pub fn optimal_weight(&self) -> Option<Weight> {
+    let base = &self.base;
+    let zero_length = |f: usize| base.intervals[f].start() == base.intervals[f].end();
+
+    let mut total: Option<Weight> = None;
+    let mut add = |weight: Option<Weight>| if let Some(weight) = weight {
+      total = Some(match total.take() { Some(total) => total + weight, None => weight });
+    };
+
+    // the stretches before the first and after the last forced intervals are in the memoizations, unless those are of
+    // zero length (as intervals of zero length where they are would fit the stretches on both of their sides)
+    let mut after = None;
+    for &f in &self.forced {
+      let to = base.intervals[f].start();
+      add(match after {
+        None if !zero_length(f) => base.ends.partition_point(|e| *e <= to).checked_sub(1).map(|k| base.prefix[k].clone()),
+        _                       => self.between(after, Some(f), |_| ())
+      });
+      add(Some(base.intervals[f].weight()));
+      after = Some(f);
+    }
+
+    add(match after {
+      None                       => base.prefix.last().cloned(),
+      Some(f) if !zero_length(f) => {
+        let from = base.intervals[f].end();
+        base.suffix.get(base.starts.partition_point(|s| *s < from)).cloned()
+      }
+      Some(_)                    => self.between(after, None, |_| ())
+    });
+
+    total
+  }
+
+  /// The optimal selection containing the forced intervals, in descending order of end (as from
+  /// [`sorted`](crate::sorted)). `O(n)`.
+  #[must_use]
+  pub fn solution(&self) -> Vec<Interval>
+    where Interval: Clone
+  {
+    let mut selected = vec![];
+
+    let mut after = None;
+    for &f in &self.forced {
+      self.between(after, Some(f), |k| selected.push(k));
+      selected.push(f);
+      after = Some(f);
+    }
+    self.between(after, None, |k| selected.push(k));
+
+    selected.sort_by(|a, b| b.cmp(a));
+    selected.into_iter().map(|k| self.base.intervals[k].clone()).collect()
+  }
+
+  /// The intervals, sorted by end (positions in this slice are those [`including`](Self::including) takes).
+  pub fn intervals(&self) -> &[Interval] { &self.base.intervals }
+
+  /// The positions of the intervals forced into the solution, in chronological order.
+  pub fn forced(&self) -> &[usize] { &self.forced }
+
+  /// Solves the stretch between the forced intervals at `after` and `before` (unbounded when `None`), calling `select`
+  /// with the positions of the optimal selection. Returns its weight. Linear in the intervals ending within it.
+  ///
+  /// Intervals of zero length where a forced interval of zero length is go to the stretch before it.
+  fn between(&self, after: Option<usize>, before: Option<usize>, mut select: impl FnMut(usize)) -> Option<Weight> {
+    let base = &self.base;
+    let from = after.map(|f| (base.intervals[f].end(), base.intervals[f].start() == base.intervals[f].end()));
+    let to = before.map(|f| base.intervals[f].start());
+
+    // anything starting at or after `from` ends at or after it too
+    let first = from.as_ref().map_or(0, |(from, _)| base.ends.partition_point(|e| e < from));
+    let last = to.as_ref().map_or(base.ends.len(), |to| base.ends.partition_point(|e| e <= to));
+    if first >= last { return None; }
+
+    let fits = |k: usize| {
+      let (start, end) = (base.intervals[k].start(), base.intervals[k].end());
+      let inside = from.as_ref().is_none_or(|(from, zero_length)| start >= *from && !(*zero_length && end == *from));
+      inside && !self.forced.contains(&k)
+    };
+    let included = |k: usize, memoization: &[Option<Weight>]| {
+      let before = base.predecessors[k].and_then(|p| p.checked_sub(first)).and_then(|p| memoization[p].clone());
+      match before {
+        Some(weight) => base.intervals[k].weight() + weight,
+        None         => base.intervals[k].weight()
+      }
+    };
+
+    let mut memoization: Vec<Option<Weight>> = Vec::with_capacity(last - first);
+    for k in first..last {
+      let excluded = memoization.last().cloned().flatten();
+      memoization.push(match (fits(k).then(|| included(k, &memoization)), excluded) {
+        (Some(included), Some(excluded)) => Some(included.max(excluded)),
+        (included, excluded)             => included.or(excluded)
+      });
+    }
+
+    let mut j = Some(last - 1 - first);
+    while let Some(i) = j {
+      let k = i + first;
+      if fits(k) && i.checked_sub(1).and_then(|p| memoization[p].clone()).is_none_or(|excluded| included(k, &memoization) > excluded) {
+        select(k);
+        j = base.predecessors[k].and_then(|p| p.checked_sub(first));
+      }
+      else { j = i.checked_sub(1); }
+    }
+
+    memoization.last().cloned().flatten()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{unsorted, Snapshot, WeightedInterval};
+
+  #[test]
+  fn branches_match_solving_what_is_left() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..30u32).map(|i| (i * 7 % 23, i * 7 % 23 + 1 + i % 4, 1 + i % 5).into()).collect();
+    let base = Snapshot::new(intervals);
+    let sorted = base.intervals().to_vec();
+
+    let total = |solution: &[WeightedInterval<u32, u32>]| solution.iter().map(|i| i.weight).reduce(|a, b| a + b);
+    assert_eq!(base.optimal_weight(), total(&unsorted(&sorted)));
+
+    // force growing sets of intervals in, comparing with solving what doesn't overlap them
+    for first in 0..sorted.len() {
+      let mut branch = base.including(first).unwrap();
+      for position in (first..sorted.len()).step_by(3) {
+        let Some(next) = branch.including(position) else { continue };
+        branch = next;
+
+        let forced: Vec<WeightedInterval<u32, u32>> = branch.forced().iter().map(|&f| sorted[f]).collect();
+        let rest: Vec<WeightedInterval<u32, u32>> = sorted.iter()
+          .enumerate()
+          .filter(|(k, i)| !branch.forced().contains(k) && forced.iter().all(|f| f.end <= i.start || i.end <= f.start))
+          .map(|(_, i)| *i)
+          .collect();
+        let expected = total(&[forced.clone(), unsorted(&rest)].concat());
+
+        let solution = branch.solution();
+        assert_eq!(branch.optimal_weight(), expected, "{:?}", branch.forced());
+        assert_eq!(total(&solution), expected);
+        assert!(forced.iter().all(|f| solution.contains(f)));
+        assert!(solution.windows(2).all(|pair| pair[1].end <= pair[0].start));
+      }
+    }
+  }
+}
@@ -1,5 +1,5 @@
-use std::ops::Add;
-use crate::{traits, util::*};
+use std::ops::{Add, Range};
+use crate::{compatibility::{Compatibility, HalfOpen}, traits, util::*};
 
 /// Faster solver, only slightly more difficult to use correctly. `O(n log n)` in interval number.
 /// 
@@ -20,9 +20,12 @@ use crate::{traits, util::*};
 /// # Example Usage
 /// 
 /// ```rust
+/// # use w_inter::*;
+/// # let problems = vec![vec![WeightedInterval::new(0u8, 1u8, 2u8)]];
+/// # fn sort(i: &mut [WeightedInterval<u8, u8>]) { i.sort_by_key(|i| i.end) }
 /// // our goal is to allocate once and reuse the same buffers
 /// // measure (or apply a guess) to avoid having to resize the vector.
-/// let max_interval_count = problems.iter().map(|i| i.len()).max();
+/// let max_interval_count = problems.iter().map(|i| i.len()).max().unwrap_or(0);
 /// 
 /// // we can say with certainty that the memo buffer 
 /// // will never need to be larger than the largest input size.
@@ -32,7 +35,7 @@ use crate::{traits, util::*};
 /// // but it can't be larger than the largest input size.
 /// let mut soln = Vec::with_capacity(max_interval_count);
 /// 
-/// for intervals in problems {
+/// for mut intervals in problems {
 ///   // perhaps we know our intervals to be *almost* sorted, 
 ///   // so we choose to use an algorithm tuned for this case.
 ///   sort(&mut intervals);
@@ -59,14 +62,142 @@ pub fn sorted<Weight, Time, Interval, InputContainer>(
         Time: Ord,
         Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
         InputContainer: AsRef<[Interval]>
+{
+  sorted_with(intervals, memoization, solution, HalfOpen)
+}
+
+/// Like `sorted`, but `compatibility` decides which intervals may follow one another
+/// (e.g. [`Closed`](crate::Closed) makes touching intervals conflict). The rules of `sorted` apply.
+/// 
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 1).into(), (2, 4, 1).into(), (1, 5, 3).into()];
+/// 
+/// let mut memo = vec![0; intervals.len()];
+/// let mut solution = vec![];
+/// sorted_with(&intervals, &mut memo, &mut solution, Closed);
+/// 
+/// assert_eq!(solution, vec![(1, 5, 3).into()]);
+/// ```
+pub fn sorted_with<Weight, Time, Interval, InputContainer>(
+  intervals:     InputContainer,
+  memoization:   &mut [Weight],
+  solution:      &mut Vec<Interval>,
+  compatibility: impl Compatibility<Time>
+) where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
 {
   let intervals = intervals.as_ref();
   
-  if let Some(i) = intervals.get(0) { memoization[0] = i.weight(); }
+  if let Some(i) = intervals.first() { memoization[0] = i.weight(); }
   else { return; } // empty intervals
 
   // actually find the optimal solution
-  internal(intervals, memoization, solution);
+  internal(intervals, memoization, solution, &compatibility);
+}
+
+/// Like `sorted`, but each interval weighs `weight(interval)`, evaluated as the solver needs it rather than stored, so a
+/// weight may depend on where the interval lies in time (e.g. a value decaying with how late it ends). The intervals
+/// don't need to carry weights, and any they do carry are ignored. The rules of `sorted` apply.
+///
+/// `weight` is called about twice per interval, so it should be cheap (and must return the same weight every time).
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<(u32, u32)> = vec![(1, 3), (0, 4), (3, 9)];
+///
+/// // each job is worth 10, less 1 for each unit of time it finishes after 3
+/// let mut memo = vec![0; jobs.len()];
+/// let mut solution = vec![];
+/// sorted_weighted_by(&jobs, &mut memo, &mut solution, |&(_, end): &(u32, u32)| 10 - end.saturating_sub(3));
+///
+/// assert_eq!(solution, vec![(3, 9), (1, 3)]);
+/// assert_eq!(memo[jobs.len() - 1], 14);
+/// ```
+pub fn sorted_weighted_by<Weight, Time, Interval, InputContainer>(
+  intervals:   InputContainer,
+  memoization: &mut [Weight],
+  solution:    &mut Vec<Interval>,
+  weight:      impl Fn(&Interval) -> Weight
+) where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  if let Some(i) = intervals.first() { memoization[0] = weight(i); }
+  else { return; } // empty intervals
+
+  let weight      = |i: usize| weight(&intervals[i]);
+  let predecessor = |i: usize| final_compatible(intervals, i, &HalfOpen);
+
+  phase!("memoize", intervals.len(), memoize(intervals.len(), memoization, weight, predecessor));
+  phase!("reconstruct", intervals.len(), reconstruct(intervals.len(), memoization, weight, predecessor, |i| {
+    solution.push(intervals[i].clone())
+  }));
+}
+
+/// Like `sorted`, but only considers the intervals lying entirely inside `window` (starting at or after its start, and
+/// ending at or before its end), without the caller filtering and re-sorting. `O(log n + m log m)` in interval number
+/// `n` and the number `m` of intervals ending within the window. The rules of `sorted` apply, except that:
+/// 
+/// - `memoization.len()` only needs to be at least `m`.
+/// 
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 3, 5).into(), (2, 4, 2).into(), (4, 6, 2).into(), (5, 9, 4).into()];
+/// 
+/// let mut memo = vec![0; intervals.len()];
+/// let mut solution = vec![];
+/// sorted_in_window(&intervals, 1..8, &mut memo, &mut solution);
+/// 
+/// assert_eq!(solution, vec![(4, 6, 2).into(), (2, 4, 2).into()]);
+/// ```
+pub fn sorted_in_window<Weight, Time, Interval, InputContainer>(
+  intervals:   InputContainer,
+  window:      Range<Time>,
+  memoization: &mut [Weight],
+  solution:    &mut Vec<Interval>
+) where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // the intervals ending within the window, of which those starting within it too are inside it
+  let low  = intervals.partition_point(|i| i.end() < window.start);
+  let high = intervals.partition_point(|i| i.end() <= window.end).max(low);
+  let candidates = &intervals[low..high];
+  let inside = |k: usize| candidates[k].start() >= window.start;
+
+  let Some(first) = (0..candidates.len()).find(|&k| inside(k)) else { return };
+
+  // memoization (indexed from `low`) is only defined from the first interval inside on, which is all predecessors need
+  let weight      = |k: usize| candidates[k].weight();
+  let predecessor = |k: usize| final_compatible(candidates, k, &HalfOpen).filter(|&j| j >= first);
+  let included    = |k: usize, memoization: &[Weight]| match predecessor(k) {
+    Some(j) => weight(k) + memoization[j].clone(),
+    None    => weight(k)
+  };
+
+  memoization[first] = weight(first);
+  phase!("memoize", candidates.len(), for k in first + 1..candidates.len() {
+    memoization[k] = if inside(k) { included(k, memoization).max(memoization[k - 1].clone()) } else { memoization[k - 1].clone() };
+  });
+
+  let mut j = candidates.len().checked_sub(1);
+  phase!("reconstruct", candidates.len(), while let Some(k) = j {
+    if !inside(k) { j = Some(k - 1); }
+    else if k == first || included(k, memoization) > memoization[k - 1] {
+      solution.push(candidates[k].clone());
+      j = predecessor(k);
+    }
+    else { j = Some(k - 1); }
+  });
 }
 
 /// - `memoization` must have a first element, and already be of length `intervals.len()` or more.
@@ -74,42 +205,181 @@ pub fn sorted<Weight, Time, Interval, InputContainer>(
 fn internal<Weight, Time, Interval>(
   intervals:        &[Interval],
   memoization:      &mut [Weight],
-  optimal_solution: &mut Vec<Interval>
+  optimal_solution: &mut Vec<Interval>,
+  compatibility:    &impl Compatibility<Time>
 ) where Weight: Ord + Add<Output = Weight> + Clone,
         Time: Ord,
         Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone
 {
-  // build the memoization array
-  for index in 1..intervals.len() {
+  let weight      = |i: usize| intervals[i].weight();
+  let predecessor = |i: usize| final_compatible(intervals, i, compatibility);
+
+  phase!("memoize", intervals.len(), memoize(intervals.len(), memoization, weight, predecessor));
+  phase!("reconstruct", intervals.len(), reconstruct(intervals.len(), memoization, weight, predecessor, |i| {
+    optimal_solution.push(intervals[i].clone())
+  }));
+}
+
+/// Fills `memoization[i]` with the optimal weight achievable using only the first `i + 1` of `count` intervals.
+/// 
+/// - `weight(i)` is the weight of the `i`th interval.
+/// - `predecessor(i)` is the index of the last interval (in end order) compatible with the `i`th interval.
+/// - `memoization[0]` must already hold the weight of the first interval.
+pub(crate) fn memoize<Weight>(
+  count:       usize,
+  memoization: &mut [Weight],
+  weight:      impl Fn(usize) -> Weight,
+  predecessor: impl Fn(usize) -> Option<usize>
+) where Weight: Ord + Add<Output = Weight> + Clone
+{
+  for index in 1..count {
     // find the last index compatible with the current interval
     let included_value = {
-      let last = final_compatible(&intervals[..], index);
+      let last = predecessor(index);
 
-      if let Some(k) = last { intervals[index].weight() + memoization[k].clone() }
-      else { intervals[index].weight() }
+      if let Some(k) = last { weight(index) + memoization[k].clone() }
+      else { weight(index) }
     };
     let excluded_value = memoization[index - 1].clone();
     memoization[index] = included_value.max(excluded_value);
   }
+}
 
-  // iteratively find the optimal solution
-  let mut j = if intervals.len() != 0 { Some(intervals.len() - 1) } else { None };
+/// Walks a filled memoization array backwards, calling `select` with the index of each member of the optimal set
+/// (in descending end order). `weight` and `predecessor` are as in `memoize`.
+pub(crate) fn reconstruct<Weight>(
+  count:       usize,
+  memoization: &[Weight],
+  weight:      impl Fn(usize) -> Weight,
+  predecessor: impl Fn(usize) -> Option<usize>,
+  mut select:  impl FnMut(usize)
+) where Weight: Ord + Add<Output = Weight> + Clone
+{
+  let mut j = count.checked_sub(1);
   while let Some(i) = j {
-    let last = final_compatible(&intervals[..], i);
+    let last = predecessor(i);
 
     let z = {
-      if let Some(k) = last { intervals[i].weight() + memoization[k].clone() }
-      else { intervals[i].weight() }
+      if let Some(k) = last { weight(i) + memoization[k].clone() }
+      else { weight(i) }
     };
     
     if i == 0 || z > memoization[i - 1] {
-      optimal_solution.push(intervals[i].clone());
+      select(i);
       j = last;
     }
     else { j = Some(i - 1); }
   }
 }
 
+/// Runs `memoize` and `reconstruct` over `count` intervals (in end order), with freshly allocated memoization,
+/// appending the indices of the optimal set to `selected` (in descending order).
+pub(crate) fn solve_by<Weight>(
+  count:       usize,
+  weight:      impl Fn(usize) -> Weight,
+  predecessor: impl Fn(usize) -> Option<usize>,
+  selected:    &mut Vec<usize>
+) where Weight: Ord + Add<Output = Weight> + Clone
+{
+  if count == 0 { return; }
+
+  let mut memoization = vec![weight(0); count];
+
+  phase!("memoize", count, memoize(count, &mut memoization, &weight, &predecessor));
+  phase!("reconstruct", count, reconstruct(count, &memoization, &weight, &predecessor, |k| selected.push(k)));
+}
+
+/// Like `sorted`, but for problems stored column-wise (structure-of-arrays). `O(n log n)` in interval number.
+/// 
+/// Interval `i` is `starts[i]..ends[i]` with weight `weights[i]`. The rules of `sorted` apply, adapted to columns:
+/// 
+/// - **`ends` must be sorted ascending** (and the other columns permuted to match).
+///   - If this invariant is not held, no claims are made about the behavior of this function.
+/// - The three columns must have the same length (this is checked, and panics otherwise).
+/// - `memoization.len()` must be at least the interval count. There is no need to clear it between invocations.
+/// - `solution` will be appended to, with the *indices* of the optimal set (in descending order).
+/// 
+/// ```rust
+/// # use w_inter::sorted_soa;
+/// let starts  = [0u32, 1, 4];
+/// let ends    = [4u32, 5, 9];
+/// let weights = [2u16, 3, 3];
+/// 
+/// let mut memo = vec![0; starts.len()];
+/// let mut solution = vec![];
+/// sorted_soa(&starts, &ends, &weights, &mut memo, &mut solution);
+/// 
+/// assert_eq!(solution, vec![2, 0]);
+/// ```
+pub fn sorted_soa<Weight, Time>(
+  starts:      &[Time],
+  ends:        &[Time],
+  weights:     &[Weight],
+  memoization: &mut [Weight],
+  solution:    &mut Vec<usize>
+) where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord
+{
+  assert!(
+    starts.len() == ends.len() && ends.len() == weights.len(),
+    "columns must have equal lengths (starts: {}, ends: {}, weights: {})", starts.len(), ends.len(), weights.len()
+  );
+
+  if let Some(w) = weights.first() { memoization[0] = w.clone(); }
+  else { return; } // empty intervals
+
+  let weight      = |i: usize| weights[i].clone();
+  let predecessor = |i: usize| ends[..i].partition_point(|e| *e <= starts[i]).checked_sub(1);
+
+  phase!("memoize", weights.len(), memoize(weights.len(), memoization, weight, predecessor));
+  phase!("reconstruct", weights.len(), reconstruct(weights.len(), memoization, weight, predecessor, |i| solution.push(i)));
+}
+
+/// Like [`sorted_soa`], for primitive times (`u32`, `u64`, `i64`, ...): the predecessor searches, the hot loop once sorting
+/// is amortized, first compare the few dozen ends just before each interval with its start all at once, in vector
+/// registers, and only binary search further back if its predecessor isn't among them. `O(n log n)` in interval number.
+///
+/// The searches run several times faster when intervals are short compared to their spacing (so that predecessors are
+/// close by), and about as fast as those of [`sorted_soa`] otherwise.
+///
+/// The requirements on the columns are those of [`sorted_soa`], and so is the solution.
+///
+/// ```rust
+/// # use w_inter::sorted_soa_vectorized;
+/// let starts  = [0u64, 1, 4];
+/// let ends    = [4u64, 5, 9];
+/// let weights = [2u16, 3, 3];
+///
+/// let mut memo = vec![0; starts.len()];
+/// let mut solution = vec![];
+/// sorted_soa_vectorized(&starts, &ends, &weights, &mut memo, &mut solution);
+///
+/// assert_eq!(solution, vec![2, 0]);
+/// ```
+pub fn sorted_soa_vectorized<Weight, Time>(
+  starts:      &[Time],
+  ends:        &[Time],
+  weights:     &[Weight],
+  memoization: &mut [Weight],
+  solution:    &mut Vec<usize>
+) where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Copy
+{
+  assert!(
+    starts.len() == ends.len() && ends.len() == weights.len(),
+    "columns must have equal lengths (starts: {}, ends: {}, weights: {})", starts.len(), ends.len(), weights.len()
+  );
+
+  if let Some(w) = weights.first() { memoization[0] = w.clone(); }
+  else { return; } // empty intervals
+
+  let weight      = |i: usize| weights[i].clone();
+  let predecessor = |i: usize| count_at_most(&ends[..i], starts[i]).checked_sub(1);
+
+  phase!("memoize", weights.len(), memoize(weights.len(), memoization, weight, predecessor));
+  phase!("reconstruct", weights.len(), reconstruct(weights.len(), memoization, weight, predecessor, |i| solution.push(i)));
+}
+
 /// Marginally slower solver, impossible to misuse. `O(n log n)` in interval number.
 /// - Should be pretty fast for most input.
 /// - Overhead comes from sorting the input and allocating multiple times for each invocation of the solver.
@@ -122,18 +392,32 @@ pub fn unsorted<Weight, Time, Interval, InputContainer>(
         Time: Ord,
         Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
         InputContainer: AsRef<[Interval]> 
+{
+  unsorted_with(intervals, HalfOpen)
+}
+
+/// Like `unsorted`, but `compatibility` decides which intervals may follow one another
+/// (e.g. [`Closed`](crate::Closed) makes touching intervals conflict).
+#[must_use]
+pub fn unsorted_with<Weight, Time, Interval, InputContainer>(
+  intervals:     InputContainer,
+  compatibility: impl Compatibility<Time>
+) -> Vec<Interval> 
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]> 
 {
   // prepare an internal mutable clone, as the input is not known to be sorted
   let mut intervals = Vec::from(intervals.as_ref());
 
   // sort unstable by end time (unstable is *often* faster)
-  intervals.sort_unstable_by(|a, b| a.end().cmp(&b.end()));
+  phase!("sort", intervals.len(), intervals.sort_unstable_by_key(|i| i.end()));
 
   // prepare memoization array (at most 1 alloc)
-  let mut memoization = {
-    let mut m: Vec<Weight> = Vec::with_capacity(intervals.len());
-    if let Some(i) = intervals.get(0) { m.push(i.weight()); }
-    m
+  let mut memoization = match intervals.first() {
+    Some(i) => vec![i.weight(); intervals.len()],
+    None    => vec![]
   };
 
   // I have no guess as to the lenth of the optimal solution.
@@ -143,15 +427,101 @@ pub fn unsorted<Weight, Time, Interval, InputContainer>(
   internal(
     &intervals[..], 
     &mut memoization[..], 
-    &mut optimal_solution
+    &mut optimal_solution,
+    &compatibility
   );
 
   optimal_solution
 }
 
+/// Like `unsorted`, but each interval weighs `weight(interval)`, as for [`sorted_weighted_by`].
+///
+/// ```rust
+/// # use w_inter::*;
+/// let jobs: Vec<(u32, u32)> = vec![(3, 9), (0, 4), (1, 3)];
+///
+/// // each job is worth 10, less 1 for each unit of time it finishes after 3
+/// let optimal = unsorted_weighted_by(&jobs, |&(_, end): &(u32, u32)| 10 - end.saturating_sub(3));
+/// assert_eq!(optimal, vec![(3, 9), (1, 3)]);
+/// ```
+#[must_use]
+pub fn unsorted_weighted_by<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  weight:    impl Fn(&Interval) -> Weight
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let mut intervals = Vec::from(intervals.as_ref());
+  phase!("sort", intervals.len(), intervals.sort_unstable_by_key(|i| i.end()));
+
+  let mut memoization = match intervals.first() {
+    Some(i) => vec![weight(i); intervals.len()],
+    None    => vec![]
+  };
+
+  let mut optimal_solution = vec![];
+  sorted_weighted_by(&intervals, &mut memoization, &mut optimal_solution, weight);
+  optimal_solution
+}
+
+/// Like `unsorted`, but for problems stored column-wise (structure-of-arrays). `O(n log n)` in interval number.
+/// 
+/// Interval `i` is `starts[i]..ends[i]` with weight `weights[i]`, the columns can be in any order (but must have the
+/// same length, this is checked, and panics otherwise). Returns the indices of the optimal set, in descending end order.
+/// 
+/// The columns aren't copied, only a sorted permutation of the indices is allocated.
+/// 
+/// ```rust
+/// # use w_inter::unsorted_soa;
+/// let starts  = [4u32, 0, 1];
+/// let ends    = [9u32, 4, 5];
+/// let weights = [3u16, 2, 3];
+/// 
+/// assert_eq!(unsorted_soa(&starts, &ends, &weights), vec![0, 1]);
+/// ```
+#[must_use]
+pub fn unsorted_soa<Weight, Time>(
+  starts:  &[Time],
+  ends:    &[Time],
+  weights: &[Weight]
+) -> Vec<usize>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord
+{
+  assert!(
+    starts.len() == ends.len() && ends.len() == weights.len(),
+    "columns must have equal lengths (starts: {}, ends: {}, weights: {})", starts.len(), ends.len(), weights.len()
+  );
+
+  // visit the columns in order of end time
+  let mut order: Vec<usize> = (0..ends.len()).collect();
+  phase!("sort", order.len(), order.sort_unstable_by(|&a, &b| ends[a].cmp(&ends[b])));
+
+  let mut memoization = match order.first() {
+    Some(&i) => vec![weights[i].clone(); order.len()],
+    None     => return vec![]
+  };
+
+  let weight      = |i: usize| weights[order[i]].clone();
+  let predecessor = |i: usize| order[..i].partition_point(|&k| ends[k] <= starts[order[i]]).checked_sub(1);
+
+  let mut optimal_solution = vec![];
+  phase!("memoize", order.len(), memoize(order.len(), &mut memoization, weight, predecessor));
+  phase!("reconstruct", order.len(), reconstruct(order.len(), &memoization, weight, predecessor, |i| optimal_solution.push(order[i])));
+
+  optimal_solution
+}
+
 #[cfg(test)]
+// the tests pass arrays of intervals by reference, as callers do, though clippy would rather copy `Copy` arrays
+#[allow(clippy::needless_borrows_for_generic_args)]
 mod tests {
-  use crate::{WeightedInterval, unsorted};
+  use crate::{Closed, HalfOpen, MinGap, WeightedInterval, sorted_in_window, sorted_soa, sorted_soa_vectorized, unsorted, unsorted_soa, unsorted_with};
+  use crate::unsorted_weighted_by;
+  use crate::util::count_at_most;
 
   #[test]
   fn small_example() {
@@ -200,4 +570,139 @@ mod tests {
     assert_eq!(optimal_set[0].end,    128);
     assert_eq!(optimal_set[0].weight, 15);
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn by_value() {
+    let intervals = vec![
+      WeightedInterval { start: 0u8, end: 6u8, weight: 3u8 },
+      WeightedInterval { start: 1u8, end: 4u8, weight: 5u8 },
+      WeightedInterval { start: 5u8, end: 9u8, weight: 7u8 }
+    ];
+    let expected = vec![intervals[2], intervals[1]];
+
+    // the container may be handed over, rather than borrowed
+    assert_eq!(unsorted(intervals), expected);
+  }
+
+  #[test]
+  fn soa_matches_aos() {
+    let starts  = [0u8, 1, 3, 3, 4, 5, 6, 8];
+    let ends    = [6u8, 4, 5, 8, 7, 9, 10, 11];
+    let weights = [3u8, 5, 5, 8, 3, 7, 3, 4];
+
+    let intervals: Vec<WeightedInterval<u8, u8>> = (0..starts.len())
+      .map(|i| WeightedInterval::new(starts[i], ends[i], weights[i]))
+      .collect();
+
+    // sort the columns by end time together
+    let mut order: Vec<usize> = (0..starts.len()).collect();
+    order.sort_by_key(|&i| ends[i]);
+    let column = |c: &[u8]| order.iter().map(|&i| c[i]).collect::<Vec<_>>();
+    let (starts, ends, weights) = (column(&starts), column(&ends), column(&weights));
+
+    let mut memo = vec![0; starts.len()];
+    let mut solution = vec![];
+    sorted_soa(&starts, &ends, &weights, &mut memo, &mut solution);
+
+    let columnar: Vec<_> = solution.iter().map(|&i| WeightedInterval::new(starts[i], ends[i], weights[i])).collect();
+    assert_eq!(columnar, unsorted(&intervals));
+
+    // and without sorting beforehand
+    let solution: Vec<_> = unsorted_soa(&starts, &ends, &weights).iter()
+      .map(|&i| WeightedInterval::new(starts[i], ends[i], weights[i]))
+      .collect();
+    assert_eq!(solution, unsorted(&intervals));
+  }
+
+  #[test]
+  fn vectorized_soa_matches_soa() {
+    fn check<Time: Ord + Copy + From<u16>>() {
+      for n in [0usize, 1, 31, 32, 33, 64, 65, 500] {
+        let mut columns: Vec<(Time, Time, u32)> = (0..n as u16)
+          .map(|i| (Time::from(i * 37 % 211), Time::from(i * 37 % 211 + 1 + i % 9), 1 + u32::from(i) * 7 % 13))
+          .collect();
+        columns.sort_by_key(|c| c.1);
+        let starts: Vec<Time> = columns.iter().map(|c| c.0).collect();
+        let ends: Vec<Time> = columns.iter().map(|c| c.1).collect();
+        let weights: Vec<u32> = columns.iter().map(|c| c.2).collect();
+
+        let (mut memo, mut expected, mut solution) = (vec![0; n], vec![], vec![]);
+        sorted_soa(&starts, &ends, &weights, &mut memo, &mut expected);
+        sorted_soa_vectorized(&starts, &ends, &weights, &mut memo, &mut solution);
+        assert_eq!(solution, expected);
+
+        for key in 0..230u16 {
+          assert_eq!(count_at_most(&ends, Time::from(key)), ends.partition_point(|e| *e <= Time::from(key)));
+        }
+      }
+    }
+
+    check::<u32>();
+    check::<u64>();
+    check::<i64>();
+  }
+
+  #[test]
+  fn weights_computed_as_needed_match_stored_ones() {
+    let spans: Vec<(u32, u32)> = (0..60u32).map(|i| (i * 17 % 53, i * 17 % 53 + 1 + i % 7)).collect();
+    let value = |&(start, end): &(u32, u32)| 100 - end + (end - start) * 3;
+
+    let stored: Vec<WeightedInterval<u32, u32>> = spans.iter().map(|s| WeightedInterval::new(s.0, s.1, value(s))).collect();
+    let expected: Vec<(u32, u32)> = unsorted(&stored).iter().map(|i| (i.start, i.end)).collect();
+    assert_eq!(unsorted_weighted_by(&spans, value), expected);
+    assert!(unsorted_weighted_by(Vec::<(u32, u32)>::new(), value).is_empty());
+  }
+
+  #[test]
+  #[should_panic]
+  fn soa_rejects_ragged_columns() {
+    sorted_soa(&[0u8, 1], &[1u8, 2], &[1u8], &mut [0u8; 2], &mut vec![]);
+  }
+
+  #[test]
+  fn closed_intervals_conflict_when_touching() {
+    // a chain of touching intervals, each lighter than the two it straddles
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (0, 1, 2).into(), (1, 2, 2).into(), (2, 3, 2).into(), (3, 4, 2).into(),
+      (0, 2, 3).into(), (2, 4, 3).into()
+    ];
+
+    let total = |s: &[WeightedInterval<u8, u8>]| s.iter().map(|i| i.weight).sum::<u8>();
+    assert_eq!(total(&unsorted_with(&intervals, HalfOpen)), 8);
+    assert_eq!(unsorted_with(&intervals, HalfOpen), unsorted(&intervals));
+
+    // only every other unit interval survives, or a single long one next to a unit one
+    let closed = unsorted_with(&intervals, Closed);
+    assert_eq!(total(&closed), 5);
+    assert!(closed.windows(2).all(|w| w[1].end < w[0].start));
+  }
+
+  #[test]
+  fn gaps_between_selected_intervals() {
+    let intervals: Vec<WeightedInterval<u8, u32>> = vec![(0, 10, 4).into(), (12, 20, 5).into(), (15, 30, 4).into()];
+
+    assert_eq!(unsorted_with(&intervals, MinGap(0)), unsorted_with(&intervals, HalfOpen));
+    assert_eq!(unsorted_with(&intervals, MinGap(2)), vec![intervals[1], intervals[0]]);
+    assert_eq!(unsorted_with(&intervals, MinGap(3)), vec![intervals[2], intervals[0]]);
+    assert_eq!(unsorted_with(&intervals, MinGap(6)), vec![intervals[1]]);
+  }
+
+  #[test]
+  fn window_matches_filtering() {
+    let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..60u32).map(|i| (i * 13 % 31, i * 13 % 31 + 1 + i % 6, 1 + i % 7).into()).collect();
+    intervals.sort_by_key(|i| i.end);
+
+    let mut memo = vec![0; intervals.len()];
+    for a in 0..30 {
+      for b in a..40 {
+        let mut solution = vec![];
+        sorted_in_window(&intervals, a..b, &mut memo, &mut solution);
+
+        let inside: Vec<_> = intervals.iter().copied().filter(|i| a <= i.start && i.end <= b).collect();
+        assert!(solution.iter().all(|i| inside.contains(i)));
+        assert!(solution.windows(2).all(|w| w[1].end <= w[0].start));
+        assert_eq!(solution.iter().map(|i| i.weight).sum::<u32>(), unsorted(&inside).iter().map(|i| i.weight).sum::<u32>());
+      }
+    }
+  }
+}
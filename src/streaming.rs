@@ -0,0 +1,506 @@
+use std::{cmp::Reverse, collections::{BinaryHeap, VecDeque}, fmt, marker::PhantomData, ops::Add};
+use crate::{solvers::reconstruct, traits, unsorted};
+
+/// An interval pushed to a [`StreamingSolver`] ending before one pushed earlier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OutOfOrder<Time> {
+  /// The end of the interval rejected.
+  pub end:  Time,
+  /// The end of the last interval accepted.
+  pub last: Time
+}
+
+impl<Time: fmt::Debug> fmt::Display for OutOfOrder<Time> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "interval ending at {:?} arrived after one ending at {:?}", self.end, self.last)
+  }
+}
+
+impl<Time: fmt::Debug> std::error::Error for OutOfOrder<Time> { }
+
+/// Solves a problem whose intervals arrive one at a time, in nondecreasing end order (e.g. from a live feed ordered by
+/// completion time), keeping the optimum up to date as they do. Pushing an interval is `O(log n)` in the number of
+/// intervals pushed so far.
+///
+/// This is the memoization behind [`sorted`](crate::sorted), grown one entry at a time: the optimum of the intervals
+/// pushed so far never depends on those still to come.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let mut solver = StreamingSolver::new();
+///
+/// solver.push(WeightedInterval::new(0u8, 3u8, 5u32)).unwrap();
+/// solver.push(WeightedInterval::new(2, 5, 7)).unwrap();
+/// assert_eq!(solver.optimal_weight(), Some(7));
+///
+/// solver.push(WeightedInterval::new(4, 6, 3)).unwrap();
+/// assert_eq!(solver.optimal_weight(), Some(8));
+/// assert_eq!(solver.solution(), vec![(4, 6, 3).into(), (0, 3, 5).into()]);
+///
+/// // too late: something ending at 6 was already pushed
+/// assert!(solver.push(WeightedInterval::new(1, 2, 9)).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct StreamingSolver<Weight, Time, Interval> {
+  intervals:    Vec<Interval>,
+  ends:         Vec<Time>,
+  predecessors: Vec<Option<usize>>,
+  memoization:  Vec<Weight>
+}
+
+impl<Weight, Time, Interval> Default for StreamingSolver<Weight, Time, Interval> {
+  fn default() -> Self {
+    Self { intervals: vec![], ends: vec![], predecessors: vec![], memoization: vec![] }
+  }
+}
+
+impl<Weight, Time, Interval> StreamingSolver<Weight, Time, Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  /// A solver yet to receive any interval.
+  pub fn new() -> Self { Self::default() }
+
+  /// Takes in the next interval, which must not end before any interval already pushed. `O(log n)`.
+  ///
+  /// Fails, leaving the solver as it was, if it does.
+  pub fn push(&mut self, interval: Interval) -> Result<(), OutOfOrder<Time>> {
+    let end = interval.end();
+    if let Some(last) = self.ends.last() {
+      if end < *last { return Err(OutOfOrder { end, last: last.clone() }); }
+    }
+
+    let start = interval.start();
+    let predecessor = self.ends.partition_point(|e| *e <= start).checked_sub(1);
+
+    let included = match predecessor {
+      Some(k) => interval.weight() + self.memoization[k].clone(),
+      None    => interval.weight()
+    };
+    let best = match self.memoization.last() {
+      Some(excluded) => included.max(excluded.clone()),
+      None           => included
+    };
+
+    self.intervals.push(interval);
+    self.ends.push(end);
+    self.predecessors.push(predecessor);
+    self.memoization.push(best);
+    Ok(())
+  }
+
+  /// The weight of the optimal selection among the intervals pushed so far (`None` before the first push). `O(1)`.
+  #[must_use]
+  pub fn optimal_weight(&self) -> Option<Weight> { self.memoization.last().cloned() }
+
+  /// The optimal selection among the intervals pushed so far, in descending order of end (as from
+  /// [`sorted`](crate::sorted)). `O(n)` in the number of intervals pushed so far.
+  #[must_use]
+  pub fn solution(&self) -> Vec<Interval>
+    where Interval: Clone
+  {
+    let mut solution = vec![];
+    reconstruct(
+      self.intervals.len(),
+      &self.memoization,
+      |k| self.intervals[k].weight(),
+      |k| self.predecessors[k],
+      |k| solution.push(self.intervals[k].clone())
+    );
+    solution
+  }
+
+  /// The intervals pushed so far, in the order they were pushed.
+  pub fn intervals(&self) -> &[Interval] { &self.intervals }
+
+  /// How many intervals were pushed so far.
+  pub fn len(&self) -> usize { self.intervals.len() }
+
+  /// Whether no interval was pushed yet.
+  pub fn is_empty(&self) -> bool { self.intervals.is_empty() }
+}
+
+/// Keeps the optimum of a window moving forward in time up to date, as intervals arrive in nondecreasing end order
+/// (as for [`StreamingSolver`]) and the start of the window moves past the oldest ones.
+///
+/// The window holds the intervals pushed that start at or after its start. Pushing an interval is `O(log m)` in the
+/// number `m` of intervals the window holds. Moving the window forward memoizes again only the intervals leaving it,
+/// and the entries depending on those whose optimal weight changed (the next entry, and the intervals following it),
+/// reusing the predecessors found when the intervals were pushed, so that neither sorting nor searching is ever
+/// repeated. That's `O((l + c) log m)` for the `l` intervals leaving the window and the `c` entries whose weight
+/// changes: none, when those leaving weren't needed by the optimum of any prefix, but most of those after them when
+/// they were part of the optimal selection.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let mut window = SlidingWindow::new();
+/// window.push(WeightedInterval::new(0u8, 3u8, 5u32)).unwrap();
+/// window.push(WeightedInterval::new(2, 5, 4)).unwrap();
+/// window.push(WeightedInterval::new(3, 6, 2)).unwrap();
+/// assert_eq!(window.optimal_weight(), Some(7));
+///
+/// // the tick: the window now starts at 1, and something new comes in
+/// window.advance(1);
+/// window.push(WeightedInterval::new(5, 8, 3)).unwrap();
+/// assert_eq!(window.optimal_weight(), Some(7));
+/// assert_eq!(window.solution(), vec![(5, 8, 3).into(), (2, 5, 4).into()]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SlidingWindow<Weight, Time, Interval> {
+  /// Where the window starts, if it was ever moved.
+  from:         Option<Time>,
+  /// How many intervals were dropped so far, the offset of the predecessors into what's left.
+  dropped:      usize,
+  intervals:    VecDeque<Interval>,
+  ends:         VecDeque<Time>,
+  /// The predecessors, counting the intervals dropped.
+  predecessors: VecDeque<Option<usize>>,
+  /// The intervals having each as predecessor, counting the intervals dropped.
+  followers:    VecDeque<Vec<usize>>,
+  /// The starts of the intervals in the window, with their positions counting the intervals dropped, earliest on top.
+  starts:       BinaryHeap<Reverse<(Time, usize)>>,
+  /// The optimal weight of each prefix, among the intervals in the window (if any).
+  memoization:  VecDeque<Option<Weight>>
+}
+
+impl<Weight, Time: Ord, Interval> Default for SlidingWindow<Weight, Time, Interval> {
+  fn default() -> Self {
+    Self {
+      from:         None,
+      dropped:      0,
+      intervals:    VecDeque::new(),
+      ends:         VecDeque::new(),
+      predecessors: VecDeque::new(),
+      followers:    VecDeque::new(),
+      starts:       BinaryHeap::new(),
+      memoization:  VecDeque::new()
+    }
+  }
+}
+
+impl<Weight, Time, Interval> SlidingWindow<Weight, Time, Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  /// An empty window, starting at the beginning of time.
+  pub fn new() -> Self { Self::default() }
+
+  /// Takes in the next interval, which must not end before any interval in the window. `O(log m)`.
+  ///
+  /// An interval starting before the window is kept out of it. Fails, leaving the window as it was, if the interval
+  /// arrives out of order.
+  pub fn push(&mut self, interval: Interval) -> Result<(), OutOfOrder<Time>> {
+    let end = interval.end();
+    if let Some(last) = self.ends.back() {
+      if end < *last { return Err(OutOfOrder { end, last: last.clone() }); }
+    }
+
+    let start = interval.start();
+    let predecessor = self.ends.partition_point(|e| *e <= start).checked_sub(1).map(|k| k + self.dropped);
+
+    let position = self.dropped + self.intervals.len();
+    if let Some(p) = predecessor { self.followers[p - self.dropped].push(position); }
+    if self.from.as_ref().is_none_or(|from| start >= *from) { self.starts.push(Reverse((start, position))); }
+
+    self.intervals.push_back(interval);
+    self.ends.push_back(end);
+    self.predecessors.push_back(predecessor);
+    self.followers.push_back(vec![]);
+    self.memoization.push_back(None);
+    self.memoize(self.intervals.len() - 1);
+    Ok(())
+  }
+
+  /// Moves the start of the window to `from`, which must not move backward: only the intervals starting at or after it
+  /// remain in the window. `O((l + c) log m)` for the `l` intervals leaving the window and the `c` memoization entries
+  /// whose weight changes.
+  pub fn advance(&mut self, from: Time) {
+    // the entries to memoize again, counting the intervals dropped: those leaving the window to begin with
+    let mut stale = BinaryHeap::new();
+    while let Some(Reverse((start, k))) = self.starts.peek() {
+      if *start >= from { break; }
+      stale.push(Reverse(*k));
+      self.starts.pop();
+    }
+    self.from = Some(from.clone());
+
+    // smallest first, so that the entries an entry depends on are up to date by the time it's memoized again
+    let mut last = None;
+    while let Some(Reverse(k)) = stale.pop() {
+      if last == Some(k) { continue; }
+      last = Some(k);
+
+      let position = k - self.dropped;
+      let before = self.memoization[position].clone();
+      self.memoize(position);
+      if self.memoization[position] == before { continue; }
+
+      if position + 1 < self.intervals.len() { stale.push(Reverse(k + 1)); }
+      stale.extend(self.followers[position].iter().map(|&f| Reverse(f)));
+    }
+
+    // those over by then can't come back, and everything up to them is out of the window, weighing nothing
+    while self.intervals.front().is_some_and(|i| i.start() < from && i.end() <= from) {
+      self.intervals.pop_front();
+      self.ends.pop_front();
+      self.predecessors.pop_front();
+      self.followers.pop_front();
+      self.memoization.pop_front();
+      self.dropped += 1;
+    }
+  }
+
+  /// The weight of the optimal selection among the intervals in the window (`None` if there are none). `O(1)`.
+  #[must_use]
+  pub fn optimal_weight(&self) -> Option<Weight> { self.memoization.back().cloned().flatten() }
+
+  /// The optimal selection among the intervals in the window, in descending order of end (as from
+  /// [`sorted`](crate::sorted)). `O(m)`.
+  #[must_use]
+  pub fn solution(&self) -> Vec<Interval>
+    where Interval: Clone
+  {
+    let mut solution = vec![];
+
+    let mut j = self.intervals.len().checked_sub(1);
+    while let Some(i) = j {
+      if let Some(included) = self.included(i) {
+        let excluded = i.checked_sub(1).and_then(|k| self.memoization[k].clone());
+        if excluded.is_none_or(|excluded| included > excluded) {
+          solution.push(self.intervals[i].clone());
+          j = self.predecessor(i);
+          continue;
+        }
+      }
+      j = i.checked_sub(1);
+    }
+
+    solution
+  }
+
+  /// The predecessor of the interval at `k`, if it wasn't dropped.
+  fn predecessor(&self, k: usize) -> Option<usize> {
+    self.predecessors[k].and_then(|p| p.checked_sub(self.dropped))
+  }
+
+  /// The optimal weight of a selection ending with the interval at `k`, unless it's outside the window.
+  fn included(&self, k: usize) -> Option<Weight> {
+    let interval = &self.intervals[k];
+    if self.from.as_ref().is_some_and(|from| interval.start() < *from) { return None; }
+
+    Some(match self.predecessor(k).and_then(|p| self.memoization[p].clone()) {
+      Some(weight) => interval.weight() + weight,
+      None         => interval.weight()
+    })
+  }
+
+  /// Brings the memoization of the interval at `k` up to date, that of those before it being so.
+  fn memoize(&mut self, k: usize) {
+    let excluded = k.checked_sub(1).and_then(|p| self.memoization[p].clone());
+    self.memoization[k] = match (self.included(k), excluded) {
+      (Some(included), Some(excluded)) => Some(included.max(excluded)),
+      (included, excluded)             => included.or(excluded)
+    };
+  }
+}
+
+/// Approximates the optimum of a stream of intervals arriving in any order, too many to keep: only those which could
+/// matter are kept, and the optimal selection among them is found when asked for.
+///
+/// This is the local ratio method, run on the stream (as for weighted matching by Paz and Schwartzman). Each interval
+/// arriving gains its weight, less the gains of the kept intervals it overlaps, and is kept only if its gain is more
+/// than `slack` times theirs. The weight of [`solution`](Self::solution) is then at least the sum of the gains, and the
+/// optimum at most `(1 + slack)·c` times it, for `c` the most intervals of an optimal selection arriving no earlier than
+/// some kept interval and overlapping it:
+/// - `c = 1` when intervals arrive in nondecreasing end order, so the approximation is within `1 + slack`;
+/// - `c ≤ 2` in any order when no interval strictly contains another (e.g. all have the same length);
+/// - otherwise, `c` is at most 2 more than the disjoint intervals fitting strictly inside a kept interval.
+///
+/// `slack` trades memory for quality: each kept interval raises the gains overlapping any time it covers by a factor
+/// `1 + slack`, so at most `log_{1+slack}(n W / w)` kept intervals overlap at any time, for `n` intervals weighing `w`
+/// to `W`, and the kept intervals number `O(α log(n W / w) / slack)` for `α` the most disjoint intervals. With a
+/// `slack` of 0, every interval with a positive gain is kept. Pushing an interval is `O(m)` in the `m` kept.
+///
+/// Weights must convert losslessly into `i128` (all primitive integers up to 64 bits do); those of 0 or less are never
+/// kept.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let mut solver = ApproximateStreamingSolver::new(0.5);
+/// for (start, end, weight) in [(0u8, 4u8, 5u32), (3, 6, 4), (4, 8, 6), (6, 10, 9), (2, 9, 20)] {
+///   solver.push(WeightedInterval::new(start, end, weight));
+/// }
+///
+/// // (3, 6) gains nothing over (0, 4), and (6, 10) gains 3, no more than half of the 6 gained by (4, 8)
+/// assert_eq!(solver.kept(), &[(0, 4, 5).into(), (4, 8, 6).into(), (2, 9, 20).into()]);
+/// assert_eq!(solver.solution(), vec![(2, 9, 20).into()]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ApproximateStreamingSolver<Weight, Time, Interval> {
+  slack:  f64,
+  /// The intervals kept, in the order they arrived.
+  kept:   Vec<Interval>,
+  /// The start, end and gain of each interval kept.
+  gains:  Vec<(Time, Time, i128)>,
+  weight: PhantomData<Weight>
+}
+
+impl<Weight, Time, Interval> ApproximateStreamingSolver<Weight, Time, Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone + Into<i128>,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>
+{
+  /// A solver yet to receive any interval, keeping those gaining more than `slack` times the kept intervals they
+  /// overlap.
+  ///
+  /// # Panics
+  /// If `slack` is negative or NaN.
+  pub fn new(slack: f64) -> Self {
+    assert!(slack >= 0.0, "the slack must be non-negative");
+    Self { slack, kept: vec![], gains: vec![], weight: PhantomData }
+  }
+
+  /// Takes in the next interval, in any order, keeping it if it gains enough. `O(m)`.
+  pub fn push(&mut self, interval: Interval) {
+    let (start, end) = (interval.start(), interval.end());
+    let overlapped: i128 = self.gains.iter()
+      .filter(|(s, e, _)| *s < end && start < *e)
+      .map(|(_, _, gain)| gain)
+      .sum();
+
+    let gain = interval.weight().into() - overlapped;
+    if gain > 0 && gain as f64 > self.slack * overlapped as f64 {
+      self.gains.push((start, end, gain));
+      self.kept.push(interval);
+    }
+  }
+
+  /// The optimal selection among the intervals kept, in descending order of end (as from [`unsorted`](crate::unsorted)).
+  /// `O(m log m)`.
+  #[must_use]
+  pub fn solution(&self) -> Vec<Interval>
+    where Interval: Clone
+  {
+    unsorted(&self.kept)
+  }
+
+  /// The intervals kept so far, in the order they arrived.
+  pub fn kept(&self) -> &[Interval] { &self.kept }
+
+  /// How many intervals are kept.
+  pub fn len(&self) -> usize { self.kept.len() }
+
+  /// Whether no interval is kept.
+  pub fn is_empty(&self) -> bool { self.kept.is_empty() }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{sorted, testing::Lcg, unsorted, ApproximateStreamingSolver, SlidingWindow, StreamingSolver, WeightedInterval};
+
+  #[test]
+  fn matches_sorted_at_every_step() {
+    let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..40u32).map(|i| (i * 13 % 23, i * 13 % 23 + 1 + i % 4, 1 + i % 7).into()).collect();
+    intervals.sort_by_key(|i| i.end);
+
+    let mut solver = StreamingSolver::new();
+    assert_eq!(solver.optimal_weight(), None);
+
+    let mut memo = vec![0; intervals.len()];
+    for (pushed, interval) in intervals.iter().enumerate() {
+      solver.push(*interval).unwrap();
+
+      let mut expected = vec![];
+      sorted(&intervals[..=pushed], &mut memo, &mut expected);
+      assert_eq!(solver.solution(), expected);
+      assert_eq!(solver.optimal_weight(), Some(expected.iter().map(|i| i.weight).sum::<u32>()));
+    }
+
+    let rejected = solver.push((0, 1, 1).into()).unwrap_err();
+    assert_eq!((rejected.end, solver.len()), (1, intervals.len()));
+  }
+
+  #[test]
+  fn sliding_matches_solving_the_window() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = {
+      let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..60u32).map(|i| (i * 7 % 50, i * 7 % 50 + 1 + i % 9, 1 + i % 5).into()).collect();
+      intervals.sort_by_key(|i| i.end);
+      intervals
+    };
+
+    let mut window = SlidingWindow::new();
+    let mut pushed = 0;
+    for tick in 0..60 {
+      let from = tick.max(8) - 8;
+      window.advance(from);
+      while pushed < intervals.len() && intervals[pushed].end <= tick {
+        window.push(intervals[pushed]).unwrap();
+        pushed += 1;
+      }
+
+      let inside: Vec<WeightedInterval<u32, u32>> = intervals[..pushed].iter().copied().filter(|i| i.start >= from).collect();
+      let expected = unsorted(&inside).iter().map(|i| i.weight).reduce(|a, b| a + b);
+      assert_eq!(window.optimal_weight(), expected, "tick {tick}");
+
+      let solution = window.solution();
+      assert!(solution.iter().all(|i| inside.contains(i)));
+      assert!(solution.windows(2).all(|pair| pair[1].end <= pair[0].start));
+      assert_eq!(solution.iter().map(|i| i.weight).reduce(|a, b| a + b), expected);
+    }
+  }
+
+  #[test]
+  fn sliding_in_uneven_steps_matches_solving_the_window() {
+    let mut rng = Lcg::new(23);
+
+    for _ in 0..100 {
+      let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..40)
+        .map(|_| { let start = rng.next(40); (start, start + rng.next(6), 1 + rng.next(9)).into() })
+        .collect();
+      intervals.sort_by_key(|i| i.end);
+
+      let (mut window, mut pushed, mut from) = (SlidingWindow::new(), 0, 0);
+      for tick in 0..50 {
+        // jumping ahead by several intervals' worth at times, and standing still at others
+        if tick > 5 { from += rng.next(4); }
+        window.advance(from);
+        while pushed < intervals.len() && intervals[pushed].end <= tick {
+          window.push(intervals[pushed]).unwrap();
+          pushed += 1;
+        }
+
+        let inside: Vec<WeightedInterval<u32, u32>> = intervals[..pushed].iter().copied().filter(|i| i.start >= from).collect();
+        let expected = (!inside.is_empty()).then(|| unsorted(&inside).iter().map(|i| i.weight).sum::<u32>());
+        assert_eq!(window.optimal_weight(), expected, "{intervals:?} tick {tick}");
+        assert_eq!(window.solution().iter().map(|i| i.weight).reduce(|a, b| a + b), expected);
+      }
+    }
+  }
+
+  #[test]
+  fn approximation_stays_within_its_guarantee() {
+    let mut rng = Lcg::new(11);
+    let total = |solution: &[WeightedInterval<u32, u32>]| solution.iter().map(|i| i.weight).sum::<u32>();
+
+    for round in 0..200 {
+      let slack = [0.0, 0.1, 0.5, 2.0][round % 4];
+      let same_length = round % 3 == 0;
+      let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..40)
+        .map(|_| { let start = rng.next(60); (start, start + if same_length { 5 } else { 1 + rng.next(8) }, 1 + rng.next(50)).into() })
+        .collect();
+      if !same_length { intervals.sort_by_key(|i| i.end); }
+
+      let mut solver = ApproximateStreamingSolver::new(slack);
+      for interval in &intervals { solver.push(*interval); }
+
+      let solution = solver.solution();
+      assert!(solution.iter().all(|i| solver.kept().contains(i)));
+      assert!(solution.windows(2).all(|pair| pair[1].end <= pair[0].start));
+
+      let factor = (1.0 + slack) * if same_length { 2.0 } else { 1.0 };
+      assert!(f64::from(total(&unsorted(&intervals))) <= factor * f64::from(total(&solution)), "round {round}");
+    }
+  }
+}
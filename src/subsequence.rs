@@ -0,0 +1,164 @@
+//! Increasing subsequences: choosing, in order, values that each exceed the one before.
+//!
+//! These are chains in a sequence, much as optimal sets are chains of compatible intervals on a timeline. As with
+//! [`sorted`](crate::sorted), the caller provides the buffers, so that solving many problems allocates once.
+
+use std::ops::Add;
+
+/// Finds a longest strictly increasing subsequence of `values`. `O(n log n)` in value number.
+///
+/// - `memoization` is an existing buffer that will be written to, and must hold at least as many elements as `values`.
+///   Afterwards, `memoization[i]` is the length of the longest increasing subsequence ending with `values[i]`.
+/// - `solution` is an existing buffer that the positions of the subsequence will be appended to, in descending order. Its
+///   spare capacity is used as scratch space along the way, so a buffer with room for `values.len()` more positions
+///   won't reallocate.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let values = [3, 1, 4, 1, 5, 9, 2, 6];
+///
+/// let mut memo = vec![0; values.len()];
+/// let mut solution = vec![];
+/// subsequence::longest_increasing(&values, &mut memo, &mut solution);
+///
+/// // 1, 4, 5, 6
+/// assert_eq!(solution, vec![7, 4, 2, 1]);
+/// ```
+pub fn longest_increasing<Value: Ord>(values: &[Value], memoization: &mut [usize], solution: &mut Vec<usize>) {
+  let base = solution.len();
+
+  // past the solution so far, the position of the least value ending an increasing subsequence of each length
+  phase!("memoize", values.len(), for i in 0..values.len() {
+    let length = solution[base..].partition_point(|&tail| values[tail] < values[i]);
+    memoization[i] = length + 1;
+    if base + length == solution.len() { solution.push(i); }
+    else { solution[base + length] = i; }
+  });
+  let mut wanted = solution.len() - base;
+  solution.truncate(base);
+
+  // walking back, the first value of each length below the one after it continues the subsequence
+  phase!("reconstruct", values.len(), {
+    let mut next: Option<usize> = None;
+    for i in (0..values.len()).rev() {
+      if wanted == 0 { break; }
+      if memoization[i] == wanted && next.is_none_or(|next| values[i] < values[next]) {
+        solution.push(i);
+        next = Some(i);
+        wanted -= 1;
+      }
+    }
+  });
+}
+
+/// Finds a heaviest strictly increasing subsequence of `values`, where `values[i]` weighs `weight(i)`, by a Fenwick
+/// tree. `O(n log n)` in value number. Weights should not be negative.
+///
+/// - `memoization` is an existing buffer that will be written to, and must hold at least twice as many elements as
+///   `values`. Afterwards, `memoization[i]` is the weight of the heaviest increasing subsequence ending with
+///   `values[i]` (the rest is scratch space).
+/// - `solution` is an existing buffer that the positions of the subsequence will be appended to, in descending order. Its
+///   spare capacity is used as scratch space along the way, so a buffer with room for `values.len()` more positions
+///   won't reallocate.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let values = [3, 1, 4, 1, 5, 9, 2, 6];
+/// let weights = [5, 1, 1, 1, 1, 2, 1, 1];
+///
+/// let mut memo = vec![0; 2 * values.len()];
+/// let mut solution = vec![];
+/// subsequence::heaviest_increasing(&values, |i| weights[i], &mut memo, &mut solution);
+///
+/// // 3, 4, 5, 9
+/// assert_eq!(solution, vec![5, 4, 2, 0]);
+/// assert_eq!(memo[5], 9);
+/// ```
+pub fn heaviest_increasing<Value, Weight>(
+  values:      &[Value],
+  weight:      impl Fn(usize) -> Weight,
+  memoization: &mut [Weight],
+  solution:    &mut Vec<usize>
+) where Value: Ord,
+        Weight: Ord + Add<Output = Weight> + Clone + Default
+{
+  let count = values.len();
+  let (best, tree) = memoization[..2 * count].split_at_mut(count);
+  tree.fill(Weight::default());
+
+  // visit the values in ascending order, each then seeing only the lesser values before it in the tree. Equal values
+  // are visited back to front, so none sees another
+  let base = solution.len();
+  solution.extend(0..count);
+  phase!("sort", count, solution[base..].sort_unstable_by(|&a, &b| values[a].cmp(&values[b]).then(b.cmp(&a))));
+
+  phase!("memoize", count, for &i in &solution[base..] {
+    // the heaviest subsequence ending before position `i`, as a maximum over a prefix of the tree
+    let mut before = Weight::default();
+    let mut position = i;
+    while position > 0 {
+      before = before.max(tree[position - 1].clone());
+      position &= position - 1;
+    }
+    best[i] = before + weight(i);
+
+    let mut position = i + 1;
+    while position <= count {
+      if tree[position - 1] < best[i] { tree[position - 1] = best[i].clone(); }
+      position += position & position.wrapping_neg();
+    }
+  });
+  solution.truncate(base);
+
+  // walking back from the heaviest end, the first lesser value weighing what is left continues the subsequence
+  let Some(mut i) = (0..count).max_by_key(|&i| best[i].clone()) else { return };
+  phase!("reconstruct", count, loop {
+    solution.push(i);
+    if best[i] == weight(i) { break; }
+
+    let left = |j: usize| best[j].clone() + weight(i) == best[i];
+    i = (0..i).rev().find(|&j| values[j] < values[i] && left(j)).unwrap_or_else(|| unreachable!());
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{subsequence::{heaviest_increasing, longest_increasing}, testing::Lcg};
+
+  #[test]
+  fn matches_quadratic_recurrence() {
+    let mut rng = Lcg::new(31);
+
+    let (mut memo, mut weighted_memo, mut solution, mut heaviest) = (vec![], vec![], vec![7], vec![7]);
+    for _ in 0..500 {
+      let n = rng.next(40) as usize;
+      let values: Vec<u32> = (0..n).map(|_| rng.next(20)).collect();
+      let weights: Vec<u32> = (0..n).map(|_| rng.next(10)).collect();
+
+      // the longest and heaviest subsequences ending at each value, the slow way
+      let (mut lengths, mut weighing) = (vec![1; n], weights.clone());
+      for i in 0..n {
+        for j in (0..i).filter(|&j| values[j] < values[i]) {
+          lengths[i] = lengths[i].max(lengths[j] + 1);
+          weighing[i] = weighing[i].max(weighing[j] + weights[i]);
+        }
+      }
+
+      memo.resize(n, 0);
+      solution.truncate(1);
+      longest_increasing(&values, &mut memo, &mut solution);
+      assert_eq!(&memo[..n], &lengths[..]);
+      assert_eq!(solution[0], 7);
+      assert_eq!(solution.len() - 1, lengths.iter().copied().max().unwrap_or(0));
+      assert!(solution[1..].windows(2).all(|pair| pair[1] < pair[0] && values[pair[1]] < values[pair[0]]));
+
+      weighted_memo.resize(2 * n, 0);
+      heaviest.truncate(1);
+      heaviest_increasing(&values, |i| weights[i], &mut weighted_memo, &mut heaviest);
+      assert_eq!(&weighted_memo[..n], &weighing[..]);
+      assert_eq!(heaviest[0], 7);
+      assert_eq!(heaviest[1..].iter().map(|&i| weights[i]).sum::<u32>(), weighing.iter().copied().max().unwrap_or(0));
+      assert!(heaviest[1..].windows(2).all(|pair| pair[1] < pair[0] && values[pair[1]] < values[pair[0]]));
+    }
+  }
+}
@@ -0,0 +1,391 @@
+//! Sweep-line analyses: walking the timeline from start to end, keeping track of the intervals running.
+
+use std::ops::{Add, Range, Sub};
+use crate::traits;
+
+/// An interval's bound, met while sweeping the timeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Event<Time> {
+  /// The interval at `index` starts running at `time`.
+  Start { time: Time, index: usize },
+  /// The interval at `index` stops running at `time`.
+  End { time: Time, index: usize }
+}
+
+impl<Time> Event<Time> {
+  /// When the event happens.
+  pub fn time(&self) -> &Time {
+    match self { Event::Start { time, .. } | Event::End { time, .. } => time }
+  }
+
+  /// The index (into the intervals swept) of the interval the event belongs to.
+  pub fn index(&self) -> usize {
+    match self { Event::Start { index, .. } | Event::End { index, .. } => *index }
+  }
+}
+
+/// Lists the start and end events of `intervals` in the order a sweep meets them. `O(n log n)` in interval number.
+///
+/// Events are ordered by time. At equal times, and in keeping with intervals being half-open:
+/// - intervals end before others start, so intervals touching end to start never run at the same time,
+/// - zero-length intervals start and end right away (in between), so they only run alongside intervals strictly
+///   containing them, matching [`interval_graph::conflicts`](crate::interval_graph::conflicts),
+/// - remaining ties go by index.
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::sweep::Event;
+/// let intervals: Vec<(u8, u8)> = vec![(2, 4), (0, 2), (2, 2)];
+///
+/// assert_eq!(sweep::events_from(&intervals), vec![
+///   Event::Start { time: 0, index: 1 },
+///   Event::End   { time: 2, index: 1 },
+///   Event::Start { time: 2, index: 2 },
+///   Event::End   { time: 2, index: 2 },
+///   Event::Start { time: 2, index: 0 },
+///   Event::End   { time: 4, index: 0 }
+/// ]);
+/// ```
+#[must_use]
+pub fn events_from<Time, Interval, InputContainer>(intervals: InputContainer) -> Vec<Event<Time>>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // ranks at equal times: ends, then zero-length intervals, then starts
+  let mut keyed: Vec<(Time, u8, usize, Event<Time>)> = intervals.iter()
+    .enumerate()
+    .flat_map(|(index, i)| {
+      let (start, end) = (i.start(), i.end());
+      let rank = if start < end { [2, 0] } else { [1, 1] };
+      [
+        (start.clone(), rank[0], index, Event::Start { time: start, index }),
+        (end.clone(),   rank[1], index, Event::End { time: end, index })
+      ]
+    })
+    .collect();
+
+  // stable, so that a zero-length interval's start stays right before its end
+  phase!("sort", keyed.len(), keyed.sort_by(|a, b| (&a.0, a.1, a.2).cmp(&(&b.0, b.1, b.2))));
+  keyed.into_iter().map(|(.., event)| event).collect()
+}
+
+/// Sweeps over `intervals`, calling `on_start` and `on_end` with `state`, the time, and the interval's index as each
+/// interval starts and ends, in the order of [`events_from`]. Returns the final state. `O(n log n)` in interval number,
+/// plus the callbacks.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<(u8, u8)> = vec![(0, 3), (1, 5), (3, 4)];
+///
+/// // how long some interval runs, by remembering when the running count leaves zero
+/// let (busy, ..) = sweep::run(&intervals, (0, 0, 0),
+///   |(_, running, since), time, _| { if *running == 0 { *since = *time; } *running += 1; },
+///   |(busy, running, since), time, _| { *running -= 1; if *running == 0 { *busy += time - *since; } }
+/// );
+/// assert_eq!(busy, 5);
+/// ```
+pub fn run<State, Time, Interval, InputContainer>(
+  intervals:    InputContainer,
+  mut state:    State,
+  mut on_start: impl FnMut(&mut State, &Time, usize),
+  mut on_end:   impl FnMut(&mut State, &Time, usize)
+) -> State
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>,
+        InputContainer: AsRef<[Interval]>
+{
+  for event in events_from(intervals) {
+    match event {
+      Event::Start { time, index } => on_start(&mut state, &time, index),
+      Event::End { time, index }   => on_end(&mut state, &time, index)
+    }
+  }
+
+  state
+}
+
+/// The greatest load reached over the timeline, and where it is reached.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Peak<Load, Time> {
+  /// The greatest load.
+  pub load: Load,
+  /// The stretches of time during which the load is at its greatest, in chronological order, each as long as possible.
+  /// Empty if no interval ever runs.
+  pub at:   Vec<Range<Time>>
+}
+
+/// Finds the greatest number of intervals running at once, and when. `O(n log n)` in interval number.
+///
+/// Intervals are half-open, so one ending when another starts never runs at the same time as it,
+/// and zero-length intervals never run at all.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let calls: Vec<(u8, u8)> = vec![(0, 4), (1, 3), (2, 6), (5, 7), (6, 8)];
+///
+/// let peak = sweep::peak_count(&calls);
+/// assert_eq!(peak.load, 3);
+/// assert_eq!(peak.at, vec![2..3]);
+/// ```
+#[must_use]
+pub fn peak_count<Time, Interval, InputContainer>(intervals: InputContainer) -> Peak<usize, Time>
+  where Time: Ord + Clone,
+        Interval: traits::Interval<Time>,
+        InputContainer: AsRef<[Interval]>
+{
+  peak_by(intervals.as_ref(), |_| 1)
+}
+
+/// Finds the greatest total weight of the intervals running at once, and when. `O(n log n)` in interval number.
+///
+/// As for [`peak_count`], intervals are half-open. The load before any interval starts is `Weight::default()`.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // jobs weighted by the memory they take
+/// let jobs: Vec<WeightedInterval<u32, u8>> = vec![
+///   (0, 4, 512).into(),
+///   (1, 3, 256).into(),
+///   (3, 6, 1024).into()
+/// ];
+///
+/// let peak = sweep::peak_weight(&jobs);
+/// assert_eq!(peak.load, 1536);
+/// assert_eq!(peak.at, vec![3..4]);
+/// ```
+#[must_use]
+pub fn peak_weight<Weight, Time, Interval, InputContainer>(intervals: InputContainer) -> Peak<Weight, Time>
+  where Weight: Ord + Add<Output = Weight> + Sub<Output = Weight> + Clone + Default,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  peak_by(intervals.as_ref(), |i| i.weight())
+}
+
+/// Finds the moment the intervals running at once weigh the most in all (a heaviest clique of the interval graph), and
+/// the intervals running then. `O(n log n)` in interval number.
+///
+/// The moment is the first at which [`peak_weight`] is reached, and the intervals are listed by index, ascending. As for
+/// `peak_weight`, intervals are half-open. `None` if no intervals ever run together for more than nothing.
+///
+/// ```rust
+/// # use w_inter::*;
+/// // meetings weighted by their attendance
+/// let meetings: Vec<WeightedInterval<u32, u8>> = vec![
+///   (9, 11, 12).into(),
+///   (10, 12, 30).into(),
+///   (11, 13, 25).into(),
+///   (12, 14, 4).into()
+/// ];
+///
+/// let (at, running) = sweep::max_weight_point(&meetings).unwrap();
+/// assert_eq!(at, 11);
+/// assert_eq!(running, vec![1, 2]);
+/// ```
+#[must_use]
+pub fn max_weight_point<Weight, Time, Interval, InputContainer>(intervals: InputContainer) -> Option<(Time, Vec<usize>)>
+  where Weight: Ord + Add<Output = Weight> + Sub<Output = Weight> + Clone + Default,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+  let at = peak_by(intervals, |i| i.weight()).at.into_iter().next()?.start;
+
+  let running = (0..intervals.len()).filter(|&i| intervals[i].start() <= at && at < intervals[i].end()).collect();
+  Some((at, running))
+}
+
+/// The sweep behind the peak finders, `load` giving how much each interval adds to the load while running.
+fn peak_by<Load, Time, Interval>(intervals: &[Interval], load: impl Fn(&Interval) -> Load) -> Peak<Load, Time>
+  where Load: Ord + Add<Output = Load> + Sub<Output = Load> + Clone + Default,
+        Time: Ord + Clone,
+        Interval: traits::Interval<Time>
+{
+  let events = events_from(intervals);
+  let mut peak = Peak { load: Load::default(), at: vec![] };
+  let mut current = Load::default();
+
+  for (position, event) in events.iter().enumerate() {
+    current = match event {
+      Event::Start { index, .. } => current + load(&intervals[*index]),
+      Event::End { index, .. }   => current - load(&intervals[*index])
+    };
+
+    // the load holds until the next event at a later time, so only look at it after the last event at this time
+    let (time, Some(next)) = (event.time(), events.get(position + 1).map(Event::time)) else { break };
+    if next == time || current < peak.load || current == Load::default() { continue; }
+
+    if current > peak.load {
+      peak.load = current.clone();
+      peak.at.clear();
+    }
+    match peak.at.last_mut() {
+      Some(stretch) if stretch.end == *time => stretch.end = next.clone(),
+      _                                     => peak.at.push(time.clone()..next.clone())
+    }
+  }
+
+  peak
+}
+
+/// How busy one stretch of the timeline is, in a [`profile`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Usage<Time> {
+  /// The stretch of time.
+  pub at:   Range<Time>,
+  /// How long intervals run during the stretch, in all, each of those running at once counting. For intervals that
+  /// don't overlap (a solution), the stretch is idle for the rest of its length.
+  pub busy: Time
+}
+
+/// Splits `horizon` into consecutive stretches `resolution` long (the last one cut short by the horizon's end), and
+/// tells how long the `selected` intervals run in each: the step function of a utilization chart. `O(n log m + m)` for
+/// `n` intervals and `m` stretches, plus the stretches each interval spans.
+///
+/// Intervals are clipped to the horizon, and zero-length intervals never run. The stretches are in chronological
+/// order, and none are returned for an empty horizon.
+///
+/// # Panics
+/// If `resolution` isn't greater than `Time::default()` (zero), unless the horizon is empty.
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::sweep::Usage;
+/// let intervals: Vec<WeightedInterval<u32, u8>> = vec![(1, 3, 2).into(), (4, 9, 5).into(), (8, 12, 1).into()];
+/// let selected = unsorted(&intervals);
+///
+/// assert_eq!(sweep::profile(&selected, 0..10, 4), vec![
+///   Usage { at: 0..4,  busy: 2 },
+///   Usage { at: 4..8,  busy: 4 },
+///   Usage { at: 8..10, busy: 1 }
+/// ]);
+/// ```
+#[must_use]
+pub fn profile<Time, Interval, InputContainer>(selected: InputContainer, horizon: Range<Time>, resolution: Time) -> Vec<Usage<Time>>
+  where Time: Ord + Add<Output = Time> + Sub<Output = Time> + Clone + Default,
+        Interval: traits::Interval<Time>,
+        InputContainer: AsRef<[Interval]>
+{
+  let selected = selected.as_ref();
+  if horizon.start < horizon.end { assert!(resolution > Time::default(), "resolution must be positive"); }
+
+  // stepping by what's left of the horizon when less than `resolution`, so as not to step past its end
+  let mut usage = vec![];
+  let mut start = horizon.start.clone();
+  while start < horizon.end {
+    let end = if horizon.end.clone() - start.clone() <= resolution { horizon.end.clone() } else { start.clone() + resolution.clone() };
+    usage.push(Usage { at: start..end.clone(), busy: Time::default() });
+    start = end;
+  }
+
+  phase!("sweep", selected.len(), for interval in selected {
+    let (from, to) = (interval.start().max(horizon.start.clone()), interval.end().min(horizon.end.clone()));
+    if from >= to { continue; }
+
+    let first = usage.partition_point(|stretch| stretch.at.end <= from);
+    for stretch in usage[first..].iter_mut().take_while(|stretch| stretch.at.start < to) {
+      let ran = to.clone().min(stretch.at.end.clone()) - from.clone().max(stretch.at.start.clone());
+      stretch.busy = stretch.busy.clone() + ran;
+    }
+  });
+
+  usage
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{sweep::{self, Event, Usage}, testing::Lcg, WeightedInterval};
+
+  #[test]
+  fn peak_stretches() {
+    let intervals: Vec<(u32, u32)> = vec![(0, 2), (1, 3), (3, 5), (4, 6), (4, 4), (6, 9), (7, 8), (8, 9)];
+
+    let peak = sweep::peak_count(&intervals);
+    assert_eq!(peak.load, 2);
+    assert_eq!(peak.at, vec![1..2, 4..5, 7..9]);
+
+    let empty = sweep::peak_count(Vec::<(u32, u32)>::new());
+    assert_eq!((empty.load, empty.at), (0, vec![]));
+  }
+
+  #[test]
+  fn peak_weight_joins_touching_stretches() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = vec![(0, 4, 3).into(), (0, 2, 2).into(), (2, 4, 2).into(), (4, 5, 4).into()];
+
+    let peak = sweep::peak_weight(&intervals);
+    assert_eq!(peak.load, 5);
+    assert_eq!(peak.at, vec![0..4]);
+  }
+
+  #[test]
+  fn event_order() {
+    let intervals: Vec<(u32, u32)> = vec![(1, 1), (0, 1), (1, 3), (1, 1), (0, 3)];
+
+    let order: Vec<(bool, usize)> = sweep::events_from(&intervals).iter()
+      .map(|e| (matches!(e, Event::Start { .. }), e.index()))
+      .collect();
+    assert_eq!(order, vec![
+      (true, 1), (true, 4), (false, 1), (true, 0), (false, 0), (true, 3), (false, 3), (true, 2), (false, 2), (false, 4)
+    ]);
+
+    // zero-length intervals run alongside those strictly containing them only
+    let most = sweep::run(&intervals, (0, 0), |(running, most), _, _| { *running += 1; *most = (*most).max(*running); }, |(running, _), _, _| *running -= 1);
+    assert_eq!(most, (0, 2));
+  }
+
+  #[test]
+  fn max_weight_point_matches_brute_force() {
+    let mut rng = Lcg::new(67);
+
+    for _ in 0..300 {
+      let intervals: Vec<WeightedInterval<u32, u32>> = (0..rng.next(12))
+        .map(|_| { let start = rng.next(20); (start, start + rng.next(6), 1 + rng.next(9)).into() })
+        .collect();
+
+      let running_at = |t: u32| (0..intervals.len()).filter(|&i| intervals[i].start <= t && t < intervals[i].end).collect::<Vec<_>>();
+      let load = |t: u32| running_at(t).iter().map(|&i| intervals[i].weight).sum::<u32>();
+      let heaviest = (0..30).map(load).max().unwrap_or(0);
+
+      match sweep::max_weight_point(&intervals) {
+        Some((at, running)) => {
+          assert_eq!(running, running_at(at));
+          assert_eq!(load(at), heaviest, "{intervals:?}");
+          assert!((0..at).all(|t| load(t) < heaviest));
+        }
+        None => assert_eq!(heaviest, 0)
+      }
+    }
+  }
+
+  #[test]
+  fn profile_matches_unit_steps() {
+    let mut rng = Lcg::new(97);
+
+    for _ in 0..300 {
+      let intervals: Vec<(u32, u32)> = (0..rng.next(6)).map(|_| { let start = rng.next(20); (start, start + rng.next(8)) }).collect();
+      let start = rng.next(15);
+      let (horizon, resolution) = (start..start + rng.next(12), 1 + rng.next(5));
+
+      let usage = sweep::profile(&intervals, horizon.clone(), resolution);
+
+      // the stretches tile the horizon
+      assert_eq!(usage.first().map(|u| u.at.start), (!horizon.is_empty()).then_some(horizon.start));
+      assert_eq!(usage.last().map(|u| u.at.end), (!horizon.is_empty()).then_some(horizon.end));
+      assert!(usage.windows(2).all(|pair| pair[0].at.end == pair[1].at.start && pair[0].at.end - pair[0].at.start == resolution));
+
+      // each running for one unit of time at each time it runs
+      for Usage { at, busy } in usage {
+        let ran = at.clone().map(|t| intervals.iter().filter(|&&(s, e)| s <= t && t < e).count() as u32).sum::<u32>();
+        assert_eq!(busy, ran, "{intervals:?} {horizon:?} {resolution}");
+      }
+    }
+
+    // an empty horizon needs no resolution
+    assert_eq!(sweep::profile(Vec::<(u32, u32)>::new(), 3..3, 0), vec![]);
+  }
+}
@@ -0,0 +1,128 @@
+use std::ops::{Add, Range};
+use crate::{traits, WeightedInterval};
+
+/// A weighted interval carrying a payload (an ID, a handle, a row, ...) through the solvers untouched.
+///
+/// Saves defining a custom type and implementing `Interval` and `Weighted` on it just to find out
+/// *which* intervals were selected.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let bookings: Vec<TaggedInterval<u32, u16, &str>> = vec![
+///   (9, 11, 3, "standup").into(), // (start, end, weight, data)
+///   (10, 12, 5, "review").into(),
+///   (11, 13, 4, "lunch").into()
+/// ];
+///
+/// let names: Vec<&str> = unsorted(&bookings).into_iter().map(|b| b.data).collect();
+/// assert_eq!(names, vec!["lunch", "standup"]);
+/// ```
+///
+/// Payloads take no part in comparisons made by the solvers, so `Data` has no bounds beyond `Clone`
+/// (needed to hand out owned solutions). Wrap expensive payloads in an `Rc` or `Arc`.
+///
+/// With the `serde` feature enabled, `TaggedInterval` (de)serializes as a struct of `start`, `end`, `weight`, and `data`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaggedInterval<Weight: Ord + Add + Clone, Time: Ord + Clone, Data> {
+  pub start:  Time,
+  pub end:    Time,
+  pub weight: Weight,
+  pub data:   Data
+}
+
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone, Data> TaggedInterval<Weight, Time, Data> {
+  pub fn new(
+    start:  Time,
+    end:    Time,
+    weight: Weight,
+    data:   Data
+  ) -> Self { Self { start, end, weight, data } }
+
+  /// Splits off the payload, leaving a plain `WeightedInterval`.
+  ///
+  /// ```rust
+  /// # use w_inter::*;
+  /// let (interval, id) = TaggedInterval::new(0u8, 4u8, 2u8, 17usize).into_parts();
+  /// assert_eq!((interval, id), (WeightedInterval::new(0, 4, 2), 17));
+  /// ```
+  pub fn into_parts(self) -> (WeightedInterval<Weight, Time>, Data) {
+    (WeightedInterval::new(self.start, self.end, self.weight), self.data)
+  }
+}
+
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone, Data> traits::Weighted<Weight> for TaggedInterval<Weight, Time, Data> {
+  fn weight(&self) -> Weight { self.weight.clone() }
+}
+
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone, Data> traits::Interval<Time> for TaggedInterval<Weight, Time, Data> {
+  fn start(&self) -> Time { self.start.clone() }
+  fn end(&self) -> Time { self.end.clone() }
+}
+
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone, Data> From<(Time, Time, Weight, Data)> for TaggedInterval<Weight, Time, Data> {
+  fn from(tuple: (Time, Time, Weight, Data)) -> Self {
+    let (start, end, weight, data) = tuple;
+    Self { start, end, weight, data }
+  }
+}
+
+/// From `(start..end, weight, data)`.
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone, Data> From<(Range<Time>, Weight, Data)> for TaggedInterval<Weight, Time, Data> {
+  fn from(tuple: (Range<Time>, Weight, Data)) -> Self {
+    let (Range { start, end }, weight, data) = tuple;
+    Self { start, end, weight, data }
+  }
+}
+
+/// From `(interval, data)`, attaching a payload to an existing `WeightedInterval`.
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone, Data> From<(WeightedInterval<Weight, Time>, Data)> for TaggedInterval<Weight, Time, Data> {
+  fn from(tuple: (WeightedInterval<Weight, Time>, Data)) -> Self {
+    let (WeightedInterval { start, end, weight }, data) = tuple;
+    Self { start, end, weight, data }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{sorted, unsorted, TaggedInterval};
+
+  #[test]
+  fn payloads_ride_along() {
+    // identical intervals, told apart only by their payload
+    let jobs: Vec<TaggedInterval<u8, u8, u32>> = vec![
+      (0, 3, 2, 100).into(),
+      (0, 3, 2, 200).into(),
+      (3, 5, 1, 300).into()
+    ];
+
+    let ids: Vec<u32> = unsorted(&jobs).into_iter().map(|j| j.data).collect();
+    assert_eq!(ids.len(), 2);
+    assert_eq!(ids[0], 300);
+    assert!(ids[1] == 100 || ids[1] == 200);
+  }
+
+  #[test]
+  fn payloads_need_no_bounds_beyond_clone() {
+    #[derive(Clone)]
+    struct Handle(Vec<u8>);
+
+    let jobs: Vec<TaggedInterval<u8, u8, Handle>> = vec![(0..2, 1, Handle(vec![1])).into(), (1..3, 3, Handle(vec![2])).into()];
+    let mut memo = vec![0; jobs.len()];
+    let mut solution = vec![];
+    sorted(&jobs, &mut memo, &mut solution);
+
+    assert_eq!(solution.len(), 1);
+    assert_eq!(solution[0].data.0, vec![2]);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde_round_trip() {
+    let interval = TaggedInterval::new(-5i64, 7i64, 3u32, String::from("a"));
+
+    let json = serde_json::to_string(&interval).unwrap();
+    assert_eq!(json, r#"{"start":-5,"end":7,"weight":3,"data":"a"}"#);
+    assert_eq!(serde_json::from_str::<TaggedInterval<u32, i64, String>>(&json).unwrap(), interval);
+  }
+}
@@ -0,0 +1,40 @@
+//! Support for the tests: a seeded source of instances, and exhaustive search over selections to check solvers
+//! against.
+
+use std::ops::Add;
+use crate::traits;
+
+/// A linear congruential generator, so that each test draws the same instances on every run.
+pub struct Lcg(u32);
+
+impl Lcg {
+  pub fn new(seed: u32) -> Self { Self(seed) }
+
+  /// A number below `modulus`.
+  pub fn next(&mut self, modulus: u32) -> u32 {
+    self.0 = self.0.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+    (self.0 >> 16) % modulus
+  }
+}
+
+/// Every subset of `0..n` (below 32), each ascending.
+pub fn subsets(n: usize) -> impl Iterator<Item = Vec<usize>> {
+  (0..1u32 << n).map(move |mask| (0..n).filter(|i| mask & (1 << i) != 0).collect())
+}
+
+/// Whether each pair in `set` is `compatible`.
+pub fn pairwise(set: &[usize], compatible: impl Fn(usize, usize) -> bool) -> bool {
+  set.iter().enumerate().all(|(x, &a)| set[..x].iter().all(|&b| compatible(a, b)))
+}
+
+/// The greatest weight of any `feasible` selection of `intervals` (given by index, ascending), or zero if there is none.
+pub fn brute_force_best<Weight, Interval>(intervals: &[Interval], feasible: impl Fn(&[usize]) -> bool) -> Weight
+  where Weight: Ord + Add<Output = Weight> + Default,
+        Interval: traits::Weighted<Weight>
+{
+  subsets(intervals.len())
+    .filter(|set| feasible(set))
+    .map(|set| set.iter().fold(Weight::default(), |total, &i| total + intervals[i].weight()))
+    .max()
+    .unwrap_or_default()
+}
@@ -0,0 +1,265 @@
+use std::ops::Add;
+use crate::{solvers::{memoize, reconstruct, solve_by}, traits, Lexicographic, Minimize};
+
+/// Which optimal set to report when several have the same (maximum) total weight.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TieBreak {
+  /// The set whose last interval ends first, then whose second to last ends first, and so on.
+  /// Intervals ending at the same time are told apart by their index in the input.
+  #[default]
+  EarliestFinish,
+  /// The set with the fewest intervals.
+  FewestIntervals,
+  /// The set with the most intervals.
+  MostIntervals,
+  /// The set containing the smallest input index on which the candidate sets differ.
+  /// Costs `O(n²)` bits of time and memory on top of the usual solve.
+  SmallestIndices
+}
+
+/// Like [`unsorted`](crate::unsorted), but when several sets are optimal, `tie_break` decides which one is reported
+/// (`unsorted` picks among them depending on sort order). `O(n log n)` in interval number, except for
+/// [`TieBreak::SmallestIndices`].
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+///   (0, 2, 2).into(),
+///   (2, 4, 2).into(),
+///   (0, 4, 4).into()
+/// ];
+///
+/// assert_eq!(unsorted_with_tie_break(&intervals, TieBreak::FewestIntervals), vec![(0, 4, 4).into()]);
+/// assert_eq!(unsorted_with_tie_break(&intervals, TieBreak::MostIntervals), vec![(2, 4, 2).into(), (0, 2, 2).into()]);
+/// ```
+#[must_use]
+pub fn unsorted_with_tie_break<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  tie_break: TieBreak
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  // a stable sort, so that equal end times stay in input order
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  let weight      = |k: usize| intervals[order[k]].weight();
+  let predecessor = |k: usize| ends[..k].partition_point(|e| *e <= intervals[order[k]].start()).checked_sub(1);
+
+  let count = order.len();
+  let mut selected = vec![];
+  match tie_break {
+    TieBreak::EarliestFinish  => solve_by(count, weight, predecessor, &mut selected),
+    TieBreak::FewestIntervals => solve_by(count, |k| Lexicographic(weight(k), Minimize(1usize)), predecessor, &mut selected),
+    TieBreak::MostIntervals   => solve_by(count, |k| Lexicographic(weight(k), 1usize), predecessor, &mut selected),
+    TieBreak::SmallestIndices => {
+      let words = intervals.len().div_ceil(64);
+      solve_by(count, |k| Lexicographic(weight(k), IndexSet::single(order[k], words)), predecessor, &mut selected)
+    }
+  }
+
+  selected.into_iter().map(|k| intervals[order[k]].clone()).collect()
+}
+
+/// Like [`unsorted`](crate::unsorted), with a secondary objective: among the sets of maximum weight, the one maximizing
+/// the sum of `secondary` over its intervals is reported. `O(n log n)` in interval number.
+///
+/// Both objectives are tracked in the memoization array, so this is exact (unlike scaling and adding the objectives).
+/// Wrap the secondary objective in [`Minimize`] to minimize it instead.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u32>> = vec![
+///   (0, 1, 1).into(), (1, 2, 1).into(), (2, 3, 1).into(),
+///   (0, 3, 3).into(),
+///   (3, 9, 2).into(), (3, 5, 2).into()
+/// ];
+///
+/// // the fewest intervals, so a single long job rather than many tiny ones
+/// let fewest = unsorted_with_secondary(&intervals, |_| Minimize(1u32));
+/// assert_eq!(fewest.len(), 2);
+///
+/// // then, the least time spent
+/// let shortest = unsorted_with_secondary(&intervals, |i| Minimize(i.end - i.start));
+/// assert_eq!(shortest[0], (3, 5, 2).into());
+/// ```
+#[must_use]
+pub fn unsorted_with_secondary<Weight, Secondary, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  secondary: impl Fn(&Interval) -> Secondary
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Secondary: Ord + Add<Output = Secondary> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  let weight      = |k: usize| Lexicographic(intervals[order[k]].weight(), secondary(&intervals[order[k]]));
+  let predecessor = |k: usize| ends[..k].partition_point(|e| *e <= intervals[order[k]].start()).checked_sub(1);
+
+  let mut selected = vec![];
+  solve_by(order.len(), weight, predecessor, &mut selected);
+
+  selected.into_iter().map(|k| intervals[order[k]].clone()).collect()
+}
+
+/// Like [`unsorted`](crate::unsorted), warm-started from a `previous` solution (the indices of its intervals): among the
+/// sets of maximum weight, the one keeping the most of `previous` is reported, so that re-planning changes as little as
+/// it needs to. `O(n log n)` in interval number.
+///
+/// If `previous` is itself optimal, it is reported as is, without reconstructing a solution. Indices out of bounds (say,
+/// of intervals since dropped) are ignored, and a `previous` that isn't feasible only serves to break ties.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let mut intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 4, 4).into(), (0, 2, 2).into(), (2, 4, 2).into(), (4, 6, 1).into()];
+/// let plan = vec![2, 1, 3];
+///
+/// // (0, 4) is just as good, but the plan stays
+/// assert_eq!(unsorted_with_hint(&intervals, &plan), vec![intervals[3], intervals[2], intervals[1]]);
+///
+/// // re-priced: only the part of the plan that no longer pays changes
+/// intervals[1].weight = 1;
+/// assert_eq!(unsorted_with_hint(&intervals, &plan), vec![intervals[3], intervals[0]]);
+/// ```
+#[must_use]
+pub fn unsorted_with_hint<Weight, Time, Interval, InputContainer>(
+  intervals: InputContainer,
+  previous:  &[usize]
+) -> Vec<Interval>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord,
+        Interval: traits::Interval<Time> + traits::Weighted<Weight> + Clone,
+        InputContainer: AsRef<[Interval]>
+{
+  let intervals = intervals.as_ref();
+
+  let mut hinted = vec![false; intervals.len()];
+  for &i in previous.iter().filter(|&&i| i < intervals.len()) { hinted[i] = true; }
+
+  let mut order: Vec<usize> = (0..intervals.len()).collect();
+  phase!("sort", order.len(), order.sort_by_key(|&i| intervals[i].end()));
+  let ends: Vec<Time> = order.iter().map(|&i| intervals[i].end()).collect();
+
+  let weight      = |k: usize| Lexicographic(intervals[order[k]].weight(), usize::from(hinted[order[k]]));
+  let predecessor = |k: usize| ends[..k].partition_point(|e| *e <= intervals[order[k]].start()).checked_sub(1);
+
+  if order.is_empty() { return vec![]; }
+  let mut memoization = vec![weight(0); order.len()];
+  phase!("memoize", order.len(), memoize(order.len(), &mut memoization, weight, predecessor));
+
+  // the previous solution, if it's feasible and still optimal
+  let mut kept: Vec<usize> = (0..intervals.len()).filter(|&i| hinted[i]).collect();
+  kept.sort_by_key(|&i| intervals[i].end());
+  let feasible = kept.windows(2).all(|pair| intervals[pair[0]].end() <= intervals[pair[1]].start());
+  let value = kept.iter().map(|&i| Lexicographic(intervals[i].weight(), 1)).reduce(|a, b| a + b);
+  if feasible && value.as_ref() == memoization.last() {
+    return kept.into_iter().rev().map(|i| intervals[i].clone()).collect();
+  }
+
+  let mut selected = vec![];
+  phase!("reconstruct", order.len(), reconstruct(order.len(), &memoization, weight, predecessor, |k| selected.push(order[k])));
+  selected.into_iter().map(|i| intervals[i].clone()).collect()
+}
+
+/// A set of indices, as a bitset where smaller indices are more significant.
+/// Of two sets, the greater is the one containing the smallest index they differ on. Adding takes the union.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct IndexSet(Vec<u64>);
+
+impl IndexSet {
+  fn single(index: usize, words: usize) -> Self {
+    let mut bits = vec![0; words];
+    bits[index / 64] = 1 << (63 - index % 64);
+    IndexSet(bits)
+  }
+}
+
+impl Add for IndexSet {
+  type Output = Self;
+
+  // the sets added up along a chain of intervals are disjoint, so their union is their sum
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn add(mut self, other: Self) -> Self {
+    for (a, b) in self.0.iter_mut().zip(other.0) { *a |= b; }
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{unsorted, unsorted_with_hint, unsorted_with_secondary, unsorted_with_tie_break, Minimize, TaggedInterval, TieBreak, WeightedInterval};
+
+  #[test]
+  fn tie_breaks() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![
+      (2, 4, 2).into(),
+      (0, 4, 4).into(),
+      (0, 2, 2).into(),
+      (4, 6, 1).into(),
+      (4, 6, 1).into()
+    ];
+
+    let solve = |tie_break| unsorted_with_tie_break(&intervals, tie_break);
+    assert_eq!(solve(TieBreak::FewestIntervals), vec![intervals[3], intervals[1]]);
+    assert_eq!(solve(TieBreak::MostIntervals).len(), 3);
+    assert_eq!(solve(TieBreak::SmallestIndices), vec![intervals[3], intervals[0], intervals[2]]);
+    assert_eq!(solve(TieBreak::EarliestFinish), vec![intervals[3], intervals[0], intervals[2]]);
+  }
+
+  #[test]
+  fn smallest_indices_beyond_a_word() {
+    // the heaviest intervals are tied, and sit past the first 64 indices
+    let jobs: Vec<TaggedInterval<u8, u8, usize>> = (0..100)
+      .map(|i| if i == 70 || i == 71 { (0, 2, 2, i).into() } else { (0, 1, 1, i).into() })
+      .rev()
+      .collect();
+
+    assert_eq!(unsorted_with_tie_break(&jobs, TieBreak::SmallestIndices)[0].data, 71);
+    assert_eq!(unsorted_with_tie_break(&jobs, TieBreak::EarliestFinish).len(), 1);
+  }
+
+  #[test]
+  fn secondary_objectives() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 2, 2).into(), (2, 4, 2).into(), (0, 4, 4).into(), (4, 5, 0).into()];
+
+    // most intervals, zero weights included
+    assert_eq!(unsorted_with_secondary(&intervals, |_| 1u8).len(), 3);
+    assert_eq!(unsorted_with_secondary(&intervals, |_| Minimize(1u8)), vec![intervals[2]]);
+
+    // the secondary objective never costs primary weight
+    assert_eq!(unsorted_with_secondary(&intervals, |i| if i.weight == 4 { 0 } else { 9u8 }).len(), 3);
+    let overlapping: Vec<WeightedInterval<u8, u8>> = vec![(0, 1, 5).into(), (0, 1, 4).into()];
+    assert_eq!(unsorted_with_secondary(&overlapping, |i| Minimize(i.weight)), vec![overlapping[0]]);
+  }
+
+  #[test]
+  fn warm_starts() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..30u32).map(|i| (i * 7 % 19, i * 7 % 19 + 1 + i % 3, 1 + i % 2).into()).collect();
+    let total = |solution: &[WeightedInterval<u32, u32>]| solution.iter().map(|i| i.weight).sum::<u32>();
+    let optimum = total(&unsorted(&intervals));
+
+    // whatever the hint, the result is optimal
+    for hint in [vec![], vec![0, 1, 2], vec![29, 3, 100], (0..30).collect()] {
+      assert_eq!(total(&unsorted_with_hint(&intervals, &hint)), optimum);
+    }
+
+    // an optimal hint is reported as is
+    let previous: Vec<usize> = unsorted(&intervals).iter().map(|i| intervals.iter().position(|j| j == i).unwrap()).collect();
+    let reported = unsorted_with_hint(&intervals, &previous);
+    assert_eq!(reported, previous.iter().map(|&i| intervals[i]).collect::<Vec<_>>());
+    assert!(unsorted_with_hint(Vec::<WeightedInterval<u32, u32>>::new(), &[0]).is_empty());
+  }
+}
@@ -0,0 +1,22 @@
+//! Optional instrumentation (behind the `tracing` feature).
+//!
+//! Without the feature, `phase!` expands to nothing more than the body it wraps, so there is no cost to not opting in.
+
+/// Runs `$body` inside a `DEBUG` span named `$name`, recording the number of intervals involved,
+/// then emits an event carrying the elapsed wall-clock time of the phase (in microseconds).
+#[cfg(feature = "tracing")]
+macro_rules! phase {
+  ($name:literal, $count:expr, $body:expr) => {{
+    let span = tracing::debug_span!($name, count = $count as u64);
+    let _entered = span.enter();
+    let started = std::time::Instant::now();
+    let output = $body;
+    tracing::debug!(elapsed_us = started.elapsed().as_micros() as u64, concat!($name, " finished"));
+    output
+  }};
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! phase {
+  ($name:literal, $count:expr, $body:expr) => { $body };
+}
@@ -1,23 +1,82 @@
-use crate::traits::Interval;
+use crate::{compatibility::Compatibility, traits::Interval};
 
 /// - `s`: start time of `index`th interval
 /// - `e`: end time of solution interval
 /// 
-/// Finds the index of the interval having maximum end time `e` such that `e <= s`.
-pub fn final_compatible<Time: Ord, I: Interval<Time>>(intervals: &[I], index: usize) -> Option<usize> {
+/// Finds the index of the interval having maximum end time `e` such that `e` is compatible with `s`
+/// (`e <= s` for `HalfOpen`).
+/// 
+/// Gallops back from `index - 1` (checking 1, 2, 4, ... intervals back) before binary searching between the last two
+/// checks, so a predecessor `d` intervals back is found in `O(log d)`: short intervals (whose predecessor is only a few
+/// slots back) are found in a step or two, and long ones in at most about twice the steps of a full binary search.
+pub fn final_compatible<Time, I, C>(intervals: &[I], index: usize, compatibility: &C) -> Option<usize>
+  where Time: Ord,
+        I: Interval<Time>,
+        C: Compatibility<Time>
+{
   if index == 0 { return None; }
 
-  let mut low = 0;
-  let mut high = index - 1;
   let target = intervals[index].start();
+  let fits = |k: usize| compatibility.compatible(&intervals[k].end(), &target);
+
+  // the first interval not fitting, and how far back to look next
+  let mut high = index - 1;
+  if fits(high) { return Some(high); }
+  let mut step = 1;
+  let mut low = loop {
+    let Some(probe) = high.checked_sub(step) else {
+      if high == 0 || !fits(0) { return None; }
+      break 0;
+    };
+    if fits(probe) { break probe; }
+    high = probe;
+    step *= 2;
+  };
+
+  // `low` fits and `high` doesn't: halve the `size` candidates from `low` on, moving `low` with a conditional move rather
+  // than a branch, as the outcome of each check is a coin flip for the branch predictor
+  let mut size = high - low;
+  while size > 1 {
+    let half = size / 2;
+    low = std::hint::select_unpredictable(fits(low + half), low + half, low);
+    size -= half;
+  }
+
+  Some(low)
+}
+
+/// How many values the vectorized search compares at once (a few vector registers' worth, for primitive types).
+const BLOCK: usize = 32;
+
+/// The number of values in `sorted` (ascending) that are at most `key`, i.e. `sorted.partition_point(|v| *v <= key)`.
+///
+/// Intervals are rarely long compared to their spacing, so the answer is usually among the last `BLOCK` values, just
+/// visited by the caller and still in cache: those are counted in a single pass without branches, which the compiler
+/// vectorizes for primitive types. Only when the answer lies before them is the rest binary searched.
+pub fn count_at_most<Time: Ord + Copy>(sorted: &[Time], key: Time) -> usize {
+  let near = sorted.len().saturating_sub(BLOCK);
+  if near == 0 || sorted[near] <= key {
+    near + sorted[near..].iter().map(|v| usize::from(*v <= key)).sum::<usize>()
+  }
+  else { sorted[..near].partition_point(|v| *v <= key) }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{compatibility::Compatibility, traits::Interval, util::final_compatible, Closed, HalfOpen, WeightedInterval};
+
+  #[test]
+  fn galloping_finds_the_last_compatible() {
+    for length in [1u32, 3, 20, 400] {
+      let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..300u32).map(|i| (i * 7 % 293, i * 7 % 293 + i % length, 1).into()).collect();
+      intervals.sort_by_key(|i| i.end);
 
-  let mut mid;
-  while low < high {
-    mid = low + (high - low + 1) / 2;
-    if intervals[mid].end() <= target { low = mid; }
-    else { high = mid - 1; }
+      for index in 0..intervals.len() {
+        let start = intervals[index].start();
+        let last = |rule: &dyn Fn(&u32) -> bool| (0..index).rev().find(|&k| rule(&intervals[k].end()));
+        assert_eq!(final_compatible(&intervals, index, &HalfOpen), last(&|end| HalfOpen.compatible(end, &start)));
+        assert_eq!(final_compatible(&intervals, index, &Closed), last(&|end| Closed.compatible(end, &start)));
+      }
+    }
   }
-  if intervals[low].end() > target { return None; }
-  
-  return Some(low);
-}
\ No newline at end of file
+}
@@ -0,0 +1,288 @@
+//! Sanity checks for problems, run before solving.
+//!
+//! The solvers trust their input: an interval ending before it starts, or a float time that is `NaN`,
+//! makes for a confusing optimal set instead of an error. Validating first points at the offending intervals.
+//!
+//! ```rust
+//! # use w_inter::*;
+//! # use w_inter::validate::{Invalid, Validator};
+//! let intervals: Vec<WeightedInterval<u8, i32>> = vec![
+//!   (0, 4, 2).into(),
+//!   (5, 3, 1).into(), // ends before it starts
+//!   (0, 4, 2).into()  // same as the first
+//! ];
+//!
+//! assert_eq!(validate_intervals(&intervals), Err(vec![Invalid::Inverted { index: 1 }]));
+//! assert_eq!(
+//!   Validator::new().duplicates().validate(&intervals),
+//!   Err(vec![Invalid::Inverted { index: 1 }, Invalid::Duplicate { index: 2, of: 0 }])
+//! );
+//! ```
+
+use std::{fmt, ops::Add};
+use crate::{Interval, TaggedInterval, Weighted, WeightedInterval};
+
+/// A structural problem with one interval of a problem.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Invalid {
+  /// The interval at `index` starts after it ends.
+  Inverted { index: usize },
+  /// The `field` (`"start"`, `"end"`, or `"weight"`) of the interval at `index` isn't finite.
+  NonFinite { index: usize, field: &'static str },
+  /// The interval at `index` has the same start, end, and weight as the (earlier) one at `of`.
+  Duplicate { index: usize, of: usize }
+}
+
+impl fmt::Display for Invalid {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Invalid::Inverted { index }         => write!(f, "interval {index} starts after it ends"),
+      Invalid::NonFinite { index, field } => write!(f, "interval {index} has a non-finite {field}"),
+      Invalid::Duplicate { index, of }    => write!(f, "interval {index} duplicates interval {of}")
+    }
+  }
+}
+
+impl std::error::Error for Invalid { }
+
+/// Checks that no interval starts after it ends. `O(n)` in interval number.
+///
+/// Returns every problem found, ordered by index. For more checks, see [`Validator`].
+pub fn validate_intervals<Weight, Time, I>(intervals: &[I]) -> Result<(), Vec<Invalid>>
+  where Weight: Ord + Add,
+        Time: Ord,
+        I: Interval<Time> + Weighted<Weight>
+{
+  Validator::new().validate(intervals)
+}
+
+/// Configures which checks to run, on top of the inverted interval check that is always made.
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::validate::{Invalid, Validator};
+/// // a float wrapper, ordered, but able to hold NaN and infinities
+/// #[derive(Clone, Copy, PartialEq)]
+/// struct Seconds(f64);
+/// impl Eq for Seconds { }
+/// impl PartialOrd for Seconds {
+///   fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+/// }
+/// impl Ord for Seconds {
+///   fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.0.total_cmp(&other.0) }
+/// }
+///
+/// let intervals: Vec<WeightedInterval<u8, Seconds>> = vec![
+///   (Seconds(0.0), Seconds(1.5), 1).into(),
+///   (Seconds(1.5), Seconds(f64::NAN), 1).into()
+/// ];
+///
+/// let report = Validator::new().finite_times(|t: &Seconds| t.0.is_finite()).validate(&intervals);
+/// assert_eq!(report, Err(vec![Invalid::NonFinite { index: 1, field: "end" }]));
+/// ```
+pub struct Validator<'a, Weight, Time> {
+  duplicates:     bool,
+  finite_times:   Option<Check<'a, Time>>,
+  finite_weights: Option<Check<'a, Weight>>
+}
+
+type Check<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+
+impl<Weight, Time> Default for Validator<'_, Weight, Time> {
+  fn default() -> Self { Self { duplicates: false, finite_times: None, finite_weights: None } }
+}
+
+impl<'a, Weight: Ord + Add, Time: Ord> Validator<'a, Weight, Time> {
+  pub fn new() -> Self { Self::default() }
+
+  /// Also report intervals equal (in start, end, and weight) to an earlier one. Costs an `O(n log n)` sort.
+  pub fn duplicates(mut self) -> Self {
+    self.duplicates = true;
+    self
+  }
+
+  /// Also report starts and ends for which `is_finite` is `false`.
+  pub fn finite_times(mut self, is_finite: impl Fn(&Time) -> bool + 'a) -> Self {
+    self.finite_times = Some(Box::new(is_finite));
+    self
+  }
+
+  /// Also report weights for which `is_finite` is `false`.
+  pub fn finite_weights(mut self, is_finite: impl Fn(&Weight) -> bool + 'a) -> Self {
+    self.finite_weights = Some(Box::new(is_finite));
+    self
+  }
+
+  /// Runs the configured checks, returning every problem found, ordered by index.
+  pub fn validate<I: Interval<Time> + Weighted<Weight>>(&self, intervals: &[I]) -> Result<(), Vec<Invalid>> {
+    let mut found = vec![];
+
+    for (index, interval) in intervals.iter().enumerate() {
+      let (start, end) = (interval.start(), interval.end());
+
+      if let Some(is_finite) = &self.finite_times {
+        if !is_finite(&start) { found.push(Invalid::NonFinite { index, field: "start" }); }
+        if !is_finite(&end)   { found.push(Invalid::NonFinite { index, field: "end" }); }
+      }
+      if let Some(is_finite) = &self.finite_weights {
+        if !is_finite(&interval.weight()) { found.push(Invalid::NonFinite { index, field: "weight" }); }
+      }
+      if start > end { found.push(Invalid::Inverted { index }); }
+    }
+
+    if self.duplicates {
+      let key = |i: usize| (intervals[i].start(), intervals[i].end(), intervals[i].weight());
+
+      // a stable sort keeps equal intervals in index order, so each duplicate follows its first occurrence
+      let mut order: Vec<usize> = (0..intervals.len()).collect();
+      order.sort_by_key(|&i| key(i));
+
+      let mut first = 0;
+      for w in 1..order.len() {
+        if key(order[w]) == key(order[first]) { found.push(Invalid::Duplicate { index: order[w], of: order[first] }); }
+        else { first = w; }
+      }
+
+      found.sort_by_key(|invalid| match invalid {
+        Invalid::Inverted { index } | Invalid::NonFinite { index, .. } | Invalid::Duplicate { index, .. } => *index
+      });
+    }
+
+    if found.is_empty() { Ok(()) } else { Err(found) }
+  }
+}
+
+/// What to do with intervals that start after they end.
+///
+/// ```rust
+/// # use w_inter::*;
+/// # use w_inter::validate::{InvertedPolicy, Invalid};
+/// let events: Vec<WeightedInterval<u8, i32>> = vec![(0, 4, 2).into(), (9, 5, 1).into()];
+///
+/// assert_eq!(InvertedPolicy::Reject.apply(&mut events.clone()), Err(vec![Invalid::Inverted { index: 1 }]));
+///
+/// let mut swapped = events.clone();
+/// assert_eq!(InvertedPolicy::Swap.apply(&mut swapped), Ok(vec![1]));
+/// assert_eq!(swapped[1], WeightedInterval::new(5, 9, 1));
+///
+/// let mut skipped = events.clone();
+/// assert_eq!(InvertedPolicy::Skip.apply(&mut skipped), Ok(vec![1]));
+/// assert_eq!(skipped, vec![WeightedInterval::new(0, 4, 2)]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum InvertedPolicy {
+  /// Fail, reporting every inverted interval. The problem is left untouched.
+  #[default]
+  Reject,
+  /// Exchange the start and end of inverted intervals.
+  Swap,
+  /// Remove inverted intervals from the problem.
+  Skip
+}
+
+impl InvertedPolicy {
+  /// Applies the policy to a problem, returning the indices (into the problem as given) of the intervals swapped or skipped.
+  /// `O(n)` in interval number.
+  pub fn apply<Time, I>(self, intervals: &mut Vec<I>) -> Result<Vec<usize>, Vec<Invalid>>
+    where Time: Ord,
+          I: Interval<Time> + SwapBounds
+  {
+    let inverted: Vec<usize> = (0..intervals.len()).filter(|&i| intervals[i].start() > intervals[i].end()).collect();
+
+    match self {
+      InvertedPolicy::Reject => if !inverted.is_empty() {
+        return Err(inverted.into_iter().map(|index| Invalid::Inverted { index }).collect());
+      },
+      InvertedPolicy::Swap   => for &i in &inverted { intervals[i].swap_bounds(); },
+      InvertedPolicy::Skip   => {
+        let mut index = 0;
+        intervals.retain(|_| { index += 1; inverted.binary_search(&(index - 1)).is_err() });
+      }
+    }
+
+    Ok(inverted)
+  }
+}
+
+/// Intervals whose start and end can be exchanged in place, so that [`InvertedPolicy::Swap`] can repair them.
+pub trait SwapBounds {
+  fn swap_bounds(&mut self);
+}
+
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone> SwapBounds for WeightedInterval<Weight, Time> {
+  fn swap_bounds(&mut self) { std::mem::swap(&mut self.start, &mut self.end); }
+}
+
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone, Data> SwapBounds for TaggedInterval<Weight, Time, Data> {
+  fn swap_bounds(&mut self) { std::mem::swap(&mut self.start, &mut self.end); }
+}
+
+impl<Time> SwapBounds for (Time, Time) {
+  fn swap_bounds(&mut self) { std::mem::swap(&mut self.0, &mut self.1); }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{validate_intervals, WeightedInterval};
+  use crate::validate::{Invalid, InvertedPolicy, Validator};
+
+  #[test]
+  fn valid_problems_pass() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 0, 1).into(), (0, 4, 2).into(), (0, 4, 3).into()];
+    assert_eq!(validate_intervals(&intervals), Ok(()));
+    assert_eq!(Validator::new().duplicates().validate(&intervals), Ok(()));
+    assert_eq!(validate_intervals::<u8, u8, WeightedInterval<u8, u8>>(&[]), Ok(()));
+  }
+
+  #[test]
+  fn every_duplicate_points_at_the_first() {
+    let intervals: Vec<WeightedInterval<u8, u8>> = vec![(1, 2, 1).into(), (0, 1, 1).into(), (1, 2, 1).into(), (1, 2, 1).into()];
+    assert_eq!(
+      Validator::new().duplicates().validate(&intervals),
+      Err(vec![Invalid::Duplicate { index: 2, of: 0 }, Invalid::Duplicate { index: 3, of: 0 }])
+    );
+  }
+
+  #[test]
+  fn non_finite_weights() {
+    #[derive(Clone, Copy, PartialEq)]
+    struct Score(f32);
+    impl Eq for Score { }
+    impl PartialOrd for Score {
+      fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+    }
+    impl Ord for Score {
+      fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.0.total_cmp(&other.0) }
+    }
+    impl std::ops::Add for Score {
+      type Output = Score;
+      fn add(self, other: Self) -> Self { Score(self.0 + other.0) }
+    }
+
+    let intervals: Vec<WeightedInterval<Score, u8>> = vec![(0, 1, Score(f32::INFINITY)).into(), (3, 2, Score(1.0)).into()];
+    assert_eq!(
+      Validator::new().finite_weights(|w: &Score| w.0.is_finite()).validate(&intervals),
+      Err(vec![Invalid::NonFinite { index: 0, field: "weight" }, Invalid::Inverted { index: 1 }])
+    );
+  }
+
+  #[test]
+  fn inverted_policies() {
+    let events = vec![(3u8, 1u8), (0, 2), (5, 4), (6, 6)];
+
+    let mut rejected = events.clone();
+    assert_eq!(
+      InvertedPolicy::default().apply(&mut rejected),
+      Err(vec![Invalid::Inverted { index: 0 }, Invalid::Inverted { index: 2 }])
+    );
+    assert_eq!(rejected, events);
+
+    let mut swapped = events.clone();
+    assert_eq!(InvertedPolicy::Swap.apply(&mut swapped), Ok(vec![0, 2]));
+    assert_eq!(swapped, vec![(1, 3), (0, 2), (4, 5), (6, 6)]);
+
+    let mut skipped = events.clone();
+    assert_eq!(InvertedPolicy::Skip.apply(&mut skipped), Ok(vec![0, 2]));
+    assert_eq!(skipped, vec![(0, 2), (6, 6)]);
+  }
+}
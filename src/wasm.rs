@@ -0,0 +1,105 @@
+//! WebAssembly bindings (behind the `wasm` feature).
+//!
+//! The exports below end up in any `wasm-bindgen` cdylib depending on this crate with the feature enabled.
+//! From JavaScript:
+//!
+//! ```js
+//! import { solveF64 } from "./pkg/your_crate.js";
+//!
+//! const starts  = new Float64Array([0, 1, 4]);
+//! const ends    = new Float64Array([4, 5, 9]);
+//! const weights = new Float64Array([2, 3, 3]);
+//!
+//! solveF64(starts, ends, weights); // Uint32Array [0, 2]
+//! ```
+//!
+//! Both functions return the ascending indices of the optimal set, and throw if the columns have different lengths.
+//! Intervals with a negative weight are never selected (as they can only make a schedule worse).
+
+use std::{cmp::Ordering, ops::Add};
+use wasm_bindgen::prelude::*;
+use crate::unsorted_soa;
+
+/// Solves the problem given as `Float64Array` columns. Throws if any value is `NaN`.
+#[wasm_bindgen(js_name = solveF64)]
+pub fn solve_f64(starts: &[f64], ends: &[f64], weights: &[f64]) -> Result<Vec<u32>, JsError> {
+  if let Some(column) = [("starts", starts), ("ends", ends), ("weights", weights)].iter()
+    .find_map(|(name, column)| column.iter().any(|v| v.is_nan()).then_some(name))
+  {
+    return Err(JsError::new(&format!("`{column}` contains NaN")));
+  }
+
+  let total = |column: &[f64]| column.iter().copied().map(Total).collect::<Vec<_>>();
+  solve(&total(starts), &total(ends), &total(weights), Total(0.0)).map_err(|e| JsError::new(&e))
+}
+
+/// Solves the problem given as `BigInt64Array` columns.
+#[wasm_bindgen(js_name = solveI64)]
+pub fn solve_i64(starts: &[i64], ends: &[i64], weights: &[i64]) -> Result<Vec<u32>, JsError> {
+  solve(starts, ends, weights, 0).map_err(|e| JsError::new(&e))
+}
+
+/// Drops negative weights, solves, and maps the solution back to indices into the columns.
+fn solve<Weight, Time>(starts: &[Time], ends: &[Time], weights: &[Weight], zero: Weight) -> Result<Vec<u32>, String>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone
+{
+  if starts.len() != ends.len() || ends.len() != weights.len() {
+    return Err(format!(
+      "columns must have equal lengths (starts: {}, ends: {}, weights: {})", starts.len(), ends.len(), weights.len()
+    ));
+  }
+
+  let kept: Vec<usize> = (0..weights.len()).filter(|&i| weights[i] >= zero).collect();
+  let column = |c: &[Time]| kept.iter().map(|&i| c[i].clone()).collect::<Vec<_>>();
+  let weights: Vec<Weight> = kept.iter().map(|&i| weights[i].clone()).collect();
+
+  let mut solution: Vec<u32> = unsorted_soa(&column(starts), &column(ends), &weights).into_iter()
+    .map(|i| kept[i] as u32)
+    .collect();
+  solution.sort_unstable();
+
+  Ok(solution)
+}
+
+/// An `f64` that has been checked not to be `NaN`, ordered by `f64::total_cmp`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Total(f64);
+
+impl Eq for Total { }
+
+impl PartialOrd for Total {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Total {
+  fn cmp(&self, other: &Self) -> Ordering { self.0.total_cmp(&other.0) }
+}
+
+impl Add for Total {
+  type Output = Total;
+  fn add(self, other: Self) -> Self { Total(self.0 + other.0) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{solve, Total};
+
+  #[test]
+  fn negative_weights_are_never_selected() {
+    assert_eq!(solve(&[0i64, 2], &[1, 3], &[-4i64, 1], 0), Ok(vec![1]));
+    assert_eq!(solve(&[0i64], &[1], &[-4i64], 0), Ok(vec![]));
+  }
+
+  #[test]
+  fn fractional_times() {
+    let total = |c: &[f64]| c.iter().copied().map(Total).collect::<Vec<_>>();
+    let solution = solve(&total(&[0.0, 0.5, 1.5]), &total(&[1.5, 1.0, 2.0]), &total(&[2.5, 1.0, 1.0]), Total(0.0));
+    assert_eq!(solution, Ok(vec![0, 2]));
+  }
+
+  #[test]
+  fn ragged_columns() {
+    assert!(solve(&[0i64], &[1, 2], &[1i64], 0).is_err());
+  }
+}
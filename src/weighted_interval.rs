@@ -1,4 +1,4 @@
-use std::ops::Add;
+use std::{cmp::Ordering, fmt::{self, Display}, ops::{Add, Range, RangeInclusive}, time::{Duration, Instant, SystemTime}};
 use crate::traits;
 
 /// A batteries-included weighted interval representation.
@@ -6,49 +6,285 @@ use crate::traits;
 /// NOTE: you can easily use your own representation (implement `Interval`, `Weighted`).
 /// 
 /// ```rust
+/// # use w_inter::WeightedInterval;
 /// // from tuple (start, end, weight)
-/// let a = (1644526945376u64, 1644526960930u64, 137u8).into();
+/// let a: WeightedInterval<_, _> = (1644526945376u64, 1644526960930u64, 137u8).into();
 /// 
 /// // a slightly more verbose equivalent
 /// let b = WeightedInterval::new(-123i32, 123i32, 11u128);
 /// ```
-#[derive(Clone, Debug)]
-pub struct WeightedInterval<Weight: Ord + Add + Clone, Time: Ord + Add + Clone> {
+/// 
+/// Time only needs to be ordered (and cloned), so date-time types like `chrono::DateTime<Utc>`
+/// or `time::OffsetDateTime` can be used directly, no conversion to epoch offsets needed.
+/// 
+/// Intervals are `Copy` and `Default` whenever both their time and weight types are, hash like their fields,
+/// and are ordered by end time (see the `Ord` impl), so they can go straight into a `HashSet` or `BTreeSet`.
+/// 
+/// With the `serde` feature enabled, `WeightedInterval` (de)serializes as a struct of `start`, `end`, and `weight`.
+/// With the `rkyv` feature enabled, it can be archived, see [`sorted_archived`](crate::sorted_archived).
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+pub struct WeightedInterval<Weight: Ord + Add + Clone, Time: Ord + Clone> {
   pub start:  Time,
   pub end:    Time,
   pub weight: Weight
 }
 
-impl<Weight: Ord + Add + Clone + PartialEq, Time: Ord + Add + Clone + PartialEq> PartialEq for WeightedInterval<Weight, Time> {
-  fn eq(&self, other: &Self) -> bool {
-    self.start.eq(&other.start) &&
-    self.end.eq(&other.end) &&
-    self.weight.eq(&other.weight)
+/// Ordered by end, then start, then weight, so sorting a slice of intervals readies it for [`sorted`](crate::sorted).
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone> Ord for WeightedInterval<Weight, Time> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.end.cmp(&other.end)
+      .then_with(|| self.start.cmp(&other.start))
+      .then_with(|| self.weight.cmp(&other.weight))
   }
 }
 
-impl<Weight: Ord + Add + Clone + PartialEq, Time: Ord + Add + Clone + PartialEq> Eq for WeightedInterval<Weight, Time> { }
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone> PartialOrd for WeightedInterval<Weight, Time> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
 
-impl<Weight: Ord + Add + Clone, Time: Ord + Add + Clone> WeightedInterval<Weight, Time> {
+/// Formats as `start..end (weight w)`.
+/// 
+/// ```rust
+/// # use w_inter::WeightedInterval;
+/// assert_eq!(WeightedInterval::new(1, 4, 5).to_string(), "1..4 (weight 5)");
+/// ```
+impl<Weight: Ord + Add + Clone + Display, Time: Ord + Clone + Display> Display for WeightedInterval<Weight, Time> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}..{} (weight {})", self.start, self.end, self.weight)
+  }
+}
+
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone> WeightedInterval<Weight, Time> {
   pub fn new(
     start:  Time, 
     end:    Time, 
     weight: Weight
   ) -> Self { Self { start, end, weight } }
+
+  /// An interval beginning at `start` and lasting for `duration`.
+  /// 
+  /// Works for any time type that can be offset by a duration type, e.g. `std::time::Instant` and `Duration`,
+  /// or `chrono::DateTime<Utc>` and `TimeDelta`.
+  /// 
+  /// ```rust
+  /// # use w_inter::WeightedInterval;
+  /// let shift = WeightedInterval::from_start_duration(9u32, 8u32, 5u8);
+  /// assert_eq!(shift, WeightedInterval::new(9, 17, 5));
+  /// ```
+  pub fn from_start_duration<Duration>(
+    start:    Time,
+    duration: Duration,
+    weight:   Weight
+  ) -> Self where Time: Add<Duration, Output = Time> {
+    Self { start: start.clone(), end: start + duration, weight }
+  }
+}
+
+impl<Weight: Ord + Add + Clone> WeightedInterval<Weight, Instant> {
+  /// An interval of the monotonic clock, beginning at `start` and lasting for `duration`.
+  /// 
+  /// ```rust
+  /// # use w_inter::WeightedInterval;
+  /// use std::time::{Duration, Instant};
+  /// 
+  /// let now = Instant::now();
+  /// let job = WeightedInterval::from_instant_duration(now, Duration::from_secs(90), 3u8);
+  /// assert_eq!(job.end - job.start, Duration::from_secs(90));
+  /// ```
+  pub fn from_instant_duration(start: Instant, duration: Duration, weight: Weight) -> Self {
+    Self::from_start_duration(start, duration, weight)
+  }
+}
+
+impl<Weight: Ord + Add + Clone> WeightedInterval<Weight, SystemTime> {
+  /// An interval of the wall clock, beginning at `start` and lasting for `duration`.
+  /// 
+  /// ```rust
+  /// # use w_inter::WeightedInterval;
+  /// use std::time::{Duration, UNIX_EPOCH};
+  /// 
+  /// let job = WeightedInterval::from_system_time_duration(UNIX_EPOCH, Duration::from_secs(60), 3u8);
+  /// assert_eq!(job.end, UNIX_EPOCH + Duration::from_secs(60));
+  /// ```
+  pub fn from_system_time_duration(start: SystemTime, duration: Duration, weight: Weight) -> Self {
+    Self::from_start_duration(start, duration, weight)
+  }
 }
 
-impl<Weight: Ord + Add + Clone, Time: Ord + Add + Clone> traits::Weighted<Weight> for WeightedInterval<Weight, Time> {
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone> traits::Weighted<Weight> for WeightedInterval<Weight, Time> {
   fn weight(&self) -> Weight { self.weight.clone() }
 }
 
-impl<Weight: Ord + Add + Clone, Time: Ord + Add + Clone> traits::Interval<Time> for WeightedInterval<Weight, Time> {
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone> traits::Interval<Time> for WeightedInterval<Weight, Time> {
   fn start(&self) -> Time { self.start.clone() }
   fn end(&self) -> Time { self.end.clone() }
 }
 
-impl<Weight: Ord + Add + Clone, Time: Ord + Add + Clone> From<(Time, Time, Weight)> for WeightedInterval<Weight, Time> {
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone> From<(Time, Time, Weight)> for WeightedInterval<Weight, Time> {
   fn from(tuple: (Time, Time, Weight)) -> Self {
     let (start, end, weight) = tuple;
     Self { start, end, weight }
   }
 }
+
+
+/// From `(start..end, weight)`.
+/// 
+/// ```rust
+/// # use w_inter::WeightedInterval;
+/// let a: WeightedInterval<u8, i32> = (-5..5, 3).into();
+/// assert_eq!(a, WeightedInterval::new(-5, 5, 3));
+/// ```
+impl<Weight: Ord + Add + Clone, Time: Ord + Clone> From<(Range<Time>, Weight)> for WeightedInterval<Weight, Time> {
+  fn from(tuple: (Range<Time>, Weight)) -> Self {
+    let (Range { start, end }, weight) = tuple;
+    Self { start, end, weight }
+  }
+}
+
+/// An inclusive range ending at the maximum value of its type, which has no exclusive end to convert to.
+/// 
+/// ```rust
+/// # use w_inter::{EndsAtMaximum, WeightedInterval};
+/// let a: WeightedInterval<i32, u8> = (0..=4, 3).try_into().unwrap();
+/// assert_eq!(a, WeightedInterval::new(0, 5, 3));
+/// 
+/// let b: Result<WeightedInterval<i32, u8>, _> = (0..=u8::MAX, 3).try_into();
+/// assert_eq!(b, Err(EndsAtMaximum));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EndsAtMaximum;
+
+impl Display for EndsAtMaximum {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "inclusive range ends at the maximum value, so it has no exclusive end")
+  }
+}
+
+impl std::error::Error for EndsAtMaximum { }
+
+/// `start..=end` covers `end` itself, so it is the same interval as `start..end + 1` (for integers).
+/// Anything else would let two ranges sharing an endpoint both be selected.
+macro_rules! from_range_inclusive {
+  ($($time:ty),*) => {$(
+    /// From `(start..=end, weight)`, which becomes the interval from `start` to `end + 1`.
+    /// 
+    /// Fails if `end` is the maximum value, as there is no `end + 1`.
+    impl<Weight: Ord + Add + Clone> TryFrom<(RangeInclusive<$time>, Weight)> for WeightedInterval<Weight, $time> {
+      type Error = EndsAtMaximum;
+
+      fn try_from(tuple: (RangeInclusive<$time>, Weight)) -> Result<Self, EndsAtMaximum> {
+        let (range, weight) = tuple;
+        let (start, end) = range.into_inner();
+        let end = end.checked_add(1).ok_or(EndsAtMaximum)?;
+        Ok(Self { start, end, weight })
+      }
+    }
+  )*};
+}
+
+from_range_inclusive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+  use crate::{EndsAtMaximum, WeightedInterval, unsorted};
+
+  #[test]
+  fn ranges() {
+    let intervals: Vec<WeightedInterval<u8, u32>> = vec![
+      (0..=2, 3).try_into().unwrap(),
+      (2..=4, 3).try_into().unwrap(), // shares 2 with the first, so conflicts with it
+      (3..5, 4).into()
+    ];
+
+    assert_eq!(intervals[0], WeightedInterval::new(0, 3, 3));
+    assert_eq!(unsorted(&intervals), vec![(3..5, 4).into(), (0..3, 3).into()]);
+  }
+
+  #[test]
+  fn inclusive_range_to_maximum() {
+    let interval: Result<WeightedInterval<u8, u8>, _> = (0..=u8::MAX, 1).try_into();
+    assert_eq!(interval, Err(EndsAtMaximum));
+    assert_eq!((0..=u8::MAX - 1, 1).try_into(), Ok(WeightedInterval::<u8, u8>::new(0, u8::MAX, 1)));
+  }
+
+  #[test]
+  fn chrono_datetimes() {
+    use chrono::{DateTime, Utc};
+
+    let at = |hour: i64| DateTime::<Utc>::from_timestamp(1_700_000_000 + hour * 3600, 0).unwrap();
+    let meetings: Vec<WeightedInterval<u32, DateTime<Utc>>> = vec![
+      (at(9),  at(11), 3).into(),
+      (at(10), at(12), 5).into(),
+      (at(11), at(13), 4).into()
+    ];
+
+    assert_eq!(unsorted(&meetings), vec![(at(11), at(13), 4).into(), (at(9), at(11), 3).into()]);
+  }
+
+  #[test]
+  fn time_offset_datetimes() {
+    use time::{macros::datetime, OffsetDateTime};
+
+    let meetings: Vec<WeightedInterval<u32, OffsetDateTime>> = vec![
+      (datetime!(2024-03-01 09:00 UTC), datetime!(2024-03-01 11:00 UTC), 3).into(),
+      (datetime!(2024-03-01 10:00 +01:00), datetime!(2024-03-01 12:00 +01:00), 5).into()
+    ];
+
+    // 10:00+01:00 is 09:00 UTC, so these overlap entirely
+    assert_eq!(unsorted(&meetings), vec![meetings[1]]);
+  }
+
+  #[test]
+  fn std_traits() {
+    use std::collections::HashSet;
+
+    let mut intervals: Vec<WeightedInterval<u8, u8>> = vec![(1, 4, 5).into(), (0, 4, 2).into(), (0, 1, 9).into(), (0, 4, 1).into()];
+    intervals.sort();
+    assert_eq!(intervals, vec![(0, 1, 9).into(), (0, 4, 1).into(), (0, 4, 2).into(), (1, 4, 5).into()]);
+
+    let copy = intervals[0];
+    let unique: HashSet<_> = intervals.iter().copied().chain([copy]).collect();
+    assert_eq!(unique.len(), 4);
+
+    assert_eq!(WeightedInterval::<u8, u8>::default(), WeightedInterval::new(0, 0, 0));
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde_round_trip() {
+    let interval = WeightedInterval::new(-5i64, 7i64, 3u32);
+
+    let json = serde_json::to_string(&interval).unwrap();
+    assert_eq!(json, r#"{"start":-5,"end":7,"weight":3}"#);
+    assert_eq!(serde_json::from_str::<WeightedInterval<u32, i64>>(&json).unwrap(), interval);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde_problem_round_trip() {
+    let problem: Vec<WeightedInterval<u8, u16>> = vec![
+      (0, 6, 3).into(),
+      (1, 4, 5).into(),
+      (3, 5, 5).into(),
+      (5, 9, 7).into()
+    ];
+
+    let json = serde_json::to_string(&problem).unwrap();
+    let restored: Vec<WeightedInterval<u8, u16>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, problem);
+
+    // and a solution survives the trip just the same
+    let solution = unsorted(&restored);
+    let json = serde_json::to_string(&solution).unwrap();
+    assert_eq!(serde_json::from_str::<Vec<WeightedInterval<u8, u16>>>(&json).unwrap(), solution);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde_rejects_missing_fields() {
+    assert!(serde_json::from_str::<WeightedInterval<u8, u8>>(r#"{"start":0,"end":1}"#).is_err());
+  }
+}
\ No newline at end of file
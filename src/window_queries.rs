@@ -0,0 +1,144 @@
+use std::{fmt, ops::{Add, Range}};
+use crate::traits;
+
+/// A [`WindowQueries`] table that would hold more than [`WindowQueries::MAX_ENTRIES`] entries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TableTooLarge {
+  /// The entries the table would hold: one per distinct start, for each prefix of the intervals.
+  pub entries: usize
+}
+
+impl fmt::Display for TableTooLarge {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "window query table would hold {} entries, more than the {} allowed", self.entries, WindowQueries::<(), ()>::MAX_ENTRIES)
+  }
+}
+
+impl std::error::Error for TableTooLarge { }
+
+/// Answers "what's the optimal weight of the intervals inside this window?" for many windows over the same intervals,
+/// in `O(log n)` per window, for `n` intervals.
+///
+/// Builds a table holding the optimal weight of every prefix (in end order) of the intervals starting at or after each
+/// distinct start: `O(n d)` time and memory for `d` distinct starts. That's quadratic in the worst case, so the table is
+/// capped at [`MAX_ENTRIES`](Self::MAX_ENTRIES), which a few thousand intervals with distinct starts reach: beyond it,
+/// or for only a few windows, or to get the optimal sets themselves, [`sorted_in_window`](crate::sorted_in_window) (in
+/// `O(n log n)` per window) is the better fit.
+///
+/// ```rust
+/// # use w_inter::*;
+/// let intervals: Vec<WeightedInterval<u8, u8>> = vec![(0, 3, 5).into(), (2, 4, 2).into(), (4, 6, 2).into(), (5, 9, 4).into()];
+/// let queries = WindowQueries::new(&intervals).unwrap();
+///
+/// assert_eq!(queries.optimal_weight(0..9), Some(9));
+/// assert_eq!(queries.optimal_weight(1..8), Some(4));
+/// assert_eq!(queries.optimal_weight(6..9), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct WindowQueries<Weight, Time> {
+  /// The distinct starts, ascending.
+  starts: Vec<Time>,
+  /// The ends, ascending.
+  ends:   Vec<Time>,
+  /// For each distinct start (a row of `ends.len() + 1`) and prefix length, the optimal weight (if anything fits).
+  table:  Vec<Option<Weight>>
+}
+
+impl<Weight, Time> WindowQueries<Weight, Time> {
+  /// The most entries a table may hold: `2^24`, enough for 4095 intervals with distinct starts (taking 256 MiB for `u64`
+  /// weights).
+  pub const MAX_ENTRIES: usize = 1 << 24;
+}
+
+impl<Weight, Time> WindowQueries<Weight, Time>
+  where Weight: Ord + Add<Output = Weight> + Clone,
+        Time: Ord + Clone
+{
+  /// Builds the table over `intervals`, which **must be sorted ascending by end time** (as for [`sorted`](crate::sorted)).
+  ///
+  /// Fails, before allocating anything sized by the table, if it would hold more than [`MAX_ENTRIES`](Self::MAX_ENTRIES).
+  pub fn new<Interval, InputContainer>(intervals: InputContainer) -> Result<Self, TableTooLarge>
+    where Interval: traits::Interval<Time> + traits::Weighted<Weight>,
+          InputContainer: AsRef<[Interval]>
+  {
+    let intervals = intervals.as_ref();
+
+    let mut starts: Vec<Time> = intervals.iter().map(|i| i.start()).collect();
+    phase!("sort", starts.len(), starts.sort_unstable());
+    starts.dedup();
+
+    let entries = starts.len().saturating_mul(intervals.len() + 1);
+    if entries > Self::MAX_ENTRIES { return Err(TableTooLarge { entries }); }
+
+    let ends: Vec<Time> = intervals.iter().map(|i| i.end()).collect();
+
+    // how many intervals (in end order) end before each one starts
+    let before: Vec<usize> = (0..intervals.len())
+      .map(|k| ends[..k].partition_point(|e| *e <= intervals[k].start()))
+      .collect();
+
+    let width = intervals.len() + 1;
+    let mut table: Vec<Option<Weight>> = vec![None; starts.len() * width];
+    phase!("memoize", table.len(), for (row, start) in starts.iter().enumerate() {
+      let row = &mut table[row * width..(row + 1) * width];
+
+      for k in 0..intervals.len() {
+        let mut best = row[k].clone();
+        if intervals[k].start() >= *start {
+          let included = match row[before[k]].clone() {
+            Some(weight) => intervals[k].weight() + weight,
+            None         => intervals[k].weight()
+          };
+          best = Some(best.map_or(included.clone(), |best| best.max(included)));
+        }
+        row[k + 1] = best;
+      }
+    });
+
+    Ok(Self { starts, ends, table })
+  }
+
+  /// The optimal weight of the intervals lying entirely inside `window` (starting at or after its start, and ending at
+  /// or before its end), or `None` if no interval does. `O(log n)`.
+  #[must_use]
+  pub fn optimal_weight(&self, window: Range<Time>) -> Option<Weight> {
+    let row = self.starts.partition_point(|s| *s < window.start);
+    if row == self.starts.len() { return None; }
+
+    let prefix = self.ends.partition_point(|e| *e <= window.end);
+    self.table[row * (self.ends.len() + 1) + prefix].clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{sorted_in_window, TableTooLarge, WeightedInterval, WindowQueries};
+
+  #[test]
+  fn matches_window_solves() {
+    let mut intervals: Vec<WeightedInterval<u32, u32>> = (0..50u32).map(|i| (i * 17 % 29, i * 17 % 29 + i % 5, 1 + i % 6).into()).collect();
+    intervals.sort_by_key(|i| i.end);
+    let queries = WindowQueries::new(&intervals).unwrap();
+
+    let mut memo = vec![0; intervals.len()];
+    for a in 0..35 {
+      for b in a..40 {
+        let mut solution = vec![];
+        sorted_in_window(&intervals, a..b, &mut memo, &mut solution);
+
+        let expected = solution.iter().map(|i| i.weight).reduce(|a, b| a + b);
+        assert_eq!(queries.optimal_weight(a..b), expected, "{a}..{b}");
+      }
+    }
+  }
+
+  #[test]
+  fn refuses_tables_too_large() {
+    let intervals: Vec<WeightedInterval<u32, u32>> = (0..5000).map(|i| (i, i + 1, 1).into()).collect();
+    assert_eq!(WindowQueries::<u32, u32>::new(&intervals).unwrap_err(), TableTooLarge { entries: 5000 * 5001 });
+
+    // only distinct starts make rows
+    let shared: Vec<WeightedInterval<u32, u32>> = (0..5000).map(|i| (i % 3, 3 + i, 1).into()).collect();
+    assert!(WindowQueries::<u32, u32>::new(&shared).is_ok());
+  }
+}